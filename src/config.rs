@@ -1,9 +1,125 @@
+use crate::logic::context_window;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::OnceLock;
 use crate::log_err;
+use crate::logic::i18n::UiLanguage;
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// 任务队列中的一项，每项使用独立的输入和输出路径
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct QueuedJob {
+    pub input_path: String,
+    pub output_path: String,
+}
+
+/// 额外的输出目标，任务完成后同步主输出目录中的资源文件，路径以 .zip 结尾时打包为资源包
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct OutputTarget {
+    pub path: String,
+    pub layout: OutputLayout,
+}
+
+/// 界面主题
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum UiTheme {
+    #[default]
+    System,
+    Dark,
+    Light,
+}
+
+/// 语言文件的输出结构
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputLayout {
+    #[default]
+    ResourcePack, // assets/<modid>/lang/
+    Kubejs, // kubejs/assets/<modid>/lang/，无需玩家启用资源包
+}
+
+/// 输出语言文件中条目的顺序
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputOrder {
+    #[default]
+    Source, // 与源文件一致
+    Alphabetical,
+    Grouped, // 按键前缀分组，.lang 文件中组间空一行
+}
+
+/// 译文文件名中语言代码的大小写
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FilenameCasing {
+    #[default]
+    Auto, // .json (1.13+) 全小写，.lang 沿用源文件的写法
+    Lower, // zh_cn
+    UpperRegion, // zh_CN
+}
+
+/// 写出语言文件时颜色代码的处理方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorCodeMode {
+    #[default]
+    Keep,
+    Section, // &a -> §a
+    Ampersand, // §a -> &a
+    Strip, // 移除全部颜色与样式代码
+}
+
+/// 写出前译文标点的统一方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PunctuationMode {
+    #[default]
+    Keep,
+    FullWidth, // 中日文字旁的 , ! ? 等转为全角，... 转为 ……
+    HalfWidth, // 全角标点转为半角
+}
+
+/// 值为空或只含空白的条目在输出中的处理方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum EmptyValueMode {
+    #[default]
+    Keep, // 原样写入，部分模组有意使用空字符串
+    Drop, // 不写入输出，游戏回退到原文
+    KeyName, // 以键名作为值，便于在游戏内定位
+}
+
+/// 批次翻译失败的条目在输出中的处理方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FailedEntryMode {
+    #[default]
+    KeepSource, // 以原文写入，其余条目的译文不受影响
+    Drop, // 不写入输出，游戏回退到源语言文件
+}
+
+/// FTB Quests 任务文件的翻译方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum QuestMode {
+    #[default]
+    InPlace, // 直接替换任务文件中的文本
+    LangKeys, // 文本替换为 {键}，原文与译文写入语言文件
+}
+
+/// Origins 数据包中名称与描述的处理方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OriginsMode {
+    #[default]
+    Off,
+    Copy, // 输出翻译后的定义副本到 datapack/
+    Lang, // 以原文为键写入语言文件
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(default)]
 pub struct AppConfig {
     pub api_key: String,
@@ -16,12 +132,81 @@ pub struct AppConfig {
     pub target_lang: String,
     pub batch_size: usize,
     pub skip_existing: bool,
+    pub reuse_builtin: bool, // 全量翻译时也沿用模组自带的目标语言译文，只翻译缺失的条目
+    pub retranslate_identical: bool, // 沿用内置或社区译文时，与英文原文相同的条目视为未翻译，仍交给模型翻译
     pub max_retries: u32,
     pub retry_delay: u64,
+    pub max_retry_delay: u64, // 单次重试等待上限，秒
+    pub connect_timeout: u64, // 建立连接超时，秒
+    pub batch_timeout: u64, // 单个批次请求超时，秒，0 为不限制
+    pub run_timeout: u64, // 整个任务的运行时长上限，分钟，0 为不限制
     pub file_semaphore: usize,
     pub max_network_concurrency: usize,
     pub prompt: String,
     pub skip_quest: bool,
+    pub qa_min_ratio: f32, // 译文/原文长度比下限，低于则进入 QA 列表
+    pub qa_max_ratio: f32,
+    pub compare_model: String, // 模型对比时的 B 模型
+    pub compare_sample_size: usize,
+    pub mock_provider: bool, // 离线伪翻译，不请求 API
+    pub debug_dump: bool, // 将原始请求/响应写入 output/debug
+    pub max_consecutive_failures: usize, // 连续失败批次数达到该值时终止任务，0 为不限制
+    pub cached_models: Vec<String>, // 上次获取到的模型列表
+    pub batch_api_mode: bool, // 使用 OpenAI Batch API，延迟高但费用约减半
+    pub batch_poll_interval: u64, // 批量任务轮询间隔，秒
+    pub job_queue: Vec<QueuedJob>,
+    pub queue_parallel: bool, // 队列中的任务是否并行执行
+    pub notify_on_finish: bool, // 任务结束时发送系统通知
+    pub notify_sound: bool,
+    pub ui_language: UiLanguage, // 界面语言
+    pub theme: UiTheme,
+    pub ui_zoom: f32, // 界面缩放
+    pub window_size: Option<[f32; 2]>, // 上次关闭时的窗口大小
+    pub window_pos: Option<[f32; 2]>, // 上次关闭时的窗口位置
+    pub max_log_entries: usize, // 界面最多保留的日志条数
+    pub check_update: bool, // 启动时检查 GitHub 上的新版本
+    pub patch_jars: bool, // 将译文直接注入 JAR 副本
+    pub merge_mod_versions: bool, // 同一模组的多个版本合并条目后翻译一次
+    pub output_layout: OutputLayout,
+    pub output_order: OutputOrder,
+    pub translate_structures: bool, // 翻译结构 NBT 中的书与告示牌文字
+    pub origins_mode: OriginsMode,
+    pub quest_mode: QuestMode,
+    pub translate_config_comments: bool, // 翻译配置文件注释，输出到 config_docs/
+    pub translate_description_data: bool, // 翻译描述类模组的数据文件
+    pub suggest_glossary: bool, // 任务完成后从译文中挖掘术语
+    pub fuzzy_memory: bool, // 按向量相似度检索历史译文作为参考
+    pub embedding_model: String,
+    pub fuzzy_memory_threshold: f32, // 余弦相似度阈值
+    pub source_lang_fallbacks: Vec<String>, // 缺少源语言文件时改用的语言，如 en_gb
+    pub walk_max_depth: usize, // 扫描输入目录的最大深度，0 为不限制
+    pub follow_symlinks: bool, // 扫描时进入符号链接指向的目录
+    pub skip_hidden_dirs: bool, // 跳过以 . 开头的目录 (.minecraft 除外)
+    pub max_file_size_kb: u64, // 单个文件大小上限，超出则跳过，0 为不限制
+    pub max_file_entries: usize, // 单个文件待翻译条目上限，超出则跳过，0 为不限制
+    pub limit_whitelist: Vec<String>, // 路径包含其中任一项的文件不受上述限制
+    pub reuse_only: bool, // 只从内置汉化、社区汉化包和翻译记忆中复用译文，不调用 API
+    pub community_packs: Vec<String>, // 复用模式使用的社区汉化包 (目录、资源包 zip)
+    pub chat_path: String, // 对话接口路径，拼接在 base_url 之后
+    pub request_template: String, // 自定义请求体模板 (JSON)，为空时使用内置请求体
+    pub context_windows: BTreeMap<String, usize>, // 模型名片段 -> 上下文窗口 (tokens)，用于自动控制批次长度
+    pub mask_placeholders: bool, // 发送前把格式代码替换为 ⟦P1⟧ 等标记，收到译文后还原
+    pub color_codes: ColorCodeMode,
+    pub empty_values: EmptyValueMode,
+    pub failed_entries: FailedEntryMode,
+    pub punctuation: BTreeMap<String, PunctuationMode>, // 目标语言 -> 标点规范化方式，未列出的语言保持原样
+    pub filename_casing: FilenameCasing,
+    pub output_path_template: String, // 语言文件输出路径模板，如 {output}/{modid}/{target}.{ext}，为空时按 output_layout
+    pub output_targets: Vec<OutputTarget>, // 额外的输出目录或资源包 zip
+    pub record_run_history: bool, // 在本机记录每次运行的耗时、token 与失败率
+    pub script_hooks: String, // rhai 脚本路径，为空时不启用
+    pub post_run_command: String, // 任务结束后执行的命令，为空时不执行
+    pub post_run_timeout: u64, // 任务结束命令的超时，秒，0 为不限制
+    pub game_dir: String, // “安装到游戏”使用的 .minecraft 或实例目录
+    pub enable_pack_in_options: bool, // 安装时在 options.txt 中启用资源包
+    pub server_pack_dir: String, // 服务端分发包的输出目录，为空时不生成
+    pub server_pack_url: String, // 资源包上传后的下载地址，写入 server.properties 片段
+    pub raw_content_retention_days: u64, // 原文快照超过该天数未更新时清理，0 为永久保留
 }
 
 impl Default for AppConfig {
@@ -37,8 +222,14 @@ impl Default for AppConfig {
             model: "gpt-3.5-turbo".to_string(), 
             batch_size: 200,
             skip_existing: true,
+            reuse_builtin: false,
+            retranslate_identical: false,
             max_retries: 5,
             retry_delay: 10,
+            max_retry_delay: 120,
+            connect_timeout: 30,
+            batch_timeout: 0,
+            run_timeout: 0,
             file_semaphore: 5,
             max_network_concurrency: 10, // Global limit for concurrent network requests
             prompt: "你是一个《我的世界》(Minecraft) 模组本地化专家。当前模组 ID: 【{MOD_ID}】。\n\
@@ -50,18 +241,113 @@ impl Default for AppConfig {
         3. 请严格保留格式代码（如 §a, %s, {{0}}，\\n 等）。\n\
         4. 只返回纯净的 JSON 字符串，不要包含 Markdown 代码块标记。".to_string(),
             skip_quest: true,
+            qa_min_ratio: 0.15,
+            qa_max_ratio: 4.0,
+            compare_model: "gpt-4o".to_string(),
+            compare_sample_size: 20,
+            mock_provider: false,
+            debug_dump: false,
+            max_consecutive_failures: 5,
+            cached_models: Vec::new(),
+            batch_api_mode: false,
+            batch_poll_interval: 30,
+            job_queue: Vec::new(),
+            queue_parallel: false,
+            notify_on_finish: true,
+            notify_sound: false,
+            ui_language: UiLanguage::ZhCn,
+            theme: UiTheme::System,
+            ui_zoom: 1.1,
+            window_size: None,
+            window_pos: None,
+            max_log_entries: 5000,
+            check_update: false,
+            patch_jars: false,
+            merge_mod_versions: false,
+            output_layout: OutputLayout::ResourcePack,
+            output_order: OutputOrder::Source,
+            translate_structures: false,
+            origins_mode: OriginsMode::Off,
+            quest_mode: QuestMode::InPlace,
+            translate_config_comments: false,
+            translate_description_data: false,
+            suggest_glossary: true,
+            fuzzy_memory: false,
+            embedding_model: "text-embedding-3-small".to_string(),
+            fuzzy_memory_threshold: 0.85,
+            source_lang_fallbacks: Vec::new(),
+            walk_max_depth: 0,
+            follow_symlinks: false,
+            skip_hidden_dirs: true,
+            max_file_size_kb: 0,
+            max_file_entries: 0,
+            limit_whitelist: Vec::new(),
+            reuse_only: false,
+            community_packs: Vec::new(),
+            chat_path: "/chat/completions".to_string(),
+            request_template: String::new(),
+            context_windows: context_window::default_table(),
+            mask_placeholders: true,
+            color_codes: ColorCodeMode::Keep,
+            empty_values: EmptyValueMode::Keep,
+            failed_entries: FailedEntryMode::KeepSource,
+            punctuation: BTreeMap::new(),
+            filename_casing: FilenameCasing::Auto,
+            output_path_template: String::new(),
+            output_targets: Vec::new(),
+            record_run_history: false,
+            script_hooks: String::new(),
+            post_run_command: String::new(),
+            post_run_timeout: 600,
+            game_dir: String::new(),
+            enable_pack_in_options: true,
+            server_pack_dir: String::new(),
+            server_pack_url: String::new(),
+            raw_content_retention_days: 90,
         }
     }
 }
 
+const LEGACY_CONFIG_PATH: &str = "./MC_Translator/config.json";
+
+/// 便携模式：通过 `--portable` 参数或 `MC_TRANSLATOR_PORTABLE` 环境变量开启，
+/// 配置保存在工作目录下的 `MC_Translator/`（旧行为），否则保存在系统的用户配置目录
+pub fn is_portable() -> bool {
+    static PORTABLE: OnceLock<bool> = OnceLock::new();
+    *PORTABLE.get_or_init(|| {
+        std::env::args().any(|arg| arg == "--portable")
+            || std::env::var_os("MC_TRANSLATOR_PORTABLE").is_some()
+    })
+}
+
+/// 配置文件所在目录，崩溃报告等也保存在这里
+pub fn data_dir() -> PathBuf {
+    if !is_portable()
+        && let Some(dir) = dirs::config_dir()
+    {
+        return dir.join("mc_translator");
+    }
+    PathBuf::from(LEGACY_CONFIG_PATH)
+        .parent()
+        .map(PathBuf::from)
+        .unwrap_or_default()
+}
+
 impl AppConfig {
     fn config_path() -> PathBuf {
-        PathBuf::from("./MC_Translator/config.json")
+        data_dir().join("config.json")
     }
 
     pub fn load() -> Self {
-        if let Ok(content) = fs::read_to_string(Self::config_path()) {
+        let path = Self::config_path();
+        if let Ok(content) = fs::read_to_string(&path) {
             serde_json::from_str(&content).unwrap_or_default()
+        } else if let Ok(content) = fs::read_to_string(LEGACY_CONFIG_PATH) {
+            // 首次使用用户配置目录时，沿用工作目录下的旧配置
+            log::info!("从 {} 迁移配置到 {:?}", LEGACY_CONFIG_PATH, path);
+            let config: Self = serde_json::from_str(&content).unwrap_or_default();
+            config.save();
+            config
         } else {
             let config = Self::default();
             config.save();
@@ -69,19 +355,27 @@ impl AppConfig {
         }
     }
 
+    /// 目标语言对应的标点规范化方式，语言代码不区分大小写
+    pub fn punctuation_for(&self, lang: &str) -> PunctuationMode {
+        self.punctuation
+            .iter()
+            .find(|(code, _)| code.eq_ignore_ascii_case(lang))
+            .map_or(PunctuationMode::Keep, |(_, mode)| *mode)
+    }
+
     pub fn save(&self) {
         let path = Self::config_path();
 
-        if let Some(parent) = path.parent() {
-            if let Err(e) = fs::create_dir_all(parent) {
-                log_err!("无法创建配置目录: {}", e);
-                return;
-            }
+        if let Some(parent) = path.parent()
+            && let Err(e) = fs::create_dir_all(parent)
+        {
+            log_err!("无法创建配置目录: {}", e);
+            return;
         }
-        if let Ok(data) = serde_json::to_string_pretty(self) {
-            if let Err(e) = fs::write(&path, data) {
-                log_err!("无法保存配置文件到 {:?}: {}", path, e);
-            }
+        if let Ok(data) = serde_json::to_string_pretty(self)
+            && let Err(e) = fs::write(&path, data)
+        {
+            log_err!("无法保存配置文件到 {:?}: {}", path, e);
         }
     }
 }
\ No newline at end of file
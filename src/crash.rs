@@ -0,0 +1,88 @@
+// 崩溃报告：发布版隐藏了控制台，panic 信息需要写入文件才能看到
+use crate::config::{AppConfig, data_dir};
+use chrono::Local;
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::fs;
+use std::panic;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+const RECENT_LOG_LIMIT: usize = 200;
+
+static RECENT_LOGS: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+static CONFIG_SNAPSHOT: Mutex<Option<AppConfig>> = Mutex::new(None);
+
+/// 记录一条日志，崩溃时一并写入报告
+pub fn record_log(line: String) {
+    let mut logs = RECENT_LOGS.lock().unwrap_or_else(|e| e.into_inner());
+    if logs.len() >= RECENT_LOG_LIMIT {
+        logs.pop_front();
+    }
+    logs.push_back(line);
+}
+
+/// 更新崩溃报告中的配置快照，API Key 不会写入报告
+pub fn set_config_snapshot(config: &AppConfig) {
+    let mut snapshot = config.clone();
+    if !snapshot.api_key.is_empty() {
+        snapshot.api_key = "<已隐藏>".to_string();
+    }
+    *CONFIG_SNAPSHOT.lock().unwrap_or_else(|e| e.into_inner()) = Some(snapshot);
+}
+
+fn build_report(info: &panic::PanicHookInfo) -> String {
+    let mut report = String::new();
+    let _ = writeln!(report, "MC Translator v{} 崩溃报告", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(report, "时间: {}", Local::now().format("%Y-%m-%d %H:%M:%S"));
+    let _ = writeln!(report, "线程: {}", std::thread::current().name().unwrap_or("<unnamed>"));
+    let _ = writeln!(report, "{}", info);
+    let _ = writeln!(report, "\n{}", std::backtrace::Backtrace::force_capture());
+
+    // panic 可能发生在持有锁时，用 try_lock 避免死锁
+    let _ = writeln!(report, "\n==== 最近日志 ====");
+    if let Ok(logs) = RECENT_LOGS.try_lock() {
+        for line in logs.iter() {
+            let _ = writeln!(report, "{}", line);
+        }
+    }
+
+    let _ = writeln!(report, "\n==== 配置 ====");
+    if let Ok(config) = CONFIG_SNAPSHOT.try_lock()
+        && let Some(config) = config.as_ref()
+        && let Ok(json) = serde_json::to_string_pretty(config)
+    {
+        let _ = writeln!(report, "{}", json);
+    }
+    report
+}
+
+fn write_report(report: &str) -> Option<PathBuf> {
+    let dir = data_dir().join("crash_reports");
+    fs::create_dir_all(&dir).ok()?;
+    let path = dir.join(format!("crash_{}.txt", Local::now().format("%Y%m%d_%H%M%S")));
+    fs::write(&path, report).ok()?;
+    Some(path)
+}
+
+/// 安装 panic hook：保留默认输出，同时写入崩溃报告，主线程崩溃时弹窗提示报告位置
+pub fn install_panic_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        default_hook(info);
+
+        let report = build_report(info);
+        let Some(path) = write_report(&report) else {
+            return;
+        };
+        log::error!("程序崩溃，报告已保存到 {:?}", path);
+
+        if std::thread::current().name() == Some("main") {
+            rfd::MessageDialog::new()
+                .set_level(rfd::MessageLevel::Error)
+                .set_title("MC Translator")
+                .set_description(format!("程序发生错误即将退出，崩溃报告已保存到:\n{}", path.display()))
+                .show();
+        }
+    }));
+}
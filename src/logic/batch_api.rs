@@ -0,0 +1,191 @@
+use crate::logic::error::{Result, TranslatorError};
+use crate::logic::openai::{OpenAIClient, is_truncated};
+use crate::logic::prompt_lint::estimate_tokens;
+use crate::{log_info, log_warn};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::select;
+use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
+
+// 多个文件任务会同时读写任务记录
+static JOB_STORE_LOCK: Mutex<()> = Mutex::new(());
+
+fn job_store_path(output_root: &str) -> PathBuf {
+    Path::new(output_root).join("batch_jobs.json")
+}
+
+fn load_jobs(path: &Path) -> HashMap<String, String> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn update_jobs(output_root: &str, f: impl FnOnce(&mut HashMap<String, String>)) {
+    let _guard = JOB_STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let path = job_store_path(output_root);
+    let mut jobs = load_jobs(&path);
+    f(&mut jobs);
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(data) = serde_json::to_string_pretty(&jobs)
+        && let Err(e) = fs::write(&path, data)
+    {
+        log_warn!("无法保存批量任务记录 {:?}: {}", path, e);
+    }
+}
+
+fn find_job(output_root: &str, key: &str) -> Option<String> {
+    let _guard = JOB_STORE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    load_jobs(&job_store_path(output_root)).get(key).cloned()
+}
+
+// FNV-1a，需跨进程稳定，不能用 DefaultHasher
+fn stable_hash(data: &str) -> u64 {
+    data.bytes().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// 等待 Batch 任务结束，返回输出文件 ID
+async fn wait_for_batch(
+    client: &OpenAIClient,
+    context_id: &str,
+    batch_id: &str,
+    poll_interval: Duration,
+    token: &CancellationToken,
+) -> Result<String> {
+    loop {
+        let info = client.get_batch(batch_id, token).await?;
+        let status = info["status"].as_str().unwrap_or("unknown");
+
+        match status {
+            "completed" => {
+                return info["output_file_id"]
+                    .as_str()
+                    .map(|s| s.to_string())
+                    .ok_or_else(|| TranslatorError::Other(format!("批量任务 {} 没有输出文件", batch_id)));
+            }
+            "failed" | "expired" | "cancelled" | "cancelling" => {
+                return Err(TranslatorError::Other(format!("批量任务 {} 状态为 {}: {}", batch_id, status, info["errors"])));
+            }
+            _ => {
+                let counts = &info["request_counts"];
+                log_info!(
+                    "[{}] 批量任务 {} 状态 {} ({}/{})",
+                    context_id,
+                    batch_id,
+                    status,
+                    counts["completed"].as_u64().unwrap_or(0),
+                    counts["total"].as_u64().unwrap_or(0)
+                );
+            }
+        }
+
+        select! {
+            _ = sleep(poll_interval) => {}
+            _ = token.cancelled() => {
+                log_info!("批量任务 {} 将在下次运行时继续等待", batch_id);
+                return Err(TranslatorError::Cancelled);
+            }
+        }
+    }
+}
+
+/// 通过 Batch API 提交一组批次并等待结果，返回值与输入批次一一对应；
+/// 输出被截断的批次返回 `Truncated`，由调用方拆分后重试
+pub async fn translate_chunks(
+    client: &OpenAIClient,
+    context_id: &str,
+    chunks: &[Vec<&str>],
+    poll_interval: Duration,
+    token: &CancellationToken,
+) -> Result<Vec<Result<Vec<String>>>> {
+    let masked: Vec<_> = chunks.iter().map(|texts| client.mask_texts(texts)).collect();
+    let mut jsonl = String::new();
+    for (idx, texts) in masked.iter().enumerate() {
+        let sources: Vec<&str> = texts.iter().map(|m| m.text.as_str()).collect();
+        let line = json!({
+            "custom_id": format!("{}", idx),
+            "method": "POST",
+            "url": client.batch_endpoint(),
+            "body": client.build_chat_body(&sources, context_id, false)?,
+        });
+        jsonl.push_str(&serde_json::to_string(&line)?);
+        jsonl.push('\n');
+    }
+
+    // 相同输入复用已提交的任务，程序重启后可继续等待
+    let job_key = format!("{}#{:016x}", context_id, stable_hash(&jsonl));
+    let output_root = client.output_root().to_string();
+
+    let batch_id = match find_job(&output_root, &job_key) {
+        Some(id) => {
+            log_info!("[{}] 继续等待已提交的批量任务 {}", context_id, id);
+            id
+        }
+        None => {
+            let file_id = client.upload_batch_file(jsonl, token).await?;
+            let id = client.create_batch(&file_id, token).await?;
+            update_jobs(&output_root, |jobs| {
+                jobs.insert(job_key.clone(), id.clone());
+            });
+            log_info!("[{}] 已提交批量任务 {} ({} 个批次)", context_id, id, chunks.len());
+            id
+        }
+    };
+
+    let output_file_id = match wait_for_batch(client, context_id, &batch_id, poll_interval, token).await {
+        Ok(id) => id,
+        Err(e) => {
+            // 任务已失效则移除记录，下次重新提交
+            if !token.is_cancelled() {
+                update_jobs(&output_root, |jobs| {
+                    jobs.remove(&job_key);
+                });
+            }
+            return Err(e);
+        }
+    };
+    let output = client.download_file(&output_file_id, token).await?;
+
+    let mut results: Vec<Result<Vec<String>>> = (0..chunks.len())
+        .map(|_| Err(TranslatorError::Other("批量结果中缺少该批次".to_string())))
+        .collect();
+    for line in output.lines().filter(|l| !l.trim().is_empty()) {
+        let Ok(item) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        let Some(idx) = item["custom_id"].as_str().and_then(|s| s.parse::<usize>().ok()) else {
+            continue;
+        };
+        if idx >= results.len() {
+            continue;
+        }
+        let choice = &item["response"]["body"]["choices"][0];
+        let content = choice["message"]["content"].as_str().unwrap_or_default();
+        client
+            .usage()
+            .record(&item["response"]["body"]["usage"], || (0, estimate_tokens(content)));
+        // 截断的 JSON 数组无法解析，直接标记
+        let result = if is_truncated(&choice["finish_reason"]) {
+            Err(TranslatorError::Truncated)
+        } else {
+            client
+                .parse_translation(content)
+                .map(|texts| client.restore_texts(&masked[idx], texts, &format!("{}_batch{}", context_id, idx + 1)))
+        };
+        results[idx] = result.map_err(|e| e.with_request_id(item["response"]["request_id"].as_str()));
+    }
+
+    update_jobs(&output_root, |jobs| {
+        jobs.remove(&job_key);
+    });
+    Ok(results)
+}
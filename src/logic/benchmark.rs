@@ -0,0 +1,129 @@
+use crate::config::AppConfig;
+use crate::logic::common::{FileFormat, extract_mod_id, read_map_from_file};
+use crate::logic::formats::jar;
+use crate::logic::openai::OpenAIClient;
+use crate::logic::processor::scan_input_files;
+use crate::message::send_batch_done;
+use crate::logic::error::{Result, TranslatorError};
+use serde_json::{Map, Value};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+/// 单条对比结果
+#[derive(Debug, Clone)]
+pub struct CompareRow {
+    pub key: String,
+    pub source: String,
+    pub model_a: String,
+    pub model_b: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct CompareResult {
+    pub model_a: String,
+    pub model_b: String,
+    pub elapsed_a: Duration,
+    pub elapsed_b: Duration,
+    pub rows: Vec<CompareRow>,
+}
+
+// 先多收集一些候选条目，再均匀抽样，避免样本全部来自第一个模组
+const SAMPLE_POOL_FACTOR: usize = 5;
+
+fn push_entries(pool: &mut Vec<(String, String)>, mod_id: &str, map: &Map<String, Value>) {
+    for (k, v) in map {
+        if let Value::String(s) = v
+            && !s.trim().is_empty()
+        {
+            pool.push((format!("{}:{}", mod_id, k), s.clone()));
+        }
+    }
+}
+
+/// 从扫描结果中抽取 N 条原文样本
+pub fn collect_sample(config: &AppConfig, sample_size: usize) -> Vec<(String, String)> {
+    let pool_limit = sample_size * SAMPLE_POOL_FACTOR;
+    let mut pool = Vec::new();
+
+    for path in scan_input_files(config) {
+        if pool.len() >= pool_limit {
+            break;
+        }
+        let ext = path.extension().unwrap_or_default().to_string_lossy().to_string();
+        match ext.as_str() {
+            "jar" => {
+                if let Ok(maps) = jar::read_source_maps(&path, &config.source_lang, &config.source_lang_fallbacks) {
+                    for (mod_id, map) in maps {
+                        push_entries(&mut pool, &mod_id, &map);
+                    }
+                }
+            }
+            "json" | "lang" => {
+                let format = if ext == "lang" { FileFormat::Lang } else { FileFormat::Json };
+                if let Ok(map) = read_map_from_file(&path, format) {
+                    push_entries(&mut pool, &extract_mod_id(&path), &map);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if pool.len() <= sample_size {
+        return pool;
+    }
+    let step = pool.len() as f64 / sample_size as f64;
+    (0..sample_size)
+        .map(|i| pool[(i as f64 * step) as usize].clone())
+        .collect()
+}
+
+async fn timed_translate(
+    client: &OpenAIClient,
+    model: &str,
+    texts: Vec<String>,
+    token: &CancellationToken,
+) -> Result<(Vec<String>, Duration)> {
+    let start = Instant::now();
+    let label = format!("benchmark_{}", model);
+    let result = client.translate_text_list(&texts, "benchmark", &label, token).await;
+    send_batch_done(&label);
+    Ok((result?, start.elapsed()))
+}
+
+/// 使用两个模型翻译同一批样本，返回逐条对比结果
+pub async fn run_model_comparison(
+    config: AppConfig,
+    sample_size: usize,
+    model_a: String,
+    model_b: String,
+    token: CancellationToken,
+) -> Result<CompareResult> {
+    let sample = collect_sample(&config, sample_size);
+    if sample.is_empty() {
+        return Err(TranslatorError::Other("未在输入路径中找到可抽样的条目".to_string()));
+    }
+    let texts: Vec<String> = sample.iter().map(|(_, v)| v.clone()).collect();
+
+    let client_a = OpenAIClient::new(AppConfig { model: model_a.clone(), ..config.clone() });
+    let client_b = OpenAIClient::new(AppConfig { model: model_b.clone(), ..config });
+
+    let (res_a, res_b) = tokio::join!(
+        timed_translate(&client_a, &model_a, texts.clone(), &token),
+        timed_translate(&client_b, &model_b, texts, &token),
+    );
+    let (out_a, elapsed_a) = res_a.map_err(|e| TranslatorError::Other(format!("{} 翻译失败: {}", model_a, e)))?;
+    let (out_b, elapsed_b) = res_b.map_err(|e| TranslatorError::Other(format!("{} 翻译失败: {}", model_b, e)))?;
+
+    let rows = sample
+        .into_iter()
+        .enumerate()
+        .map(|(i, (key, source))| CompareRow {
+            key,
+            source,
+            model_a: out_a.get(i).cloned().unwrap_or_default(),
+            model_b: out_b.get(i).cloned().unwrap_or_default(),
+        })
+        .collect();
+
+    Ok(CompareResult { model_a, model_b, elapsed_a, elapsed_b, rows })
+}
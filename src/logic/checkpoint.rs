@@ -0,0 +1,94 @@
+// 单文件断点：条目很多的文件 (如数万条的任务导出) 每完成一个批次就把译文追加到
+// output_root/checkpoints，中途取消或部分批次失败后重新运行时只发送剩余的条目，全部完成后删除
+use crate::log_warn;
+use crate::logic::paths::sanitize_component;
+use serde_json::{Map, Value, json};
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const DIR_NAME: &str = "checkpoints";
+/// 批次数达到此值的文件才记录断点，小文件重跑的代价不大
+pub const MIN_BATCHES: usize = 4;
+
+pub struct Checkpoint {
+    path: PathBuf,
+    file: Mutex<Option<File>>, // 首次记录时才创建
+}
+
+/// 同一 context_id 下可能有多个文件 (如同一模组的多个语言文件)，按键集合区分
+fn checkpoint_path(output_root: &Path, context_id: &str, map: &Map<String, Value>) -> PathBuf {
+    let mut keys: Vec<&str> = map.keys().map(String::as_str).collect();
+    keys.sort_unstable();
+    let mut hasher = Sha1::new();
+    for key in keys {
+        hasher.update(key.as_bytes());
+        hasher.update([0]);
+    }
+    let digest: String = hasher.finalize().iter().take(4).map(|b| format!("{:02x}", b)).collect();
+    output_root.join(DIR_NAME).join(sanitize_component(&format!("{}_{}.jsonl", context_id, digest)))
+}
+
+impl Checkpoint {
+    pub fn open(output_root: &Path, context_id: &str, map: &Map<String, Value>) -> Self {
+        Self { path: checkpoint_path(output_root, context_id, map), file: Mutex::new(None) }
+    }
+
+    /// 读取上次记录的译文，只保留原文与当前一致的条目；中断时写了一半的行直接忽略
+    pub fn recover(&self, map: &Map<String, Value>) -> HashMap<String, String> {
+        let Ok(content) = fs::read_to_string(&self.path) else {
+            return HashMap::new();
+        };
+        content
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+            .filter_map(|record| {
+                let key = record.get("key")?.as_str()?;
+                let source = record.get("source")?.as_str()?;
+                let target = record.get("target")?.as_str()?;
+                (map.get(key)?.as_str()? == source).then(|| (key.to_string(), target.to_string()))
+            })
+            .collect()
+    }
+
+    /// 追加一个批次的译文，写入失败只警告，不影响本次翻译
+    pub fn record(&self, keys: &[String], sources: &[String], targets: &[String]) {
+        let Ok(mut guard) = self.file.lock() else {
+            return;
+        };
+        if guard.is_none() {
+            let opened = self
+                .path
+                .parent()
+                .map_or(Ok(()), fs::create_dir_all)
+                .and_then(|_| OpenOptions::new().create(true).append(true).open(&self.path));
+            match opened {
+                Ok(file) => *guard = Some(file),
+                Err(e) => {
+                    log_warn!("无法写入断点文件 {:?}: {}", self.path, e);
+                    return;
+                }
+            }
+        }
+        let Some(file) = guard.as_mut() else {
+            return;
+        };
+        let mut lines = String::new();
+        for ((key, source), target) in keys.iter().zip(sources).zip(targets) {
+            lines.push_str(&json!({ "key": key, "source": source, "target": target }).to_string());
+            lines.push('\n');
+        }
+        if let Err(e) = file.write_all(lines.as_bytes()) {
+            log_warn!("无法写入断点文件 {:?}: {}", self.path, e);
+        }
+    }
+
+    /// 文件的所有批次都已完成，删除断点
+    pub fn finish(self) {
+        drop(self.file);
+        let _ = fs::remove_file(&self.path);
+    }
+}
@@ -0,0 +1,33 @@
+// 颜色代码规范化：插件常用 &a，资源包只认 §a
+use crate::config::ColorCodeMode;
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::sync::LazyLock;
+
+static SECTION_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"§([0-9a-fk-orA-FK-OR])").unwrap());
+// & 也常见于普通文本 (如 R&D)，只认小写代码
+static AMPERSAND_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"&([0-9a-fk-or])").unwrap());
+
+pub fn normalize(text: &str, mode: ColorCodeMode) -> String {
+    match mode {
+        ColorCodeMode::Keep => text.to_string(),
+        ColorCodeMode::Section => AMPERSAND_RE.replace_all(text, "§$1").into_owned(),
+        ColorCodeMode::Ampersand => SECTION_RE.replace_all(text, "&$1").into_owned(),
+        ColorCodeMode::Strip => {
+            let text = SECTION_RE.replace_all(text, "");
+            AMPERSAND_RE.replace_all(&text, "").into_owned()
+        }
+    }
+}
+
+/// 对写出的所有字符串值应用规范化
+pub fn normalize_map(map: &mut Map<String, Value>, mode: ColorCodeMode) {
+    if mode == ColorCodeMode::Keep {
+        return;
+    }
+    for value in map.values_mut() {
+        if let Value::String(text) = value {
+            *text = normalize(text, mode);
+        }
+    }
+}
@@ -1,122 +1,970 @@
+use crate::config::{ColorCodeMode, EmptyValueMode, FailedEntryMode, FilenameCasing, OriginsMode, OutputLayout, OutputOrder, PunctuationMode, QuestMode};
+use crate::logic::batch_api;
+use crate::logic::checkpoint::{self, Checkpoint};
+use crate::logic::color_codes;
+use crate::logic::punctuation;
+use crate::logic::concurrency;
+use crate::logic::mc_version::McVersion;
+use crate::logic::error::{Result, TranslatorError, describe};
+use crate::logic::formats::jar::VersionMerge;
+use crate::logic::hooks::Hooks;
 use crate::logic::openai::OpenAIClient;
-use crate::{log_info, log_warn, log_err};
-use anyhow::Result;
+use crate::logic::pack_manifest::ModEntry;
+use crate::logic::paths;
+use crate::logic::placeholder::PLACEHOLDER_RE;
+use crate::logic::prompt_lint::estimate_tokens;
+use crate::logic::raw_content;
+use crate::logic::qa::report_length_issue;
+use crate::logic::tolerant_json;
+use crate::logic::i18n::tr;
+use crate::message::{Logger, send_batch_done};
+use crate::{log_info, log_warn, log_err, log_success};
+use regex::Regex;
+use serde::Serialize;
 use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::convert::Infallible;
 use std::fs;
-use std::io::{BufRead, BufReader, Write};
-use std::path::Path;
+use std::io::Write;
+use std::ops::Range;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
 use tokio_util::sync::CancellationToken;
 use tokio::task::JoinSet;
 use tokio::sync::Semaphore;
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Duration;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// 熔断器：连续失败的批次数达到阈值后终止整个任务
+#[derive(Debug, Default)]
+pub struct CircuitBreaker {
+    threshold: usize,
+    consecutive: AtomicUsize,
+    tripped: AtomicBool,
+}
+
+impl CircuitBreaker {
+    /// threshold 为 0 时不启用熔断
+    pub fn new(threshold: usize) -> Self {
+        Self {
+            threshold,
+            ..Default::default()
+        }
+    }
+
+    pub fn record_success(&self) {
+        self.consecutive.store(0, Ordering::SeqCst);
+    }
+
+    /// 记录一次失败，刚好触发熔断时返回 true
+    pub fn record_failure(&self) -> bool {
+        let count = self.consecutive.fetch_add(1, Ordering::SeqCst) + 1;
+        self.threshold > 0
+            && count >= self.threshold
+            && !self.tripped.swap(true, Ordering::SeqCst)
+    }
+
+    /// 遇到重试也无法恢复的错误时立即熔断，不受阈值限制，首次触发时返回 true
+    pub fn trip(&self) -> bool {
+        !self.tripped.swap(true, Ordering::SeqCst)
+    }
+
+    pub fn is_tripped(&self) -> bool {
+        self.tripped.load(Ordering::SeqCst)
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+}
+
+/// 文件被跳过的原因，分别计数并在运行汇总中列出
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SkipReason {
+    Existing, // 输出已存在
+    Vanilla, // minecraft 命名空间，原版已有译文
+    Empty, // 没有可翻译的内容
+    Unsupported, // 不支持的文件类型
+    Unparsable, // 语法错误
+    Filtered, // 超出大小或条目上限
+    Duplicate, // 与另一个 JAR 内容相同
+}
+
+impl SkipReason {
+    pub const ALL: [SkipReason; 7] = [
+        SkipReason::Existing,
+        SkipReason::Vanilla,
+        SkipReason::Empty,
+        SkipReason::Unsupported,
+        SkipReason::Unparsable,
+        SkipReason::Filtered,
+        SkipReason::Duplicate,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SkipReason::Existing => tr("输出已存在"),
+            SkipReason::Vanilla => tr("原版命名空间"),
+            SkipReason::Empty => tr("无可翻译内容"),
+            SkipReason::Unsupported => tr("不支持的类型"),
+            SkipReason::Unparsable => tr("语法错误"),
+            SkipReason::Filtered => tr("超出上限"),
+            SkipReason::Duplicate => tr("重复的模组"),
+        }
+    }
+
+    /// 用于接口输出的名称
+    pub fn name(self) -> &'static str {
+        match self {
+            SkipReason::Existing => "existing",
+            SkipReason::Vanilla => "vanilla",
+            SkipReason::Empty => "empty",
+            SkipReason::Unsupported => "unsupported",
+            SkipReason::Unparsable => "unparsable",
+            SkipReason::Filtered => "filtered",
+            SkipReason::Duplicate => "duplicate",
+        }
+    }
+}
+
+/// 批次翻译失败的条目，context 为日志中的批次标识 (ModID、任务文件等)
+#[derive(Debug, Clone, Serialize)]
+pub struct FailedEntry {
+    pub context: String,
+    pub key: String,
+    pub source: String,
+}
+
+/// 单次运行的计数，任务结束时汇总为 RunSummary
+#[derive(Debug, Default)]
+pub struct RunStats {
+    pub processed_files: AtomicUsize,
+    pub skipped_files: AtomicUsize, // 输出已存在、无内容或超出上限等 (不含语法错误)
+    pub skip_reasons: [AtomicUsize; SkipReason::ALL.len()], // 按 SkipReason 分别计数
+    pub failed_files: AtomicUsize,
+    pub translated_entries: AtomicUsize,
+    pub failed_entries: AtomicUsize,
+    pub reused_entries: AtomicUsize, // 仅复用模式下从已有译文中填充的条目
+    pub untranslated_entries: AtomicUsize, // 仅复用模式下仍需调用 API 的条目
+    pub untranslated_chars: AtomicUsize,
+    pub empty_entries: AtomicUsize, // 值为空或只含空白的条目
+    pub unparsable_files: Mutex<Vec<String>>, // 语法错误无法解析而跳过的文件
+    pub failed_keys: Mutex<Vec<FailedEntry>>, // 批次翻译失败的条目，在运行汇总中逐条列出
+}
+
+impl RunStats {
+    pub fn add(counter: &AtomicUsize, n: usize) {
+        counter.fetch_add(n, Ordering::Relaxed);
+    }
+
+    pub fn get(counter: &AtomicUsize) -> usize {
+        counter.load(Ordering::Relaxed)
+    }
+
+    /// 并入另一份计数，用于把单个文件的统计汇总到整次运行
+    pub fn merge(&self, other: &RunStats) {
+        Self::add(&self.processed_files, Self::get(&other.processed_files));
+        Self::add(&self.skipped_files, Self::get(&other.skipped_files));
+        Self::add(&self.failed_files, Self::get(&other.failed_files));
+        Self::add(&self.translated_entries, Self::get(&other.translated_entries));
+        Self::add(&self.failed_entries, Self::get(&other.failed_entries));
+        Self::add(&self.reused_entries, Self::get(&other.reused_entries));
+        Self::add(&self.untranslated_entries, Self::get(&other.untranslated_entries));
+        Self::add(&self.untranslated_chars, Self::get(&other.untranslated_chars));
+        Self::add(&self.empty_entries, Self::get(&other.empty_entries));
+        for (counter, other) in self.skip_reasons.iter().zip(&other.skip_reasons) {
+            Self::add(counter, Self::get(other));
+        }
+        let other_files = other.unparsable_files.lock().map(|files| files.clone()).unwrap_or_default();
+        if let Ok(mut files) = self.unparsable_files.lock() {
+            files.extend(other_files);
+        }
+        let other_keys = other.failed();
+        if let Ok(mut keys) = self.failed_keys.lock() {
+            keys.extend(other_keys);
+        }
+    }
+
+    /// 记录跳过的文件，语法错误的文件另由 record_unparsable 记录名称
+    pub fn record_skip(&self, reason: SkipReason) {
+        Self::add(&self.skip_reasons[reason as usize], 1);
+        if reason != SkipReason::Unparsable {
+            Self::add(&self.skipped_files, 1);
+        }
+    }
+
+    /// 各原因跳过的文件数，省略为 0 的原因
+    pub fn skip_counts(&self) -> Vec<(SkipReason, usize)> {
+        SkipReason::ALL
+            .iter()
+            .map(|&reason| (reason, Self::get(&self.skip_reasons[reason as usize])))
+            .filter(|(_, count)| *count > 0)
+            .collect()
+    }
+
+    pub fn record_unparsable(&self, name: String) {
+        self.record_skip(SkipReason::Unparsable);
+        if let Ok(mut files) = self.unparsable_files.lock() {
+            files.push(name);
+        }
+    }
+
+    pub fn unparsable(&self) -> Vec<String> {
+        self.unparsable_files.lock().map(|files| files.clone()).unwrap_or_default()
+    }
+
+    pub fn record_failed(&self, entries: impl IntoIterator<Item = FailedEntry>) {
+        if let Ok(mut keys) = self.failed_keys.lock() {
+            keys.extend(entries);
+        }
+    }
+
+    pub fn failed(&self) -> Vec<FailedEntry> {
+        self.failed_keys.lock().map(|keys| keys.clone()).unwrap_or_default()
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct TranslationContext {
     pub batch_size: usize,
     pub skip_existing: bool,
+    pub reuse_builtin: bool, // 全量模式下沿用内置汉化
+    pub retranslate_identical: bool, // 沿用的译文与原文相同时仍重新翻译
     pub update_existing: bool,
     pub network_semaphore: Arc<Semaphore>,
     pub source_lang: String,
     pub target_lang: String,
+    pub qa_min_ratio: f32,
+    pub qa_max_ratio: f32,
+    pub breaker: Arc<CircuitBreaker>,
+    pub batch_api: bool,
+    pub batch_poll_interval: Duration,
+    pub patch_jars: bool, // 将译文注入 JAR 副本，输出到 mods_patched/
+    pub output_layout: OutputLayout,
+    pub output_order: OutputOrder,
+    pub color_codes: ColorCodeMode, // 写出前转换或移除颜色代码
+    pub empty_values: EmptyValueMode,
+    pub failed_entries: FailedEntryMode,
+    pub punctuation: PunctuationMode, // 按目标语言解析后的标点规范化方式
+    pub filename_casing: FilenameCasing,
+    pub translate_structures: bool, // 翻译结构文件中的书与告示牌
+    pub origins_mode: OriginsMode,
+    pub quest_mode: QuestMode,
+    pub translate_description_data: bool, // 翻译附魔/物品描述类模组的数据文件
+    pub source_fallbacks: Vec<String>, // 备选源语言
+    pub max_file_size: u64, // 字节，0 为不限制
+    pub max_file_entries: usize,
+    pub limit_whitelist: Vec<String>,
+    pub stats: Arc<RunStats>,
+    pub reuse_only: bool, // 不调用 API，只复用已有译文
+    pub community_entries: Arc<HashMap<String, Map<String, Value>>>, // ModID -> 社区汉化包中的译文
+    pub mod_names: Arc<Mutex<HashMap<String, String>>>, // ModID -> 显示名称，扫描 JAR 时收集
+    pub mod_versions: Arc<Mutex<BTreeMap<String, ModEntry>>>, // 本次翻译的模组版本，写入 pack_manifest.json
+    pub mc_version: Option<McVersion>, // 从模组元数据推断的整合包版本
+    pub version_merges: Arc<HashMap<String, VersionMerge>>, // ModID -> 多个版本合并后的原文
+    pub hooks: Option<Arc<Hooks>>, // 用户脚本钩子
+    pub output_template: String, // 语言文件输出路径模板，为空时按 output_layout
+    pub input_root: PathBuf, // 输入目录 (输入为单个文件时为其所在目录)，用于保留输出的相对路径
+    pub output_root: PathBuf, // 大文件的断点保存在其下
+    pub logger: Logger, // 日志与进度消息的去向，派生的任务需在其 scope 中运行
+}
+
+impl TranslationContext {
+    /// 源语言文件对应的译文文件名，备选源语言 (如 en_gb.json) 同样替换为目标语言
+    pub fn target_filename(&self, original_name: &str) -> String {
+        let lang = self
+            .source_fallbacks
+            .iter()
+            .find(|l| is_lang_file(original_name, l))
+            .unwrap_or(&self.source_lang);
+        // 已知整合包版本时按版本决定大小写，否则按扩展名
+        let casing = match (self.filename_casing, self.mc_version) {
+            (FilenameCasing::Auto, Some(version)) if version.uses_json_lang() => FilenameCasing::Lower,
+            (FilenameCasing::Auto, Some(_)) => FilenameCasing::UpperRegion,
+            (casing, _) => casing,
+        };
+        get_target_filename_cased(original_name, lang, &self.target_lang, casing)
+    }
+
+    /// 写出的语言文件格式：已知整合包版本时 1.13+ 统一为 JSON，之前统一为 .lang
+    pub fn output_format(&self, format: FileFormat) -> FileFormat {
+        match (format, self.mc_version) {
+            (FileFormat::Lang, Some(version)) if version.uses_json_lang() => FileFormat::Json,
+            (FileFormat::Json, Some(version)) if !version.uses_json_lang() => FileFormat::Lang,
+            _ => format,
+        }
+    }
+
+    /// 写出的译文文件名，格式与源文件不同时一并替换扩展名
+    pub fn output_filename(&self, original_name: &str, format: FileFormat) -> String {
+        let name = self.target_filename(original_name);
+        let extension = match self.output_format(format) {
+            output if output == format => return name,
+            FileFormat::Json => "json",
+            FileFormat::Lang => "lang",
+            FileFormat::Lines => return name,
+        };
+        match name.rsplit_once('.') {
+            Some((stem, _)) => format!("{}.{}", stem, extension),
+            None => name,
+        }
+    }
+
+    /// 语言文件的最终输出位置：按路径模板 (未设置时按输出结构) 计算，再经脚本钩子重写
+    pub fn lang_output_path(&self, output_root: &Path, mod_id: &str, target_name: &str) -> PathBuf {
+        let path = match self.output_template.trim() {
+            "" => None,
+            template => templated_output_path(template, output_root, mod_id, target_name),
+        };
+        let path = path.unwrap_or_else(|| lang_output_path(output_root, mod_id, target_name, self.output_layout));
+        self.map_output_path(output_root, path)
+    }
+
+    /// 经脚本 map_output_path 钩子重写后的输出路径，并清理 Windows 不允许的字符、处理超长路径
+    pub fn map_output_path(&self, output_root: &Path, path: PathBuf) -> PathBuf {
+        let path = match &self.hooks {
+            Some(hooks) => hooks.map_output_path(output_root, path),
+            None => path,
+        };
+        paths::long_path(paths::sanitize_output_path(output_root, path))
+    }
+
+    pub fn register_mod_names(&self, names: HashMap<String, String>) {
+        if let Ok(mut known) = self.mod_names.lock() {
+            known.extend(names);
+        }
+    }
+
+    /// 记录 JAR 中有待翻译内容的模组版本
+    pub fn register_mod_versions(&self, jar_name: &str, sha1: Option<String>, versions: HashMap<String, String>) {
+        let names = self.mod_names.lock().map(|names| names.clone()).unwrap_or_default();
+        if let Ok(mut known) = self.mod_versions.lock() {
+            for (id, version) in versions {
+                let name = names.get(&id).cloned();
+                known.insert(id, ModEntry { name, version, jar: jar_name.to_string(), sha1: sha1.clone() });
+            }
+        }
+    }
+
+    /// 日志中的模组标识，已知显示名称时为 "名称 (ModID)"
+    pub fn mod_label(&self, mod_id: &str) -> String {
+        match self.mod_names.lock().ok().and_then(|names| names.get(mod_id).cloned()) {
+            Some(name) => format!("{} ({})", name, mod_id),
+            None => mod_id.to_string(),
+        }
+    }
+
+    /// 开启跳过已存在文件且输出已存在时记录并返回 true
+    pub fn skip_existing_output(&self, output_path: &Path) -> bool {
+        if !self.skip_existing || !output_path.exists() {
+            return false;
+        }
+        log_success!("跳过已存在的文件: {:?}", output_path);
+        self.stats.record_skip(SkipReason::Existing);
+        true
+    }
+
+    fn is_whitelisted(&self, name: &str) -> bool {
+        let name = name.replace('\\', "/").to_lowercase();
+        self.limit_whitelist.iter().any(|w| !w.is_empty() && name.contains(&w.replace('\\', "/").to_lowercase()))
+    }
+
+    /// 文件超出大小上限且不在白名单中时记录警告并返回 true
+    pub fn exceeds_size_limit(&self, name: &str, size: u64) -> bool {
+        if self.max_file_size == 0 || size <= self.max_file_size || self.is_whitelisted(name) {
+            return false;
+        }
+        log_warn!(
+            "跳过过大的文件 ({} KB，上限 {} KB): {}，如需翻译请加入白名单",
+            size / 1024,
+            self.max_file_size / 1024,
+            name
+        );
+        self.stats.record_skip(SkipReason::Filtered);
+        true
+    }
+
+    /// 待翻译条目数超出上限且不在白名单中时记录警告并返回 true
+    pub fn exceeds_entry_limit(&self, name: &str, entries: usize) -> bool {
+        if self.max_file_entries == 0 || entries <= self.max_file_entries || self.is_whitelisted(name) {
+            return false;
+        }
+        log_warn!(
+            "跳过条目过多的文件 ({} 条，上限 {} 条): {}，如需翻译请加入白名单",
+            entries,
+            self.max_file_entries,
+            name
+        );
+        self.stats.record_skip(SkipReason::Filtered);
+        true
+    }
+}
+
+/// 语言文件在输出目录中的位置: [kubejs/]assets/{modid}/lang/{zh_cn.x}
+pub fn lang_output_path(output_root: &Path, mod_id: &str, target_name: &str, layout: OutputLayout) -> PathBuf {
+    let root = match layout {
+        OutputLayout::ResourcePack => output_root.to_path_buf(),
+        OutputLayout::Kubejs => output_root.join("kubejs"),
+    };
+    root.join("assets").join(mod_id).join("lang").join(target_name)
+}
+
+/// 输出路径模板中可用的占位符
+const TEMPLATE_PLACEHOLDERS: [&str; 5] = ["{output}", "{modid}", "{target}", "{filename}", "{ext}"];
+
+/// 检查输出路径模板：占位符须可识别，且需区分不同模组与文件名，否则多个文件会写到同一位置
+pub fn check_output_template(template: &str) -> Result<(), String> {
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..].find('}').map(|i| start + i + 1).ok_or("模板中的 { 没有闭合")?;
+        let placeholder = &rest[start..end];
+        if !TEMPLATE_PLACEHOLDERS.contains(&placeholder) {
+            return Err(format!("未知的占位符 {}，可用: {}", placeholder, TEMPLATE_PLACEHOLDERS.join(" ")));
+        }
+        rest = &rest[end..];
+    }
+    if template.split(['/', '\\']).any(|c| c == "..") {
+        return Err("模板不能包含 .. (结果须位于输出目录内)".to_string());
+    }
+    if !template.contains("{modid}") {
+        return Err("模板必须包含 {modid}".to_string());
+    }
+    if !template.contains("{filename}") && !template.contains("{target}") {
+        return Err("模板必须包含 {filename} 或 {target}".to_string());
+    }
+    Ok(())
+}
+
+/// 按模板计算语言文件位置，如 `{output}/{modid}/{target}.{ext}`。
+/// {target} 为不含扩展名的译文文件名 (按文件名大小写设置)，结果不在输出目录内时返回 None
+pub fn templated_output_path(template: &str, output_root: &Path, mod_id: &str, target_name: &str) -> Option<PathBuf> {
+    let (stem, ext) = target_name.rsplit_once('.').unwrap_or((target_name, ""));
+    let relative = template
+        .trim_start_matches("{output}")
+        .trim_start_matches(['/', '\\'])
+        .replace("{modid}", mod_id)
+        .replace("{filename}", target_name)
+        .replace("{target}", stem)
+        .replace("{ext}", ext);
+    let relative = Path::new(&relative);
+    let inside = relative.components().all(|c| matches!(c, std::path::Component::Normal(_)));
+    (inside && !relative.as_os_str().is_empty()).then(|| output_root.join(relative))
 }
 
+/// 数据文件在输出数据包中的位置: datapack/data/...
+pub fn datapack_output_path(output_root: &Path, source_path: &str) -> PathBuf {
+    let source_path = source_path.replace('\\', "/");
+    let parts: Vec<&str> = source_path.split('/').collect();
+    let relative = match parts.iter().rposition(|&c| c == "data") {
+        Some(idx) => parts[idx..].join("/"),
+        None => parts.last().copied().unwrap_or_default().to_string(),
+    };
+    output_root.join("datapack").join(relative)
+}
+
+/// 输出数据包缺少 pack.mcmeta 时补上，否则游戏不会加载。版本未知时按 1.20.1 的格式写出
+pub fn write_pack_meta(output_path: &Path, mc_version: Option<McVersion>) -> Result<()> {
+    let Some(pack_root) = output_path.ancestors().find(|p| p.file_name().is_some_and(|n| n == "datapack")) else {
+        return Ok(());
+    };
+    let meta_path = pack_root.join("pack.mcmeta");
+    if !meta_path.exists() {
+        fs::write(
+            meta_path,
+            format!(
+                "{{\n  \"pack\": {{\n    \"pack_format\": {},\n    \"description\": \"MC Translator translations\"\n  }}\n}}\n",
+                mc_version.map_or(15, |v| v.datapack_format())
+            ),
+        )?;
+    }
+    Ok(())
+}
+
+/// 键的分组前缀：item.modid.copper_ingot 与 item.modid.copper_nugget 同属 item.modid.copper
+fn key_group(key: &str) -> String {
+    let mut segments: Vec<&str> = key.split('.').take(3).collect();
+    if let Some(last) = segments.last_mut() {
+        *last = last.split('_').next().unwrap_or(last);
+    }
+    segments.join(".")
+}
+
+/// 按键前缀分组后再切分批次，同组条目尽量放在同一批次中，保证相关名称译法一致；
+/// 超过批次大小或 token 上限 (`token_budget`) 的组才会被拆开
+fn group_into_batches<'a>(
+    items: &[(&'a String, &'a String)],
+    batch_size: usize,
+    token_budget: Option<usize>,
+) -> Vec<Vec<(&'a String, &'a String)>> {
+    let mut groups: Vec<Vec<(&'a String, &'a String)>> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for &(key, value) in items {
+        let group = key_group(key);
+        match index.get(&group) {
+            Some(&i) => groups[i].push((key, value)),
+            None => {
+                index.insert(group, groups.len());
+                groups.push(vec![(key, value)]);
+            }
+        }
+    }
+
+    // 每条原文额外计入引号和分隔符
+    let item_tokens = |text: &str| estimate_tokens(text) + 2;
+    let fits = |len: usize, tokens: usize| len <= batch_size && token_budget.is_none_or(|budget| tokens <= budget);
+    let mut batches: Vec<Vec<(&String, &String)>> = Vec::new();
+    let mut current: Vec<(&String, &String)> = Vec::new();
+    let mut current_tokens = 0;
+    for group in groups {
+        let group_tokens: usize = group.iter().map(|(_, v)| item_tokens(v)).sum();
+        if !current.is_empty() && !fits(current.len() + group.len(), current_tokens + group_tokens) {
+            batches.push(std::mem::take(&mut current));
+            current_tokens = 0;
+        }
+        if fits(group.len(), group_tokens) {
+            current.extend(group);
+            current_tokens += group_tokens;
+            continue;
+        }
+        // 组本身超出限制，逐条切分
+        for item in group {
+            let tokens = item_tokens(item.1);
+            if !current.is_empty() && !fits(current.len() + 1, current_tokens + tokens) {
+                batches.push(std::mem::take(&mut current));
+                current_tokens = 0;
+            }
+            current.push(item);
+            current_tokens += tokens;
+        }
+    }
+    if !current.is_empty() {
+        batches.push(current);
+    }
+    batches
+}
+
+static NUMBER_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[+-]?[\d.,\s]*\d[\d.,\s]*%?$").unwrap());
+static RESOURCE_LOCATION_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^#?[a-z0-9_.\-]+:[a-z0-9_/.\-]+$").unwrap());
+static URL_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(https?://|www\.)\S+$").unwrap());
+
+/// 纯数字、资源 ID (modid:item)、链接以及只由格式代码组成的值原样保留，
+/// 发给模型反而可能被改坏
+fn is_untranslatable(value: &str) -> bool {
+    let value = value.trim();
+    NUMBER_RE.is_match(value)
+        || RESOURCE_LOCATION_RE.is_match(value)
+        || URL_RE.is_match(value)
+        || PLACEHOLDER_RE.replace_all(value, "").trim().is_empty()
+}
+
+/// 待翻译条目按批次顺序排成一份共享的键与原文，批次任务只持有下标范围，不再各自复制原文
+struct Pending {
+    keys: Arc<[String]>,
+    sources: Arc<[String]>,
+    ranges: Vec<Range<usize>>,
+}
+
+impl Pending {
+    fn new(batches: &[Vec<(&String, &String)>]) -> Self {
+        let total = batches.iter().map(Vec::len).sum();
+        let mut keys = Vec::with_capacity(total);
+        let mut sources = Vec::with_capacity(total);
+        let mut ranges = Vec::with_capacity(batches.len());
+        for chunk in batches {
+            let start = keys.len();
+            for &(key, source) in chunk {
+                keys.push(key.clone());
+                sources.push(source.clone());
+            }
+            ranges.push(start..keys.len());
+        }
+        Self { keys: keys.into(), sources: sources.into(), ranges }
+    }
+}
+
+/// 翻译表中的字符串条目，返回原表 (按原顺序，译文直接写回) 与本次翻译失败的键
 pub async fn execute_translation_batches(
-    map: &Map<String, Value>,
+    map: Map<String, Value>,
     client: &OpenAIClient,
     context_id: &str,
     ctx: &TranslationContext,
     token: &CancellationToken,
-) -> Map<String, Value> {
+) -> (Map<String, Value>, Vec<String>) {
     let batch_size = ctx.batch_size;
     let safe_batch_size = if batch_size == 0 { 20 } else { batch_size };
 
-    let pending_items: Vec<(&String, &String)> = map
+    let mut untranslatable = 0;
+    let mut filtered = 0;
+    let mut pending_items: Vec<(&String, &String)> = map
         .iter()
         .filter_map(|(k, v)| {
-            if let Value::String(s) = v {
-                if !s.trim().is_empty() {
-                    return Some((k, s));
+            if let Value::String(s) = v
+                && !s.trim().is_empty()
+            {
+                if is_untranslatable(s) {
+                    untranslatable += 1;
+                    return None;
+                }
+                if let Some(hooks) = &ctx.hooks
+                    && !hooks.key_filter(k, s)
+                {
+                    filtered += 1;
+                    return None;
                 }
+                return Some((k, s));
             }
             None
         })
         .collect();
+    if untranslatable > 0 {
+        log_info!("[{}] 跳过 {} 条无需翻译的条目 (数字、资源 ID、链接或格式代码)", context_id, untranslatable);
+    }
+    if filtered > 0 {
+        log_info!("[{}] 脚本 on_key_filter 排除了 {} 条条目，保留原文", context_id, filtered);
+    }
 
     let total_items = pending_items.len();
-    let mut final_map = map.clone();
-
     if total_items == 0 {
-        return final_map;
+        return (map, Vec::new());
+    }
+
+    // 条目很多的文件逐批记录断点 (批量模式的结果一次性返回，不需要)，重新运行时跳过上次已完成的条目
+    let use_batch_api = ctx.batch_api && !client.is_mock();
+    let checkpoint = (!use_batch_api && total_items.div_ceil(safe_batch_size) >= checkpoint::MIN_BATCHES)
+        .then(|| Arc::new(Checkpoint::open(&ctx.output_root, context_id, &map)));
+    let mut recovered_chunk: Vec<(&String, &String)> = Vec::new();
+    let mut recovered_texts: Vec<String> = Vec::new();
+    if let Some(checkpoint) = &checkpoint {
+        let mut recovered = checkpoint.recover(&map);
+        if !recovered.is_empty() {
+            let (done, remaining): (Vec<_>, Vec<_>) =
+                pending_items.into_iter().partition(|(k, _)| recovered.contains_key(k.as_str()));
+            log_info!("[{}] 从断点恢复 {} 条译文，剩余 {} 条需要翻译", context_id, done.len(), remaining.len());
+            recovered_texts = done.iter().filter_map(|(k, _)| recovered.remove(k.as_str())).collect();
+            recovered_chunk = done;
+            pending_items = remaining;
+        }
+    }
+
+    let token_budget = client.batch_token_budget(context_id);
+    let batches = group_into_batches(&pending_items, safe_batch_size, token_budget);
+    if let Some(budget) = token_budget {
+        let by_count = group_into_batches(&pending_items, safe_batch_size, None).len();
+        if batches.len() > by_count {
+            log_info!(
+                "[{}] 按模型上下文窗口限制每批约 {} tokens，批次由 {} 个调整为 {} 个",
+                context_id,
+                budget,
+                by_count,
+                batches.len()
+            );
+        }
+        let oversized = pending_items.iter().filter(|(_, v)| estimate_tokens(v) > budget).count();
+        if oversized > 0 {
+            log_warn!("[{}] {} 条原文单独超出上下文窗口，可能被截断或失败", context_id, oversized);
+        }
+    }
+
+    // 从断点恢复的条目作为最后一个已完成的批次，同样经过脚本钩子与 QA 检查
+    let total_batches = batches.len();
+    let mut batches = batches;
+    if !recovered_chunk.is_empty() {
+        batches.push(recovered_chunk);
+    }
+    let pending = Pending::new(&batches);
+
+    // 伪翻译不经过网络，直接走普通流程
+    if use_batch_api {
+        let results = execute_with_batch_api(&pending, total_items, client, context_id, ctx, token).await;
+        return assemble_results(map, &pending, results, context_id, ctx);
     }
 
     let mut tasks = JoinSet::new();
+    let mut spawned = 0;
+    concurrency::enqueue(total_batches);
 
-    // 分批并创建异步任务
-    for (batch_idx, chunk) in pending_items.chunks(safe_batch_size).enumerate() {
+    // 分批并创建异步任务，任务共享全部原文，只按下标范围取本批，结果按批次序号回填
+    for (batch_idx, range) in pending.ranges.iter().take(total_batches).enumerate() {
         if token.is_cancelled() {
             break;
         }
 
-        let source_texts: Vec<String> = chunk.iter().map(|(_, v)| v.to_string()).collect();
-        let original_keys: Vec<String> = chunk.iter().map(|(k, _)| (*k).clone()).collect();
+        let range = range.clone();
+        let keys = pending.keys.clone();
+        let sources = pending.sources.clone();
+        let checkpoint = checkpoint.clone();
         
         let client = client.clone();
         let context_id = context_id.to_string();
         let token = token.clone();
-        let permit = ctx.network_semaphore.clone().acquire_owned().await.unwrap();
+        let permit = tokio::select! {
+            slot = concurrency::acquire(ctx.network_semaphore.clone()) => slot,
+            _ = token.cancelled() => break,
+        };
+        spawned += 1;
         
-        let chunk_len = chunk.len();
-        let total_batches = (total_items + safe_batch_size - 1) / safe_batch_size;
+        let breaker = ctx.breaker.clone();
+        let chunk_len = range.len();
+        // 批次标签同时用于日志、进度与调试文件名，便于相互对应
+        let batch_label = format!("{}_batch{}", context_id, batch_idx + 1);
 
         log_info!(
             "[{}] 准备批次 {}/{} ({} 条目)",
-            context_id,
+            batch_label,
             batch_idx + 1,
             total_batches,
             chunk_len
         );
 
-        tasks.spawn(async move {
+        tasks.spawn(ctx.logger.clone().scope(async move {
             let _permit = permit; // 任务结束时自动释放信号量
             
             // 执行翻译请求
-            let result = match client.translate_text_list(source_texts, &context_id, &token).await {
+            let source_texts = &sources[range.clone()];
+            let result = translate_with_split(&client, source_texts, &context_id, batch_label.clone(), &token, 0).await;
+            send_batch_done(&batch_label);
+            let result = match result {
                 Ok(translated_texts) => {
                     if translated_texts.len() == chunk_len {
+                        if let Some(checkpoint) = &checkpoint {
+                            checkpoint.record(&keys[range], source_texts, &translated_texts);
+                        }
                         Some(translated_texts)
                     } else {
-                        log_err!("[{}] 批次 {} 返回数量不匹配，跳过翻译", context_id, batch_idx + 1);
+                        log_err!("[{}] 返回数量不匹配，跳过翻译", batch_label);
                         None
                     }
                 }
+                Err(e) if e.is_cancelled() => None,
                 Err(e) => {
-                    log_err!("[{}] 批次翻译失败，跳过翻译。原因: {}", context_id, e);
+                    log_err!("[{}] 批次翻译失败，跳过翻译。原因: {}", batch_label, describe(&e));
+                    if e.is_fatal() && breaker.trip() {
+                        log_err!("该错误无法通过重试恢复，已终止任务");
+                        token.cancel();
+                    }
                     None
                 }
             };
-            (original_keys, result)
-        });
+
+            if result.is_some() {
+                breaker.record_success();
+            } else if !token.is_cancelled() && breaker.record_failure() {
+                log_err!(
+                    "连续 {} 个批次翻译失败，已自动终止任务。请检查 API Key、额度或服务状态",
+                    breaker.threshold()
+                );
+                token.cancel();
+            }
+            (batch_idx, result)
+        }));
     }
 
-    // 收集所有任务结果并回填到 Map 中
+    if spawned < total_batches {
+        concurrency::dequeue(total_batches - spawned);
+    }
+
+    // 收集所有任务结果，未执行或失败的批次为 None
+    let mut results: Vec<Option<Vec<String>>> = vec![None; total_batches];
     while let Some(res) = tasks.join_next().await {
-        if let Ok((keys, maybe_texts)) = res {
-            match maybe_texts {
-                Some(texts) => {
-                    for (key, text) in keys.iter().zip(texts.iter()) {
-                        final_map.insert(key.clone(), Value::String(text.clone()));
+        if let Ok((batch_idx, maybe_texts)) = res {
+            results[batch_idx] = maybe_texts;
+        }
+    }
+
+    // 所有批次都成功时删除断点，否则保留，下次只补翻失败或未执行的批次
+    if let Some(checkpoint) = checkpoint
+        && !token.is_cancelled()
+        && results.iter().all(Option::is_some)
+        && let Ok(checkpoint) = Arc::try_unwrap(checkpoint)
+    {
+        checkpoint.finish();
+    }
+    if pending.ranges.len() > total_batches {
+        results.push(Some(recovered_texts));
+    }
+
+    assemble_results(map, &pending, results, context_id, ctx)
+}
+
+// 截断后最多拆分的层数，2^3 = 8 份
+const MAX_SPLIT_DEPTH: u32 = 3;
+
+/// 翻译一个批次，若输出被截断则对半拆分后分别重试
+fn translate_with_split<'a>(
+    client: &'a OpenAIClient,
+    texts: &'a [String],
+    context_id: &'a str,
+    batch_label: String,
+    token: &'a CancellationToken,
+    depth: u32,
+) -> Pin<Box<dyn Future<Output = Result<Vec<String>>> + Send + 'a>> {
+    Box::pin(async move {
+        match client.translate_text_list(texts, context_id, &batch_label, token).await {
+            Err(e) if matches!(e, TranslatorError::Truncated) && texts.len() > 1 && depth < MAX_SPLIT_DEPTH => {
+                let mid = texts.len() / 2;
+                log_warn!("[{}] {}，拆分为 {} + {} 条重试", batch_label, e, mid, texts.len() - mid);
+                let (left, right) = texts.split_at(mid);
+                let label_a = format!("{}a", batch_label);
+                let label_b = format!("{}b", batch_label);
+                let left = translate_with_split(client, left, context_id, label_a.clone(), token, depth + 1).await;
+                send_batch_done(&label_a);
+                let mut merged = left?;
+                let right = translate_with_split(client, right, context_id, label_b.clone(), token, depth + 1).await;
+                send_batch_done(&label_b);
+                merged.extend(right?);
+                Ok(merged)
+            }
+            other => other,
+        }
+    })
+}
+
+/// 回填结果：成功批次的条目直接在原表中换成译文，失败批次的条目记入统计，
+/// 按 failed_entries 保留原文或移除，其余条目保持原值
+fn assemble_results(
+    mut map: Map<String, Value>,
+    pending: &Pending,
+    results: Vec<Option<Vec<String>>>,
+    context_id: &str,
+    ctx: &TranslationContext,
+) -> (Map<String, Value>, Vec<String>) {
+    let mut qa_count = 0;
+    let mut translated = 0;
+    let mut failed_keys = Vec::new();
+    for (range, result) in pending.ranges.iter().zip(results) {
+        let keys = &pending.keys[range.clone()];
+        let sources = &pending.sources[range.clone()];
+        match result {
+            Some(texts) => {
+                for ((key, src), text) in keys.iter().zip(sources).zip(texts) {
+                    let text = match &ctx.hooks {
+                        Some(hooks) => hooks.post_translate(key, src, text),
+                        None => text,
+                    };
+                    if report_length_issue(context_id, key, src, &text, ctx.qa_min_ratio, ctx.qa_max_ratio) {
+                        qa_count += 1;
                     }
-                }
-                None => {
-                    for key in keys {
-                        final_map.remove(&key);
+                    if let Some(value) = map.get_mut(key) {
+                        *value = Value::String(text);
                     }
+                    translated += 1;
                 }
             }
+            None => failed_keys.extend(keys.iter().zip(sources).map(|(key, src)| FailedEntry {
+                context: context_id.to_string(),
+                key: key.clone(),
+                source: src.clone(),
+            })),
         }
     }
 
-    final_map
+    RunStats::add(&ctx.stats.translated_entries, translated);
+    RunStats::add(&ctx.stats.failed_entries, failed_keys.len());
+
+    if qa_count > 0 {
+        log_warn!("[{}] {} 条译文长度异常，已加入 QA 列表", context_id, qa_count);
+    }
+
+    if ctx.failed_entries == FailedEntryMode::Drop && !failed_keys.is_empty() {
+        let failed: HashSet<&str> = failed_keys.iter().map(|entry| entry.key.as_str()).collect();
+        map.retain(|key, _| !failed.contains(key.as_str()));
+    }
+    let failed = failed_keys.iter().map(|entry| entry.key.clone()).collect();
+    ctx.stats.record_failed(failed_keys);
+    (map, failed)
+}
+
+/// 批量模式：所有批次打包为一个 Batch API 任务提交，等待完成后返回各批次结果
+async fn execute_with_batch_api(
+    pending: &Pending,
+    total_items: usize,
+    client: &OpenAIClient,
+    context_id: &str,
+    ctx: &TranslationContext,
+    token: &CancellationToken,
+) -> Vec<Option<Vec<String>>> {
+    let batches: Vec<&[String]> = pending.ranges.iter().map(|range| &pending.sources[range.clone()]).collect();
+    let texts: Vec<Vec<&str>> = batches
+        .iter()
+        .map(|chunk| chunk.iter().map(String::as_str).collect())
+        .collect();
+
+    log_info!("[{}] 批量模式：{} 个批次 ({} 条目)", context_id, batches.len(), total_items);
+
+    let results = match batch_api::translate_chunks(client, context_id, &texts, ctx.batch_poll_interval, token).await {
+        Ok(results) => {
+            ctx.breaker.record_success();
+            results
+        }
+        Err(e) if e.is_cancelled() => return vec![None; batches.len()],
+        Err(e) => {
+            log_err!("[{}] 批量任务失败，跳过翻译。原因: {}", context_id, describe(&e));
+            if e.is_fatal() && ctx.breaker.trip() {
+                log_err!("该错误无法通过重试恢复，已终止任务");
+                token.cancel();
+            } else if !token.is_cancelled() && ctx.breaker.record_failure() {
+                log_err!(
+                    "连续 {} 个批次翻译失败，已自动终止任务。请检查 API Key、额度或服务状态",
+                    ctx.breaker.threshold()
+                );
+                token.cancel();
+            }
+            return vec![None; batches.len()];
+        }
+    };
+
+    let mut output = Vec::with_capacity(batches.len());
+    for (batch_idx, (chunk, result)) in batches.iter().zip(results).enumerate() {
+        let batch_label = format!("{}_batch{}", context_id, batch_idx + 1);
+        let texts = match result {
+            Ok(texts) => Some(texts),
+            Err(e) if matches!(e, TranslatorError::Truncated) && chunk.len() > 1 && !token.is_cancelled() => {
+                log_warn!("[{}] 输出被截断，拆分后改用普通请求重试: {}", batch_label, describe(&e));
+                match retry_in_halves(client, chunk, context_id, &batch_label, token).await {
+                    Ok(texts) => Some(texts),
+                    Err(e) if e.is_cancelled() => None,
+                    Err(e) => {
+                        log_err!("[{}] 重试失败，跳过翻译。原因: {}", batch_label, describe(&e));
+                        None
+                    }
+                }
+            }
+            Err(e) => {
+                log_warn!("[{}] 批量结果解析失败: {}", batch_label, describe(&e));
+                None
+            }
+        };
+        output.push(texts.filter(|texts| {
+            let matched = texts.len() == chunk.len();
+            if !matched {
+                log_err!("[{}] 返回数量不匹配，跳过翻译", batch_label);
+            }
+            matched
+        }));
+    }
+    output
+}
+
+/// 批量模式中被截断的批次拆成两半，通过普通请求重新翻译
+async fn retry_in_halves(
+    client: &OpenAIClient,
+    texts: &[String],
+    context_id: &str,
+    label: &str,
+    token: &CancellationToken,
+) -> Result<Vec<String>> {
+    let (left, right) = texts.split_at(texts.len() / 2);
+    let mut merged = Vec::with_capacity(texts.len());
+    for (half, suffix) in [(left, "a"), (right, "b")] {
+        let half_label = format!("{}{}", label, suffix);
+        let result = translate_with_split(client, half, context_id, half_label.clone(), token, 1).await;
+        send_batch_done(&half_label);
+        merged.extend(result?);
+    }
+    Ok(merged)
 }
 
 pub fn extract_mod_id(path: &Path) -> String {
@@ -131,10 +979,10 @@ pub fn extract_mod_id(path: &Path) -> String {
         }
     }
     // support special path like modpack_dir/resources/dsurround/dsurround/data/chat/en_us.lang
-    else if let Some(idx) = parts.iter().position(|x| x == "data") {
-        if idx > 0 {
-            return parts[idx - 1].to_string();
-        }
+    else if let Some(idx) = parts.iter().position(|x| x == "data")
+        && idx > 0
+    {
+        return parts[idx - 1].to_string();
     }
     log_warn!("发现无法解析的模组：{:?}", path);
 
@@ -145,103 +993,91 @@ pub fn extract_mod_id(path: &Path) -> String {
 pub enum FileFormat {
     Json,
     Lang,
+    Lines, // 每行一条的纯文本，如 splashes.txt，键为补零的行号
 }
 
-pub fn get_target_filename(original_name: &str, source_lang: &str, target_lang: &str) -> String {
-    let s_low = source_lang.to_lowercase();
-    let t_low = target_lang.to_lowercase();
+/// 文件名是否为该语言的语言文件 (<lang>.json / <lang>.lang)，不区分大小写，如 en_US.lang
+pub fn is_lang_file(file_name: &str, lang: &str) -> bool {
+    match file_name.rsplit_once('.') {
+        Some((stem, ext)) => {
+            stem.eq_ignore_ascii_case(lang) && (ext.eq_ignore_ascii_case("json") || ext.eq_ignore_ascii_case("lang"))
+        }
+        None => false,
+    }
+}
 
-    let s_mix = format!("{}{}", &s_low[..3], &s_low[3..].to_uppercase());
-    let t_mix = format!("{}{}", &t_low[..3], &t_low[3..].to_uppercase());
+/// 从同一目录下的语言文件中选出要翻译的一个：优先源语言，缺失时按顺序取第一个存在的备选语言
+pub fn pick_source_lang_file<'a>(names: &[&'a str], source_lang: &str, fallbacks: &[String]) -> Option<&'a str> {
+    std::iter::once(source_lang)
+        .chain(fallbacks.iter().map(String::as_str))
+        .find_map(|lang| names.iter().copied().find(|n| is_lang_file(n, lang)))
+}
 
-    if original_name.contains(&s_mix) {
-        original_name.replace(&s_mix, &t_mix)
-    } else if original_name.contains(&s_low) {
-        original_name.replace(&s_low, &t_low)
-    } else {
-        format!("{}_{}", t_low, original_name)
-    }
-}
-
-pub fn sanitize_json_content(content: &str) -> String {
-    let mut result = String::with_capacity(content.len());
-    let mut chars = content.chars().peekable();
-    let mut in_string = false;
-    let mut escape = false;
-
-    // 移除 BOM
-    if let Some('\u{feff}') = chars.peek() {
-        chars.next();
-    }
-
-    while let Some(c) = chars.next() {
-        if !in_string {
-            // 检查开头是否是 // 注释
-            if c == '/' {
-                if let Some(&next_c) = chars.peek() {
-                    if next_c == '/' {
-                        // 发现注释，跳过直到换行
-                        chars.next(); // consume second '/'
-                        while let Some(&comment_c) = chars.peek() {
-                            if comment_c == '\n' || comment_c == '\r' {
-                                break;
-                            }
-                            chars.next();
-                        }
-                        continue;
-                    }
-                }
-            }
-            // 检查 # 注释 (YAML/Properties 风格兼容)
-            if c == '#' {
-                 while let Some(&comment_c) = chars.peek() {
-                    if comment_c == '\n' || comment_c == '\r' {
-                        break;
-                    }
-                    chars.next();
-                }
-                continue;
-            }
+/// 源语言文件，或同目录下没有源语言文件时的备选语言文件 (如只提供 en_gb.json 的模组)
+pub fn is_source_lang_file(path: &Path, source_lang: &str, fallbacks: &[String]) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    if is_lang_file(name, source_lang) {
+        return true;
+    }
+    if !fallbacks.iter().any(|l| is_lang_file(name, l)) {
+        return false;
+    }
+    let siblings: Vec<String> = path
+        .parent()
+        .and_then(|p| fs::read_dir(p).ok())
+        .map(|entries| entries.flatten().map(|e| e.file_name().to_string_lossy().into_owned()).collect())
+        .unwrap_or_default();
+    let names: Vec<&str> = siblings.iter().map(String::as_str).collect();
+    pick_source_lang_file(&names, source_lang, fallbacks) == Some(name)
+}
 
-            if c == '\"' {
-                in_string = true;
-            }
-            result.push(c);
-        } else {
-            // inside string
-            if escape {
-                escape = false;
-                match c {
-                    '\n' => result.push('n'), // 修正：反斜杠后接换行符，视为需要被转换为 \n
-                    '\r' => {}, 
-                    _ => result.push(c),
-                }
-            } else if c == '\\' {
-                escape = true;
-                result.push(c);
-            } else if c == '\"' {
-                in_string = false;
-                result.push(c);
-            } else {
-                // 处理字符串内部的换行符和控制字符
-                match c {
-                    '\n' => result.push_str("\\n"),
-                    '\r' => {}, // 忽略回车
-                    '\t' => result.push_str("\\t"),
-                    _ => {
-                        if c.is_control() {
-                            // 忽略其他控制字符，防止 JSON 解析报错
-                        } else {
-                            result.push(c);
-                        }
-                    },
-                }
-            }
+/// 将名称中的源语言代码替换为目标语言，自动选择大小写 (en_US.lang -> zh_CN.lang，en_US.json -> zh_cn.json)
+pub fn get_target_filename(original_name: &str, source_lang: &str, target_lang: &str) -> String {
+    get_target_filename_cased(original_name, source_lang, target_lang, FilenameCasing::Auto)
+}
+
+pub fn get_target_filename_cased(
+    original_name: &str,
+    source_lang: &str,
+    target_lang: &str,
+    casing: FilenameCasing,
+) -> String {
+    let s_low = source_lang.to_ascii_lowercase();
+    let t_low = target_lang.to_ascii_lowercase();
+
+    let Some(start) = original_name.to_ascii_lowercase().find(&s_low) else {
+        return format!("{}_{}", t_low, original_name);
+    };
+    let end = start + s_low.len();
+    let matched = &original_name[start..end];
+    // 1.13+ 的 .json 只认小写；旧版本 .lang 常用 en_US 形式，地区部分大写
+    let upper_region = match casing {
+        FilenameCasing::Lower => false,
+        FilenameCasing::UpperRegion => true,
+        FilenameCasing::Auto => {
+            !original_name.to_ascii_lowercase().ends_with(".json") && matched.chars().any(|c| c.is_ascii_uppercase())
         }
+    };
+    let target = match t_low.split_once('_') {
+        Some((lang, region)) if upper_region => format!("{}_{}", lang, region.to_ascii_uppercase()),
+        _ => t_low,
+    };
+    format!("{}{}{}", &original_name[..start], target, &original_name[end..])
+}
+
+/// 路径片段 (目录名或文件名) 恰为源语言代码时替换为目标语言，其余片段保持不变
+pub fn localize_segment(segment: &str, source_lang: &str, target_lang: &str) -> String {
+    let stem = segment.split_once('.').map_or(segment, |(stem, _)| stem);
+    if stem.eq_ignore_ascii_case(source_lang) {
+        get_target_filename(segment, source_lang, target_lang)
+    } else {
+        segment.to_string()
     }
-    result
 }
 
+/// 读取语言文件，JSON 无法解析时返回错误，避免把空表当作原文生成空的译文文件
 pub fn read_map_from_file(
     path: &Path,
     format: FileFormat,
@@ -249,35 +1085,79 @@ pub fn read_map_from_file(
     if !path.exists() {
         return Ok(Map::new());
     }
-    match format {
-        FileFormat::Json => {
-            let content = fs::read_to_string(path)?;
-            let sanitized = sanitize_json_content(&content);
-            let json: serde_json::Value =
-                serde_json::from_str(&sanitized).unwrap_or(serde_json::Value::Object(Map::new()));
-            Ok(json.as_object().cloned().unwrap_or_default())
-        }
+    let content = fs::read_to_string(path)?;
+    try_parse_map_content(&content, format)
+        .map_err(|e| TranslatorError::Unparsable(format!("{}: {}", path.display(), e)))
+}
+
+/// 解析语言文件内容，JSON 解析失败时记录警告并返回空表
+pub fn parse_map_content(content: &str, format: FileFormat) -> Map<String, serde_json::Value> {
+    try_parse_map_content(content, format).unwrap_or_else(|e| {
+        log_warn!("{}，按空文件处理", e);
+        Map::new()
+    })
+}
+
+pub fn try_parse_map_content(content: &str, format: FileFormat) -> Result<Map<String, serde_json::Value>, String> {
+    Ok(match format {
+        FileFormat::Json => tolerant_json::parse_object(content)?,
         FileFormat::Lang => {
-            let file = fs::File::open(path)?;
-            let reader = BufReader::new(file);
-            let mut map = Map::new();
-            for line in reader.lines() {
-                let line = line?;
-                if line.trim().is_empty() || line.trim().starts_with('#') {
-                    continue;
-                }
-                if let Some((k, v)) = line.split_once('=') {
-                    map.insert(
-                        k.trim().to_string(),
-                        serde_json::Value::String(v.trim().to_string()),
-                    );
-                }
-            }
-            Ok(map)
+            let Ok(map) = parse_lang_lines(content.lines().map(|line| Ok::<_, Infallible>(line.to_string())));
+            map
+        }
+        FileFormat::Lines => {
+            // 中间的空行由写回时补齐，末尾的空行之后没有条目可以定位，原样保留为空值 (不会被翻译)
+            let lines: Vec<&str> = content.lines().collect();
+            let last_text = lines.iter().rposition(|line| !line.trim().is_empty());
+            lines
+                .into_iter()
+                .enumerate()
+                .filter(|(i, line)| !line.trim().is_empty() || last_text.is_some_and(|last| *i > last))
+                .map(|(i, line)| (format!("{:05}", i), serde_json::Value::String(line.to_string())))
+                .collect()
         }
+    })
+}
+
+/// 行尾有奇数个反斜杠，即最后一个反斜杠未被转义
+fn is_continued(line: &str) -> bool {
+    line.chars().rev().take_while(|&c| c == '\\').count() % 2 == 1
+}
+
+/// 按逻辑行解析 .lang：行尾的反斜杠表示续行 (1.7.10 时期部分模组使用)，下一行去掉缩进后接在后面，
+/// 否则续行会被当作缺少 = 的行丢弃，或在含 = 时被拆成错误的条目
+pub fn parse_lang_lines<E>(lines: impl IntoIterator<Item = std::result::Result<String, E>>) -> std::result::Result<Map<String, Value>, E> {
+    let mut map = Map::new();
+    let mut lines = lines.into_iter();
+    while let Some(line) = lines.next() {
+        let mut line = line?;
+        if line.trim().is_empty() || line.trim().starts_with('#') {
+            continue;
+        }
+        while is_continued(&line) {
+            let Some(next) = lines.next() else { break };
+            line.pop();
+            line.push_str(next?.trim_start());
+        }
+        if let Some((k, v)) = line.split_once('=') {
+            map.insert(k.trim().to_string(), Value::String(v.trim().to_string()));
+        }
+    }
+    Ok(map)
+}
+
+/// 译文中的换行写回 .lang 时的写法：文件已使用 %n 标记 (旧版模组的换行约定) 时沿用，否则写为 \n
+fn lang_newline<'a>(mut values: impl Iterator<Item = &'a Value>) -> &'static str {
+    match values.any(|v| v.as_str().is_some_and(|s| s.contains("%n"))) {
+        true => "%n",
+        false => "\\n",
     }
 }
 
+fn lang_line(key: &str, value: &str, newline: &str) -> String {
+    format!("{}={}", key, value.replace('\r', "").replace('\n', newline))
+}
+
 pub fn write_map_to_file(
     path: &Path,
     map: &Map<String, serde_json::Value>,
@@ -293,19 +1173,185 @@ pub fn write_map_to_file(
             serde_json::to_writer_pretty(file, map)?;
         }
         FileFormat::Lang => {
+            let newline = lang_newline(map.values());
             for (k, v) in map {
                 if let Some(str_val) = v.as_str() {
-                    let escaped_val = str_val.replace('\n', "\\n").replace('\r', ""); // 处理换行符
-                    writeln!(file, "{}={}", k, escaped_val)?;
+                    writeln!(file, "{}", lang_line(k, str_val, newline))?;
                 }
             }
         }
+        FileFormat::Lines => {
+            // 按行号写回，缺失的行号补空行以保留原有的空行
+            let mut lines: Vec<(usize, &str)> = map
+                .iter()
+                .filter_map(|(k, v)| Some((k.parse::<usize>().ok()?, v.as_str()?)))
+                .collect();
+            lines.sort_by_key(|(line_no, _)| *line_no);
+            let mut next_line = 0;
+            for (line_no, str_val) in lines {
+                while next_line < line_no {
+                    writeln!(file)?;
+                    next_line += 1;
+                }
+                writeln!(file, "{}", str_val.replace(['\n', '\r'], " "))?;
+                next_line = line_no + 1;
+            }
+        }
     }
     Ok(())
 }
 
+/// 语言文件中用于空行分隔的分组: item.modid、block.modid
+fn order_group(key: &str) -> &str {
+    match key.match_indices('.').nth(1) {
+        Some((idx, _)) => &key[..idx],
+        None => key,
+    }
+}
+
+/// 按输出顺序设置重排条目后写入；源顺序以 `source_keys` 为准，其余条目保持原位置排在后面
+pub fn write_ordered_map(
+    path: &Path,
+    map: Map<String, Value>,
+    source_keys: &[String],
+    format: FileFormat,
+    order: OutputOrder,
+) -> Result<()> {
+    let source_rank: HashMap<&str, usize> = source_keys.iter().enumerate().map(|(i, k)| (k.as_str(), i)).collect();
+    let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+    match order {
+        OutputOrder::Alphabetical => entries.sort_by(|a, b| a.0.cmp(&b.0)),
+        OutputOrder::Source | OutputOrder::Grouped => {
+            entries.sort_by_key(|(k, _)| source_rank.get(k.as_str()).copied().unwrap_or(usize::MAX));
+        }
+    }
+    if order == OutputOrder::Grouped {
+        // 组按首次出现的顺序排列，组内保持源顺序
+        let mut group_rank: HashMap<String, usize> = HashMap::new();
+        for (k, _) in &entries {
+            let next = group_rank.len();
+            group_rank.entry(order_group(k).to_string()).or_insert(next);
+        }
+        entries.sort_by_key(|(k, _)| group_rank[order_group(k)]);
+    }
+
+    if order != OutputOrder::Grouped || format != FileFormat::Lang {
+        return write_map_to_file(path, &entries.into_iter().collect(), format);
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = fs::File::create(path)?;
+    let newline = lang_newline(entries.iter().map(|(_, v)| v));
+    let mut last_group: Option<&str> = None;
+    for (k, v) in &entries {
+        let Some(str_val) = v.as_str() else { continue };
+        let group = order_group(k);
+        if last_group.is_some_and(|g| g != group) {
+            writeln!(file)?;
+        }
+        last_group = Some(group);
+        writeln!(file, "{}", lang_line(k, str_val, newline))?;
+    }
+    Ok(())
+}
+
+/// 含有至少两个字母组成的单词，%s、%1$d 等格式符不算
+fn has_words(text: &str) -> bool {
+    text.split(|c: char| !c.is_alphabetic()).any(|word| word.chars().nth(1).is_some())
+}
+
+/// 沿用的译文是否可用：空串不可用；开启 retranslate_identical 时，与含字母的原文完全相同的
+/// 视为未翻译的占位 (部分汉化直接复制英文)，纯符号、数字、格式符等本就无需翻译的条目不受影响
+fn is_reusable(ctx: &TranslationContext, source: &Value, reused: &Value) -> bool {
+    let Some(text) = reused.as_str() else {
+        return true;
+    };
+    if text.trim().is_empty() {
+        return false;
+    }
+    !(ctx.retranslate_identical
+        && source.as_str().is_some_and(|src| src.trim() == text.trim() && has_words(src)))
+}
+
+/// 仅复用模式：内置汉化已在更新模式中补入，这里依次从社区汉化包、翻译记忆 (原文完全相同)
+/// 中取译文填入 `base_map`，找不到的条目只计入统计
+fn reuse_existing_translations(
+    pending: Map<String, Value>,
+    base_map: &mut Map<String, Value>,
+    mod_id: &str,
+    client: &OpenAIClient,
+    ctx: &TranslationContext,
+) {
+    let pack = ctx.community_entries.get(mod_id);
+    let (mut reused, mut remaining, mut remaining_chars, mut placeholders) = (0, 0, 0, 0);
+    for (key, value) in pending {
+        let community = pack.and_then(|p| p.get(&key));
+        let community = match community {
+            Some(translation) if !is_reusable(ctx, &value, translation) => {
+                placeholders += 1;
+                None
+            }
+            other => other,
+        };
+        let found = community
+            .cloned()
+            .or_else(|| value.as_str().and_then(|text| client.recall(text)).map(Value::String));
+        match found {
+            Some(translation) => {
+                base_map.insert(key, translation);
+                reused += 1;
+            }
+            None => {
+                remaining += 1;
+                remaining_chars += value.as_str().map_or(0, |text| text.chars().count());
+            }
+        }
+    }
+    RunStats::add(&ctx.stats.reused_entries, reused);
+    RunStats::add(&ctx.stats.untranslated_entries, remaining);
+    RunStats::add(&ctx.stats.untranslated_chars, remaining_chars);
+    if placeholders > 0 {
+        log_info!("[{}] 社区汉化中有 {} 个条目与原文相同或为空，视为未翻译", ctx.mod_label(mod_id), placeholders);
+    }
+    if remaining > 0 {
+        log_info!(
+            "[{}] 复用 {} 条，剩余 {} 条 ({} 字符) 需要翻译",
+            ctx.mod_label(mod_id),
+            reused,
+            remaining,
+            remaining_chars
+        );
+    }
+}
+
+/// 取出值为空或只含空白的条目，它们不参与翻译与复用，写出前由 apply_empty_values 按设置处理
+fn take_empty_values(src_map: &mut Map<String, Value>) -> Map<String, Value> {
+    let empty_keys: Vec<String> =
+        src_map.iter().filter(|(_, v)| v.as_str().is_some_and(|s| s.trim().is_empty())).map(|(k, _)| k.clone()).collect();
+    empty_keys.into_iter().filter_map(|k| src_map.remove(&k).map(|v| (k, v))).collect()
+}
+
+fn apply_empty_values(base_map: &mut Map<String, Value>, empty: &Map<String, Value>, mode: EmptyValueMode) {
+    for (k, v) in empty {
+        match mode {
+            EmptyValueMode::Keep => {
+                base_map.insert(k.clone(), v.clone());
+            }
+            EmptyValueMode::Drop => {
+                base_map.remove(k);
+            }
+            EmptyValueMode::KeyName => {
+                base_map.insert(k.clone(), Value::String(k.clone()));
+            }
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn core_translation_pipeline(
-    src_map: serde_json::Map<String, serde_json::Value>,
+    mut src_map: serde_json::Map<String, serde_json::Value>,
     mod_id: &str,
     original_filename: &str,
     output_root: &Path,
@@ -314,22 +1360,28 @@ pub async fn core_translation_pipeline(
     format: FileFormat,
     builtin_map: Option<serde_json::Map<String, serde_json::Value>>,
     token: &CancellationToken,
-) -> anyhow::Result<()> {
-    let skip_existing = ctx.skip_existing;
+) -> Result<()> {
     let update_existing = ctx.update_existing;
-    // 构造标准输出路径: output/assets/{modid}/lang/{zh_cn.x}
-    let target_name = get_target_filename(original_filename, &ctx.source_lang, &ctx.target_lang);
-    let final_path = output_root
-        .join("assets")
-        .join(mod_id)
-        .join("lang")
-        .join(&target_name);
-
-    if !update_existing && skip_existing && final_path.exists() {
-        log_info!("跳过已存在的文件: {:?}", final_path);
+    let target_name = ctx.output_filename(original_filename, format);
+    let format = ctx.output_format(format);
+    let final_path = ctx.lang_output_path(output_root, mod_id, &target_name);
+
+    if !update_existing && ctx.skip_existing_output(&final_path) {
         return Ok(());
     }
 
+    let source_keys: Vec<String> = src_map.keys().cloned().collect();
+    let source_snapshot = src_map.clone();
+    let empty_values = take_empty_values(&mut src_map);
+    if !empty_values.is_empty() {
+        RunStats::add(&ctx.stats.empty_entries, empty_values.len());
+        let action = match ctx.empty_values {
+            EmptyValueMode::Keep => "原样保留",
+            EmptyValueMode::Drop => "不写入输出",
+            EmptyValueMode::KeyName => "以键名作为值",
+        };
+        log_info!("[{}] {} 个条目的值为空或只含空白，{}", ctx.mod_label(mod_id), empty_values.len(), action);
+    }
     let (map_to_translate, mut base_map) = if update_existing {
         // [更新模式]
         let existing_map = read_map_from_file(&final_path, format).unwrap_or_default();
@@ -337,11 +1389,27 @@ pub async fn core_translation_pipeline(
 
         let mut pending = serde_json::Map::new();
         let mut recovered_from_builtin = 0;
+        let mut builtin_placeholders = 0;
 
         // 这里需要修改 base_map，因为我们要把 built-in 的内容补充进去
         // 但 existing_map 是只读的，所以我们要先 clone 一份作为 base
         let mut final_base_map = existing_map.clone();
 
+        // 与上次的原文快照比较，原文改动过的条目丢弃旧译文重新翻译
+        if let Some(snapshot) = raw_content::load(output_root, mod_id, original_filename) {
+            let changed: Vec<String> = raw_content::changed_keys(&snapshot, &src_map)
+                .into_iter()
+                .filter(|k| final_base_map.remove(k).is_some())
+                .collect();
+            let (failed, changed): (Vec<_>, Vec<_>) = changed.into_iter().partition(|k| snapshot[k].is_null());
+            if !changed.is_empty() {
+                log_info!("检测到 {} 个条目原文有改动，将重新翻译 (ModID: {})", changed.len(), mod_id);
+            }
+            if !failed.is_empty() {
+                log_info!("{} 个条目上次翻译失败、以原文写入，将重新翻译 (ModID: {})", failed.len(), mod_id);
+            }
+        }
+
         for (k, v) in &src_map {
             // 如果输出文件里已经有了，跳过
             if final_base_map.contains_key(k) {
@@ -349,22 +1417,31 @@ pub async fn core_translation_pipeline(
             }
 
             // 如果输出文件没有，检查内置汉化
-            if let Some(builtin_val) = builtin_entries.get(k) {
+            match builtin_entries.get(k) {
                 // 有内置汉化，直接使用，不重新翻译
-                final_base_map.insert(k.clone(), builtin_val.clone());
-                recovered_from_builtin += 1;
-            } else {
-                // 既没有输出，也没有内置，加入待翻译队列
-                pending.insert(k.clone(), v.clone());
+                Some(builtin_val) if is_reusable(&ctx, v, builtin_val) => {
+                    final_base_map.insert(k.clone(), builtin_val.clone());
+                    recovered_from_builtin += 1;
+                }
+                Some(_) => {
+                    builtin_placeholders += 1;
+                    pending.insert(k.clone(), v.clone());
+                }
+                None => {
+                    // 既没有输出，也没有内置，加入待翻译队列
+                    pending.insert(k.clone(), v.clone());
+                }
             }
         }
 
         if pending.is_empty() && recovered_from_builtin == 0 {
             log_info!("无新增条目，无需更新: {:?}", final_path);
+            raw_content::save(output_root, mod_id, original_filename, &source_snapshot)?;
             return Ok(());
         }
 
         if recovered_from_builtin > 0 {
+            RunStats::add(&ctx.stats.reused_entries, recovered_from_builtin);
             log_info!(
                 "从内置汉化中恢复了 {} 个条目 (ModID: {})",
                 recovered_from_builtin,
@@ -372,50 +1449,242 @@ pub async fn core_translation_pipeline(
             );
         }
 
+        if builtin_placeholders > 0 {
+            log_info!("内置汉化中有 {} 个条目与原文相同或为空，将重新翻译 (ModID: {})", builtin_placeholders, mod_id);
+        }
+
         if !pending.is_empty() {
              log_info!(
                 "增量更新检测到 {} 个新条目 (ModID: {})",
                 pending.len(),
                 mod_id
             );
-
-            // [保存增量原始内容]
-            let raw_dir = output_root.join("raw_content");
-            if !raw_dir.exists() {
-                fs::create_dir_all(&raw_dir)?;
-            }
-            let raw_path = raw_dir.join(format!("{}_{}", mod_id, original_filename));
-            let raw_file = fs::File::create(&raw_path)?;
-            serde_json::to_writer_pretty(raw_file, &pending)?;
-            log_info!("已备份增量原始内容: {:?}", raw_path);
         }
 
         (pending, final_base_map)
+    } else if ctx.reuse_builtin
+        && let Some(builtin_entries) = builtin_map
+    {
+        // [全量模式] 沿用模组自带的译文，只翻译缺失或不可用的条目
+        let (reused, pending): (Vec<_>, Vec<_>) = src_map
+            .into_iter()
+            .partition(|(k, v)| builtin_entries.get(k).is_some_and(|b| is_reusable(&ctx, v, b)));
+        let placeholders = pending.iter().filter(|(k, _)| builtin_entries.contains_key(k)).count();
+        if placeholders > 0 {
+            log_info!("内置汉化中有 {} 个条目与原文相同或为空，将重新翻译 (ModID: {})", placeholders, mod_id);
+        }
+        let base_map: serde_json::Map<String, serde_json::Value> =
+            reused.into_iter().filter_map(|(k, _)| builtin_entries.get(&k).map(|b| (k, b.clone()))).collect();
+        if !base_map.is_empty() {
+            RunStats::add(&ctx.stats.reused_entries, base_map.len());
+            log_info!(
+                "沿用内置汉化中的 {} 个条目，其余 {} 个条目需要翻译 (ModID: {})",
+                base_map.len(),
+                pending.len(),
+                mod_id
+            );
+        }
+        (pending.into_iter().collect(), base_map)
     } else {
         // [全量模式]
         (src_map, serde_json::Map::new())
     };
 
-    let translated_part =
-        execute_translation_batches(&map_to_translate, client, mod_id, &ctx, token).await;
+    if ctx.reuse_only {
+        reuse_existing_translations(map_to_translate, &mut base_map, mod_id, client, &ctx);
+        if !base_map.is_empty() {
+            apply_empty_values(&mut base_map, &empty_values, ctx.empty_values);
+            color_codes::normalize_map(&mut base_map, ctx.color_codes);
+            punctuation::normalize_map(&mut base_map, ctx.punctuation);
+            write_ordered_map(&final_path, base_map, &source_keys, format, ctx.output_order)?;
+            raw_content::save(output_root, mod_id, original_filename, &source_snapshot)?;
+            log_info!("复用完成 [{}]: {:?}", ctx.mod_label(mod_id), final_path);
+        }
+        return Ok(());
+    }
+
+    if ctx.exceeds_entry_limit(&format!("{}/{}", mod_id, original_filename), map_to_translate.len()) {
+        return Ok(());
+    }
+
+    let (translated_part, failed_keys) =
+        execute_translation_batches(map_to_translate, client, mod_id, &ctx, token).await;
 
     if token.is_cancelled() {
         log_warn!("任务取消，放弃保存: {:?}", final_path);
         return Ok(());
     }
 
+    // 失败的条目在快照中记为 null，下次更新时视为原文改动，即使已以原文写入也会重新翻译
+    let mut source_snapshot = source_snapshot;
+    for key in &failed_keys {
+        if let Some(value) = source_snapshot.get_mut(key) {
+            *value = Value::Null;
+        }
+    }
+
     for (k, v) in translated_part {
         base_map.insert(k, v);
     }
 
-    write_map_to_file(&final_path, &base_map, format)?;
+    apply_empty_values(&mut base_map, &empty_values, ctx.empty_values);
+    color_codes::normalize_map(&mut base_map, ctx.color_codes);
+    punctuation::normalize_map(&mut base_map, ctx.punctuation);
+    write_ordered_map(&final_path, base_map, &source_keys, format, ctx.output_order)?;
+    raw_content::save(output_root, mod_id, original_filename, &source_snapshot)?;
 
     let action_str = if update_existing && final_path.exists() {
         "更新"
     } else {
         "生成"
     };
-    log_info!("{}完成 (ModID: {}): {:?}", action_str, mod_id, final_path);
+    log_info!("{}完成 [{}]: {:?}", action_str, ctx.mod_label(mod_id), final_path);
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_lang(content: &str) -> Map<String, Value> {
+        try_parse_map_content(content, FileFormat::Lang).unwrap()
+    }
+
+    #[test]
+    fn joins_lines_ending_in_odd_backslashes() {
+        let map = parse_lang("tile.a.desc=First line \\\n    second line\nitem.b=Plain\n");
+        assert_eq!(map["tile.a.desc"], "First line second line");
+        assert_eq!(map["item.b"], "Plain");
+
+        let map = parse_lang("a=x\\\\\\\n  y\n");
+        assert_eq!(map["a"], "x\\\\y");
+    }
+
+    #[test]
+    fn keeps_escaped_trailing_backslash() {
+        let map = parse_lang("a=C:\\\\\nb=next\n");
+        assert_eq!(map["a"], "C:\\\\");
+        assert_eq!(map["b"], "next");
+    }
+
+    #[test]
+    fn continuation_on_last_line_keeps_entry() {
+        let map = parse_lang("a=one\nb=dangling\\");
+        assert_eq!(map["a"], "one");
+        assert_eq!(map["b"], "dangling\\");
+    }
+
+    #[test]
+    fn continuation_does_not_split_on_equals() {
+        let map = parse_lang("a=x \\\ny=z\n");
+        assert_eq!(map.len(), 1);
+        assert_eq!(map["a"], "x y=z");
+    }
+
+    #[test]
+    fn round_trips_percent_n_newlines() {
+        let path = std::env::temp_dir().join(format!("mc_translator_lang_{}.lang", std::process::id()));
+        let mut map = Map::new();
+        map.insert("a".into(), Value::String("第一行%n第二行".into()));
+        map.insert("b".into(), Value::String("上\n下".into()));
+        write_map_to_file(&path, &map, FileFormat::Lang).unwrap();
+        let written = fs::read_to_string(&path);
+        let reread = read_map_from_file(&path, FileFormat::Lang);
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(written.unwrap(), "a=第一行%n第二行\nb=上%n下\n");
+        let reread = reread.unwrap();
+        assert_eq!(reread["a"], "第一行%n第二行");
+        assert_eq!(reread["b"], "上%n下");
+    }
+
+    #[test]
+    fn lines_round_trip_keeps_blank_lines() {
+        let content = "first\n\nsecond\n\n\n";
+        let map = try_parse_map_content(content, FileFormat::Lines).unwrap();
+        assert_eq!(map.keys().collect::<Vec<_>>(), ["00000", "00002", "00003", "00004"]);
+
+        let path = std::env::temp_dir().join(format!("mc_translator_lines_{}.txt", std::process::id()));
+        write_map_to_file(&path, &map, FileFormat::Lines).unwrap();
+        let written = fs::read_to_string(&path);
+        let _ = fs::remove_file(&path);
+        assert_eq!(written.unwrap(), content);
+
+        assert!(try_parse_map_content(" \n\n", FileFormat::Lines).unwrap().is_empty());
+    }
+
+    #[test]
+    fn writes_escaped_newlines_without_percent_n() {
+        let mut map = Map::new();
+        map.insert("a".into(), Value::String("上\r\n下".into()));
+        let newline = lang_newline(map.values());
+        assert_eq!(lang_line("a", map["a"].as_str().unwrap(), newline), "a=上\\n下");
+    }
+
+    fn owned(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    fn batch_keys(items: &[(String, String)], batch_size: usize, token_budget: Option<usize>) -> Vec<Vec<&str>> {
+        let refs: Vec<(&String, &String)> = items.iter().map(|(k, v)| (k, v)).collect();
+        group_into_batches(&refs, batch_size, token_budget)
+            .into_iter()
+            .map(|batch| batch.into_iter().map(|(k, _)| k.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn key_group_uses_first_word_of_third_segment() {
+        assert_eq!(key_group("item.modid.copper_ingot"), "item.modid.copper");
+        assert_eq!(key_group("item.modid.copper_nugget.desc"), "item.modid.copper");
+        assert_eq!(key_group("itemGroup.modid"), "itemGroup.modid");
+    }
+
+    #[test]
+    fn shared_prefixes_land_in_same_batch() {
+        let items = owned(&[
+            ("item.m.copper_ingot", "Copper Ingot"),
+            ("item.m.iron_ingot", "Iron Ingot"),
+            ("item.m.copper_nugget", "Copper Nugget"),
+            ("item.m.iron_nugget", "Iron Nugget"),
+        ]);
+        assert_eq!(
+            batch_keys(&items, 2, None),
+            [["item.m.copper_ingot", "item.m.copper_nugget"], ["item.m.iron_ingot", "item.m.iron_nugget"]]
+        );
+    }
+
+    #[test]
+    fn oversized_group_is_split_by_batch_size() {
+        let items = owned(&[
+            ("block.m.stone", "Stone"),
+            ("item.m.gear_a", "A"),
+            ("item.m.gear_b", "B"),
+            ("item.m.gear_c", "C"),
+            ("item.m.gear_d", "D"),
+            ("item.m.gear_e", "E"),
+        ]);
+        let batches = batch_keys(&items, 2, None);
+        assert_eq!(
+            batches,
+            [
+                vec!["block.m.stone"],
+                vec!["item.m.gear_a", "item.m.gear_b"],
+                vec!["item.m.gear_c", "item.m.gear_d"],
+                vec!["item.m.gear_e"],
+            ]
+        );
+        assert!(batches.iter().all(|batch| batch.len() <= 2));
+    }
+
+    #[test]
+    fn token_budget_splits_groups() {
+        let long = "word ".repeat(40);
+        let items = owned(&[("item.m.a", &long), ("item.m.a_b", &long), ("item.m.a_c", &long)]);
+        let by_count = batch_keys(&items, 20, None);
+        assert_eq!(by_count.len(), 1);
+        let budget = estimate_tokens(&long) + 2;
+        assert_eq!(batch_keys(&items, 20, Some(budget)).len(), 3);
+    }
+}
@@ -0,0 +1,102 @@
+// 网络并发状态：统计进行中的请求、等待许可的批次与遭遇限流的请求，供界面显示
+use crate::message::send_concurrency;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+static CAPACITY: AtomicUsize = AtomicUsize::new(0);
+static IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+static QUEUED: AtomicUsize = AtomicUsize::new(0);
+static THROTTLED: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ConcurrencySnapshot {
+    pub in_flight: usize,
+    pub capacity: usize,
+    pub queued: usize,
+    pub throttled: usize, // 收到 429 后正在等待重试的请求
+}
+
+impl ConcurrencySnapshot {
+    /// 所有许可都在使用且仍有批次排队，瓶颈在并发设置
+    pub fn saturated(&self) -> bool {
+        self.capacity > 0 && self.in_flight >= self.capacity && self.queued > 0
+    }
+}
+
+fn report() {
+    send_concurrency(ConcurrencySnapshot {
+        in_flight: IN_FLIGHT.load(Ordering::Relaxed),
+        capacity: CAPACITY.load(Ordering::Relaxed),
+        queued: QUEUED.load(Ordering::Relaxed),
+        throttled: THROTTLED.load(Ordering::Relaxed),
+    });
+}
+
+/// 并行任务各有自己的信号量，运行期间把各自的上限计入总数
+pub struct Capacity(usize);
+
+impl Capacity {
+    pub fn register(max: usize) -> Self {
+        CAPACITY.fetch_add(max, Ordering::Relaxed);
+        report();
+        Self(max)
+    }
+}
+
+impl Drop for Capacity {
+    fn drop(&mut self) {
+        CAPACITY.fetch_sub(self.0, Ordering::Relaxed);
+        report();
+    }
+}
+
+pub fn enqueue(batches: usize) {
+    QUEUED.fetch_add(batches, Ordering::Relaxed);
+    report();
+}
+
+/// 取消时撤回尚未开始的批次
+pub fn dequeue(batches: usize) {
+    QUEUED.fetch_sub(batches, Ordering::Relaxed);
+    report();
+}
+
+/// 持有期间计为进行中的请求
+pub struct Slot {
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        IN_FLIGHT.fetch_sub(1, Ordering::Relaxed);
+        report();
+    }
+}
+
+/// 等待网络许可，取得后该批次从排队转为进行中
+pub async fn acquire(semaphore: Arc<Semaphore>) -> Slot {
+    let permit = semaphore.acquire_owned().await.unwrap();
+    QUEUED.fetch_sub(1, Ordering::Relaxed);
+    IN_FLIGHT.fetch_add(1, Ordering::Relaxed);
+    report();
+    Slot { _permit: permit }
+}
+
+/// 持有期间计为受服务商限流的请求
+pub struct Throttled;
+
+impl Throttled {
+    pub fn begin() -> Self {
+        THROTTLED.fetch_add(1, Ordering::Relaxed);
+        report();
+        Self
+    }
+}
+
+impl Drop for Throttled {
+    fn drop(&mut self) {
+        THROTTLED.fetch_sub(1, Ordering::Relaxed);
+        report();
+    }
+}
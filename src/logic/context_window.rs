@@ -0,0 +1,57 @@
+// 模型上下文窗口：按窗口大小限制单个批次的原文长度，避免请求超出上下文后失败
+use std::collections::BTreeMap;
+
+/// 为输出和消息格式预留的 token
+const RESERVE_TOKENS: usize = 512;
+/// 译文 token 约为原文的 1.5 倍，批次需要同时容纳原文与译文
+const OUTPUT_RATIO_NUM: usize = 3;
+const OUTPUT_RATIO_DEN: usize = 2;
+
+/// 默认的上下文窗口表，键为模型名中包含的片段 (小写)
+pub fn default_table() -> BTreeMap<String, usize> {
+    [
+        ("gpt-3.5-turbo", 16_385),
+        ("gpt-4-turbo", 128_000),
+        ("gpt-4o", 128_000),
+        ("gpt-4.1", 1_047_576),
+        ("gpt-5", 400_000),
+        ("o3", 200_000),
+        ("o4-mini", 200_000),
+        ("deepseek", 65_536),
+        ("qwen", 32_768),
+        ("glm-4", 128_000),
+        ("gemini", 1_048_576),
+        ("moonshot-v1-8k", 8_192),
+        ("moonshot-v1-32k", 32_768),
+        ("moonshot-v1-128k", 131_072),
+    ]
+    .into_iter()
+    .map(|(model, tokens)| (model.to_string(), tokens))
+    .collect()
+}
+
+/// 取表中与模型名匹配的最长片段对应的窗口大小，没有匹配时返回 None
+pub fn lookup(table: &BTreeMap<String, usize>, model: &str) -> Option<usize> {
+    let model = model.to_lowercase();
+    table
+        .iter()
+        .filter(|(pattern, tokens)| **tokens > 0 && model.contains(&pattern.to_lowercase()))
+        .max_by_key(|(pattern, _)| pattern.len())
+        .map(|(_, tokens)| *tokens)
+}
+
+/// 扣除系统提示词等固定开销后，单个批次原文可用的 token 数
+pub fn input_budget(window: usize, fixed_tokens: usize) -> usize {
+    let available = window.saturating_sub(fixed_tokens + RESERVE_TOKENS);
+    (available * OUTPUT_RATIO_DEN / (OUTPUT_RATIO_DEN + OUTPUT_RATIO_NUM)).max(1)
+}
+
+/// 原文及其译文占用的 token 估算
+fn round_trip_tokens(input_tokens: usize) -> usize {
+    input_tokens + input_tokens * OUTPUT_RATIO_NUM / OUTPUT_RATIO_DEN
+}
+
+/// 在窗口内放入原文与译文后，剩余可用于参考译文的 token
+pub fn remaining_tokens(window: usize, fixed_tokens: usize, input_tokens: usize) -> usize {
+    window.saturating_sub(fixed_tokens + RESERVE_TOKENS + round_trip_tokens(input_tokens))
+}
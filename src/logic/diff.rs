@@ -0,0 +1,104 @@
+// 对比两份译文（或译文与源模组），找出缺失、多余和不一致的条目
+use crate::logic::merge::read_lang_files;
+use crate::logic::error::Result;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+#[derive(Debug, Clone)]
+pub struct DiffEntry {
+    pub mod_id: String,
+    pub key: String,
+    pub a: String,
+    pub b: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DiffReport {
+    pub source_a: String,
+    pub source_b: String,
+    pub only_a: Vec<DiffEntry>,
+    pub only_b: Vec<DiffEntry>,
+    pub different: Vec<DiffEntry>,
+}
+
+fn value_text(v: &Value) -> String {
+    v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string())
+}
+
+// 同一模组的 .json 与 .lang 合并为一张表，按模组对比
+fn load_by_mod(source: &str, lang: &str) -> Result<BTreeMap<String, Map<String, Value>>> {
+    let mut mods: BTreeMap<String, Map<String, Value>> = BTreeMap::new();
+    for (file, _, map) in read_lang_files(Path::new(source), lang)? {
+        let mod_id = file.split_once('/').map(|(m, _)| m).unwrap_or(&file).to_string();
+        mods.entry(mod_id).or_default().extend(map);
+    }
+    Ok(mods)
+}
+
+/// 对比 A 与 B。两侧语言不同（如译文对比源模组）时只比较键，不比较取值
+pub fn diff_sources(a: &str, lang_a: &str, b: &str, lang_b: &str) -> Result<DiffReport> {
+    let mods_a = load_by_mod(a, lang_a)?;
+    let mods_b = load_by_mod(b, lang_b)?;
+    let compare_values = lang_a.eq_ignore_ascii_case(lang_b);
+    let empty = Map::new();
+
+    let mut report = DiffReport {
+        source_a: format!("{} ({})", a, lang_a),
+        source_b: format!("{} ({})", b, lang_b),
+        ..Default::default()
+    };
+    let all_mods: std::collections::BTreeSet<&String> = mods_a.keys().chain(mods_b.keys()).collect();
+    for mod_id in all_mods {
+        let map_a = mods_a.get(mod_id).unwrap_or(&empty);
+        let map_b = mods_b.get(mod_id).unwrap_or(&empty);
+        for (key, va) in map_a {
+            let entry = |b: String| DiffEntry { mod_id: mod_id.clone(), key: key.clone(), a: value_text(va), b };
+            match map_b.get(key) {
+                None => report.only_a.push(entry(String::new())),
+                Some(vb) if compare_values && va != vb => report.different.push(entry(value_text(vb))),
+                Some(_) => {}
+            }
+        }
+        for (key, vb) in map_b {
+            if !map_a.contains_key(key) {
+                report.only_b.push(DiffEntry {
+                    mod_id: mod_id.clone(),
+                    key: key.clone(),
+                    a: String::new(),
+                    b: value_text(vb),
+                });
+            }
+        }
+    }
+    Ok(report)
+}
+
+impl DiffReport {
+    /// 导出为 CSV（UTF-8 BOM，便于 Excel 直接打开）
+    pub fn export_csv(&self, path: &Path) -> Result<()> {
+        fn field(s: &str) -> String {
+            format!("\"{}\"", s.replace('"', "\"\""))
+        }
+        let mut out = format!(
+            "\u{feff}A,{}\nB,{}\n\n类型,模组,键,A,B\n",
+            field(&self.source_a),
+            field(&self.source_b)
+        );
+        let sections = [("仅 A", &self.only_a), ("仅 B", &self.only_b), ("不同", &self.different)];
+        for (kind, entries) in sections {
+            for e in entries {
+                out.push_str(&format!(
+                    "{},{},{},{},{}\n",
+                    kind,
+                    field(&e.mod_id),
+                    field(&e.key),
+                    field(&e.a),
+                    field(&e.b)
+                ));
+            }
+        }
+        std::fs::write(path, out)?;
+        Ok(())
+    }
+}
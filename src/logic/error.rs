@@ -0,0 +1,168 @@
+use crate::logic::i18n::tr;
+use reqwest::StatusCode;
+use std::fmt;
+use std::io;
+
+pub type Result<T, E = TranslatorError> = std::result::Result<T, E>;
+
+/// 逻辑层的错误分类，界面、命令行与服务端据此分别处理：
+/// 如 API Key 无效时终止整次运行，模型输出格式错误时只跳过该批次
+#[derive(Debug)]
+pub enum TranslatorError {
+    Network(String), // 连接失败、超时或服务端错误，重试耗尽后返回
+    Auth(String), // API Key 无效或没有访问权限
+    RateLimited(String), // 请求过于频繁，重试耗尽后仍被限流
+    QuotaExhausted(String),
+    ModelNotFound(String),
+    EndpointNotFound(String), // 接口路径 404，多为 API 地址或 chat_path 填写错误
+    ModelOutputInvalid(String), // 模型未按要求返回 JSON 数组
+    Truncated, // 输出因长度上限被截断，可拆分批次后重试
+    Unparsable(String), // 输入文件有语法错误
+    Io(io::Error),
+    Cancelled, // 用户取消或任务被终止，不记为失败
+    Other(String),
+}
+
+impl TranslatorError {
+    /// 根据 HTTP 状态码与响应内容归类，无法归类时返回 None。
+    /// 404 时只有响应提到模型才视为模型不存在，否则视为接口路径错误，并附上请求地址
+    pub fn from_response(status: StatusCode, url: &str, body: &str) -> Option<Self> {
+        let lower = body.to_lowercase();
+        let detail = format!("HTTP {}: {}", status, body);
+        match status {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Some(Self::Auth(detail)),
+            StatusCode::NOT_FOUND if lower.contains("model") => {
+                Some(Self::ModelNotFound(format!("HTTP {} {}: {}", status, url, body)))
+            }
+            StatusCode::NOT_FOUND => Some(Self::EndpointNotFound(format!("HTTP {} {}: {}", status, url, body))),
+            StatusCode::PAYMENT_REQUIRED => Some(Self::QuotaExhausted(detail)),
+            StatusCode::TOO_MANY_REQUESTS if lower.contains("quota") || lower.contains("balance") => {
+                Some(Self::QuotaExhausted(detail))
+            }
+            StatusCode::TOO_MANY_REQUESTS => Some(Self::RateLimited(detail)),
+            StatusCode::BAD_REQUEST if lower.contains("model_not_found") || lower.contains("does not exist") => {
+                Some(Self::ModelNotFound(detail))
+            }
+            status if status.is_server_error() => Some(Self::Network(detail)),
+            _ => None,
+        }
+    }
+
+    /// 与具体批次无关、后续请求也必然失败的错误，应终止整次运行
+    pub fn is_fatal(&self) -> bool {
+        matches!(self, Self::Auth(_) | Self::QuotaExhausted(_) | Self::ModelNotFound(_) | Self::EndpointNotFound(_))
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, Self::Cancelled)
+    }
+
+    pub fn is_unparsable(&self) -> bool {
+        matches!(self, Self::Unparsable(_))
+    }
+
+    /// 在错误信息末尾附上服务商的请求 ID，不改变分类
+    pub fn with_request_id(self, request_id: Option<&str>) -> Self {
+        let Some(id) = request_id else {
+            return self;
+        };
+        let append = |detail: String| format!("{} (请求 ID: {})", detail, id);
+        match self {
+            Self::Network(d) => Self::Network(append(d)),
+            Self::Auth(d) => Self::Auth(append(d)),
+            Self::RateLimited(d) => Self::RateLimited(append(d)),
+            Self::QuotaExhausted(d) => Self::QuotaExhausted(append(d)),
+            Self::ModelNotFound(d) => Self::ModelNotFound(append(d)),
+            Self::EndpointNotFound(d) => Self::EndpointNotFound(append(d)),
+            Self::ModelOutputInvalid(d) => Self::ModelOutputInvalid(append(d)),
+            Self::Other(d) => Self::Other(append(d)),
+            other => other,
+        }
+    }
+
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            Self::Auth(_) => Some(tr("请检查 API Key 是否填写正确、是否已过期，以及是否与 API 地址对应的服务商匹配")),
+            Self::RateLimited(_) => Some(tr("请求过于频繁，可降低网络并发数或增大重试间隔后使用“更新翻译”补翻")),
+            Self::ModelNotFound(_) => Some(tr("请检查模型名称与 API 地址，可点击“检查 & 刷新”获取可用模型列表")),
+            Self::EndpointNotFound(_) => Some(tr("请检查 API 地址与对话接口路径 (chat_path) 是否与服务商文档一致")),
+            Self::QuotaExhausted(_) => Some(tr("账户额度或余额已用尽，请充值或更换 API Key 后使用“更新翻译”补翻")),
+            Self::ModelOutputInvalid(_) => Some(tr("模型未按要求返回 JSON 数组，可减小批次大小、更换模型或检查自定义提示词")),
+            Self::Io(e) if e.kind() == io::ErrorKind::PermissionDenied => {
+                Some(tr("没有读写权限，请检查输出目录是否被占用或只读，或将输出目录改到有权限的位置"))
+            }
+            Self::Unparsable(_) => Some(tr("该文件已跳过，请按提示的行列号修复语法错误后使用“更新翻译”重试")),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TranslatorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Network(detail) => write!(f, "网络错误 ({})", detail),
+            Self::Auth(detail) => write!(f, "API Key 无效 ({})", detail),
+            Self::RateLimited(detail) => write!(f, "请求被限流 ({})", detail),
+            Self::QuotaExhausted(detail) => write!(f, "额度已用尽 ({})", detail),
+            Self::ModelNotFound(detail) => write!(f, "模型不存在 ({})", detail),
+            Self::EndpointNotFound(detail) => write!(f, "接口不存在 ({})", detail),
+            Self::ModelOutputInvalid(detail) => write!(f, "模型输出无法解析 ({})", detail),
+            Self::Truncated => f.write_str("模型输出被截断 (finish_reason = length)"),
+            Self::Unparsable(detail) => write!(f, "无法解析 {}", detail),
+            Self::Io(e) if e.kind() == io::ErrorKind::PermissionDenied => write!(f, "权限不足 ({})", e),
+            Self::Io(e) => write!(f, "{}", e),
+            Self::Cancelled => f.write_str("任务已取消"),
+            Self::Other(detail) => f.write_str(detail),
+        }
+    }
+}
+
+impl std::error::Error for TranslatorError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for TranslatorError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<reqwest::Error> for TranslatorError {
+    fn from(e: reqwest::Error) -> Self {
+        Self::Network(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for TranslatorError {
+    fn from(e: serde_json::Error) -> Self {
+        match e.io_error_kind() {
+            Some(_) => Self::Io(e.into()),
+            None => Self::Other(e.to_string()),
+        }
+    }
+}
+
+impl From<zip::result::ZipError> for TranslatorError {
+    fn from(e: zip::result::ZipError) -> Self {
+        Self::Io(e.into())
+    }
+}
+
+impl From<String> for TranslatorError {
+    fn from(detail: String) -> Self {
+        Self::Other(detail)
+    }
+}
+
+/// 错误信息，能归类时在末尾附上提示
+pub fn describe(err: &TranslatorError) -> String {
+    match err.hint() {
+        Some(hint) => format!("{} | 💡 {}", err, hint),
+        None => err.to_string(),
+    }
+}
@@ -0,0 +1,222 @@
+// 额外输出目标：任务完成后把主输出目录中的资源文件同步到其他目录或资源包 zip，
+// 省去手动复制和打包
+use crate::config::{OutputLayout, OutputTarget};
+use crate::logic::mc_version::McVersion;
+use crate::logic::paths::long_path;
+use crate::{log_err, log_info, log_success, log_warn};
+use crate::logic::error::Result;
+use sha1::{Digest, Sha1};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+/// 目标路径以 .zip 结尾时打包为资源包
+pub fn is_zip(target: &OutputTarget) -> bool {
+    Path::new(&target.path).extension().is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+}
+
+fn layout_root(root: &Path, layout: OutputLayout) -> PathBuf {
+    match layout {
+        OutputLayout::ResourcePack => root.to_path_buf(),
+        OutputLayout::Kubejs => root.join("kubejs"),
+    }
+}
+
+/// 主输出中 assets/ 下的所有文件，(assets/ 起的相对路径, 绝对路径)
+fn collect_assets(output_root: &Path, layout: OutputLayout) -> Vec<(String, PathBuf)> {
+    let root = layout_root(output_root, layout);
+    WalkDir::new(root.join("assets"))
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| {
+            let relative = e.path().strip_prefix(&root).ok()?.to_string_lossy().replace('\\', "/");
+            Some((relative, e.into_path()))
+        })
+        .collect()
+}
+
+/// 资源包布局需要 pack.mcmeta 才能被游戏识别，版本未知时按 1.20.1 的格式写出
+fn pack_meta(mc_version: Option<McVersion>) -> String {
+    format!(
+        "{{\n  \"pack\": {{\n    \"pack_format\": {},\n    \"description\": \"MC Translator translations\"\n  }}\n}}\n",
+        mc_version.map_or(15, |v| v.resource_pack_format())
+    )
+}
+
+fn write_folder(target: &OutputTarget, files: &[(String, PathBuf)], mc_version: Option<McVersion>) -> Result<()> {
+    let root = layout_root(Path::new(&target.path), target.layout);
+    for (relative, source) in files {
+        let dest = long_path(root.join(relative));
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(source, dest)?;
+    }
+    let meta_path = Path::new(&target.path).join("pack.mcmeta");
+    if target.layout == OutputLayout::ResourcePack && !meta_path.exists() {
+        fs::write(meta_path, pack_meta(mc_version))?;
+    }
+    Ok(())
+}
+
+fn write_zip(target: &OutputTarget, files: &[(String, PathBuf)], mc_version: Option<McVersion>) -> Result<()> {
+    let zip_path = Path::new(&target.path);
+    if let Some(parent) = zip_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // 先写临时文件，避免中途失败留下损坏的压缩包
+    let tmp_path = zip_path.with_extension("zip.tmp");
+    let mut writer = ZipWriter::new(fs::File::create(&tmp_path)?);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let prefix = match target.layout {
+        OutputLayout::ResourcePack => {
+            writer.start_file("pack.mcmeta", options)?;
+            writer.write_all(pack_meta(mc_version).as_bytes())?;
+            ""
+        }
+        OutputLayout::Kubejs => "kubejs/",
+    };
+    for (relative, source) in files {
+        writer.start_file(format!("{}{}", prefix, relative), options)?;
+        writer.write_all(&fs::read(source)?)?;
+    }
+    writer.finish()?;
+    fs::rename(&tmp_path, zip_path)?;
+    Ok(())
+}
+
+/// 把主输出打包为资源包 zip，返回打包的资源文件数
+pub fn pack_zip(output_root: &str, layout: OutputLayout, zip_path: &Path, mc_version: Option<McVersion>) -> Result<usize> {
+    let files = collect_assets(Path::new(output_root), layout);
+    if files.is_empty() {
+        return Ok(0);
+    }
+    let target = OutputTarget { path: zip_path.to_string_lossy().to_string(), layout: OutputLayout::ResourcePack };
+    write_zip(&target, &files, mc_version)?;
+    Ok(files.len())
+}
+
+/// 把主输出同步到所有额外输出目标，单个目标失败不影响其他目标
+pub fn export_targets(output_root: &str, layout: OutputLayout, targets: &[OutputTarget], mc_version: Option<McVersion>) {
+    let targets: Vec<&OutputTarget> = targets.iter().filter(|t| !t.path.trim().is_empty()).collect();
+    if targets.is_empty() {
+        return;
+    }
+    let files = collect_assets(Path::new(output_root), layout);
+    if files.is_empty() {
+        log_info!("主输出中没有资源文件，跳过额外输出目标");
+        return;
+    }
+    let canonical_root = fs::canonicalize(output_root).ok();
+    for target in targets {
+        // 同一目录同一结构时复制会覆盖源文件本身
+        if !is_zip(target) && target.layout == layout && fs::canonicalize(&target.path).ok() == canonical_root {
+            log_warn!("输出目标与主输出目录相同，已跳过: {}", target.path);
+            continue;
+        }
+        let result = if is_zip(target) {
+            write_zip(target, &files, mc_version)
+        } else {
+            write_folder(target, &files, mc_version)
+        };
+        match result {
+            Ok(()) => log_success!("已同步 {} 个文件到输出目标: {}", files.len(), target.path),
+            Err(e) => log_err!("写入输出目标失败 [{}]: {}", target.path, e),
+        }
+    }
+}
+
+/// 数据包中的服务端语言文件：assets/<命名空间>/lang/*.json -> data/<命名空间>/lang/*.json，
+/// 由 Server Translations API 等服务端翻译模组读取，玩家无需安装资源包即可看到译文
+fn write_datapack(dir: &Path, files: &[(String, PathBuf)], mc_version: Option<McVersion>) -> Result<usize> {
+    let mut count = 0;
+    for (relative, source) in files {
+        let Some(rest) = relative.strip_prefix("assets/") else {
+            continue;
+        };
+        let parts: Vec<&str> = rest.split('/').collect();
+        if parts.len() != 3 || parts[1] != "lang" || !parts[2].ends_with(".json") {
+            continue;
+        }
+        let dest = dir.join("data").join(rest);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(source, dest)?;
+        count += 1;
+    }
+    fs::write(
+        dir.join("pack.mcmeta"),
+        format!(
+            "{{\n  \"pack\": {{\n    \"pack_format\": {},\n    \"description\": \"MC Translator server translations\"\n  }}\n}}\n",
+            mc_version.map_or(15, |v| v.datapack_format())
+        ),
+    )?;
+    Ok(count)
+}
+
+/// server.properties 中强制资源包的配置，url 为空时留待服主填写上传后的下载地址。
+/// 旧版服务端按 ISO-8859-1 读取该文件，提示文本只用 ASCII
+fn server_properties(url: &str, sha1: &str, pack_name: &str) -> String {
+    let url_line = if url.trim().is_empty() {
+        format!("# 把 {} 上传到玩家可以直接下载的地址后填写\nresource-pack=", pack_name)
+    } else {
+        // server.properties 中的 : 与 = 需要转义
+        format!("resource-pack={}", url.trim().replace(':', "\\:").replace('=', "\\="))
+    };
+    format!(
+        "# 由 MC Translator 生成，合并到服务器的 server.properties\n{}\nresource-pack-sha1={}\nrequire-resource-pack=true\nresource-pack-prompt={{\"text\":\"MC Translator: {}\"}}\n",
+        url_line,
+        sha1,
+        pack_name.trim_end_matches(".zip")
+    )
+}
+
+/// 生成服务端分发包：world/datapacks/ 下的服务端语言数据包、资源包 zip 与强制资源包的 server.properties 片段
+pub fn export_server_pack(
+    output_root: &str,
+    layout: OutputLayout,
+    dir: &str,
+    url: &str,
+    target_lang: &str,
+    mc_version: Option<McVersion>,
+) {
+    if dir.trim().is_empty() {
+        return;
+    }
+    let files = collect_assets(Path::new(output_root), layout);
+    if files.is_empty() {
+        log_info!("主输出中没有资源文件，跳过服务端分发包");
+        return;
+    }
+    let result = (|| -> Result<(usize, String)> {
+        let dir = Path::new(dir.trim());
+        let name = format!("mc_translator_{}", target_lang.to_ascii_lowercase());
+        let datapack_dir = dir.join("datapacks").join(&name);
+        if datapack_dir.exists() {
+            fs::remove_dir_all(&datapack_dir)?; // 清除上次生成的旧文件
+        }
+        let lang_files = write_datapack(&datapack_dir, &files, mc_version)?;
+
+        let pack_name = format!("{}.zip", name);
+        let zip_path = dir.join(&pack_name);
+        let target = OutputTarget { path: zip_path.to_string_lossy().to_string(), layout: OutputLayout::ResourcePack };
+        write_zip(&target, &files, mc_version)?;
+        let sha1 = format!("{:x}", Sha1::digest(fs::read(&zip_path)?));
+        fs::write(dir.join("server.properties.txt"), server_properties(url, &sha1, &pack_name))?;
+        Ok((lang_files, sha1))
+    })();
+    match result {
+        Ok((lang_files, sha1)) => {
+            log_success!("已生成服务端分发包: {} (数据包 {} 个语言文件，资源包 SHA-1 {})", dir, lang_files, sha1);
+            if url.trim().is_empty() {
+                log_warn!("未设置资源包下载地址，请上传资源包后在 server.properties 中填写 resource-pack");
+            }
+        }
+        Err(e) => log_err!("生成服务端分发包失败 [{}]: {}", dir, e),
+    }
+}
@@ -0,0 +1,134 @@
+use crate::logic::common::{SkipReason, TranslationContext, execute_translation_batches};
+use crate::logic::error::Result;
+use crate::logic::openai::OpenAIClient;
+use crate::config::AppConfig;
+use crate::logic::formats::{FormatHandler, ProcessFuture};
+use crate::{log_info, log_success};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+
+/// NeoForge/Forge 自动生成的取值说明，保持原样
+const SPEC_PREFIXES: [&str; 3] = ["Range:", "Default:", "Allowed Values:"];
+
+/// config 目录下带注释的配置文件 (.toml / .hjson / .json5)
+pub fn is_config_file(path: &Path) -> bool {
+    let ext = path.extension().unwrap_or_default().to_string_lossy().to_lowercase();
+    matches!(ext.as_str(), "toml" | "hjson" | "json5")
+        && path.components().any(|c| c.as_os_str().eq_ignore_ascii_case("config"))
+}
+
+/// 拆分注释行为 (前缀, 注释文本)，前缀包含缩进与 `#` / `//`
+fn split_comment(line: &str) -> Option<(&str, &str)> {
+    let trimmed = line.trim_start();
+    let marker = if trimmed.starts_with("//") { 2 } else if trimmed.starts_with('#') { 1 } else { return None };
+    let prefix_len = line.len() - trimmed.len() + marker;
+    let rest = &line[prefix_len..];
+    let text = rest.trim_start();
+    let prefix = &line[..prefix_len + rest.len() - text.len()];
+    Some((prefix, text.trim_end()))
+}
+
+/// 配置文件注释，输出到 config_docs/
+pub struct ConfigCommentsFormat;
+
+impl FormatHandler for ConfigCommentsFormat {
+    fn matches(&self, path: &Path, config: &AppConfig, _is_ftb_1_21: bool) -> bool {
+        config.translate_config_comments && is_config_file(path)
+    }
+
+    fn process<'a>(
+        &'a self,
+        path: &'a Path,
+        output_root: &'a str,
+        client: &'a OpenAIClient,
+        ctx: Arc<TranslationContext>,
+        token: &'a CancellationToken,
+    ) -> ProcessFuture<'a> {
+        Box::pin(process_config_comments(path, output_root, client, ctx, token))
+    }
+}
+
+/// 将配置文件中的注释翻译后写入 config_docs/ 下的同名文件，供整合包文档使用，不影响原配置
+pub async fn process_config_comments(
+    file_path: &Path,
+    output_root: &str,
+    client: &OpenAIClient,
+    ctx: Arc<TranslationContext>,
+    token: &CancellationToken,
+) -> Result<()> {
+    let output_path = match file_path.components().position(|c| c.as_os_str().eq_ignore_ascii_case("config")) {
+        Some(idx) => {
+            let relative: PathBuf = file_path.components().skip(idx + 1).collect();
+            Path::new(output_root).join("config_docs").join(relative)
+        }
+        None => Path::new(output_root).join("config_docs").join(file_path.file_name().unwrap_or_default()),
+    };
+    let output_path = ctx.map_output_path(Path::new(output_root), output_path);
+    if ctx.skip_existing_output(&output_path) {
+        return Ok(());
+    }
+
+    let content = fs::read_to_string(file_path)?;
+    let lines: Vec<&str> = content.lines().collect();
+
+    let mut extracted_map = Map::new();
+    for (i, line) in lines.iter().enumerate() {
+        if let Some((_, text)) = split_comment(line)
+            && text.chars().any(|c| c.is_alphabetic())
+            && !SPEC_PREFIXES.iter().any(|p| text.starts_with(p))
+        {
+            extracted_map.insert(format!("{:05}", i), Value::String(text.to_string()));
+        }
+    }
+
+    if extracted_map.is_empty() {
+        ctx.stats.record_skip(SkipReason::Empty);
+        return Ok(());
+    }
+    if ctx.exceeds_entry_limit(&file_path.to_string_lossy(), extracted_map.len()) {
+        return Ok(());
+    }
+    log_info!("提取到 {} 条注释，开始翻译 [{:?}]", extracted_map.len(), file_path);
+
+    let file_stem = file_path.file_stem().unwrap_or_default().to_string_lossy();
+    let (translated_map, _) = execute_translation_batches(
+        extracted_map,
+        client,
+        &format!("Config_{}", file_stem),
+        &ctx,
+        token,
+    ).await;
+
+    if token.is_cancelled() {
+        return Ok(());
+    }
+
+    let mut new_content = String::with_capacity(content.len());
+    for (i, line) in lines.iter().enumerate() {
+        match (split_comment(line), translated_map.get(&format!("{:05}", i)).and_then(|v| v.as_str())) {
+            // 译文中的换行拆成多行注释，保证仍是合法注释
+            (Some((prefix, _)), Some(trans_val)) => {
+                for part in trans_val.lines() {
+                    new_content.push_str(prefix);
+                    new_content.push_str(part);
+                    new_content.push('\n');
+                }
+            }
+            _ => {
+                new_content.push_str(line);
+                new_content.push('\n');
+            }
+        }
+    }
+
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&output_path, new_content)?;
+
+    log_success!("配置注释翻译完成: {:?}", output_path);
+    Ok(())
+}
@@ -0,0 +1,144 @@
+use crate::logic::common::{
+    SkipReason, TranslationContext, datapack_output_path, execute_translation_batches, write_pack_meta,
+};
+use crate::logic::error::Result;
+use crate::logic::openai::OpenAIClient;
+use crate::logic::tolerant_json;
+use crate::config::AppConfig;
+use crate::logic::formats::{FormatHandler, has_extension, ProcessFuture};
+use crate::{log_info, log_success};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+
+/// 描述类模组存放数据的目录，如 data/<命名空间>/enchantment_descriptions/
+const DESCRIPTION_DIRS: [&str; 4] = ["enchantment_descriptions", "item_descriptions", "descriptions", "tooltips"];
+/// 需要翻译的字段，值可以是字符串或字符串数组
+const TEXT_KEYS: [&str; 6] = ["description", "desc", "tooltip", "tooltips", "text", "lines"];
+
+pub fn is_description_file(path: &str) -> bool {
+    let path = path.replace('\\', "/");
+    let parts: Vec<&str> = path.split('/').collect();
+    path.ends_with(".json")
+        && parts
+            .iter()
+            .enumerate()
+            .any(|(i, &c)| c == "data" && parts.get(i + 2).is_some_and(|d| DESCRIPTION_DIRS.contains(d)))
+}
+
+/// data/ 下的附魔、物品描述数据
+pub struct DescriptionFormat;
+
+impl FormatHandler for DescriptionFormat {
+    fn matches(&self, path: &Path, config: &AppConfig, _is_ftb_1_21: bool) -> bool {
+        config.translate_description_data && has_extension(path, "json") && is_description_file(&path.to_string_lossy())
+    }
+
+    fn process<'a>(
+        &'a self,
+        path: &'a Path,
+        output_root: &'a str,
+        client: &'a OpenAIClient,
+        ctx: Arc<TranslationContext>,
+        token: &'a CancellationToken,
+    ) -> ProcessFuture<'a> {
+        Box::pin(process_description(path, output_root, client, ctx, token))
+    }
+}
+
+pub async fn process_description(
+    file_path: &Path,
+    output_root: &str,
+    client: &OpenAIClient,
+    ctx: Arc<TranslationContext>,
+    token: &CancellationToken,
+) -> Result<()> {
+    let output_path = datapack_output_path(Path::new(output_root), &file_path.to_string_lossy());
+    let output_path = ctx.map_output_path(Path::new(output_root), output_path);
+    if ctx.skip_existing_output(&output_path) {
+        return Ok(());
+    }
+
+    let content = fs::read_to_string(file_path)?;
+    let file_stem = file_path.file_stem().unwrap_or_default().to_string_lossy();
+    translate_description_json(&content, &file_path.display().to_string(), &file_stem, &output_path, client, ctx, token).await
+}
+
+/// 翻译描述数据中的文本字段，保持其余结构不变，写入 `output_path`
+pub async fn translate_description_json(
+    content: &str,
+    source_name: &str,
+    file_stem: &str,
+    output_path: &Path,
+    client: &OpenAIClient,
+    ctx: Arc<TranslationContext>,
+    token: &CancellationToken,
+) -> Result<()> {
+    let mut root: Value = serde_json::from_str(&tolerant_json::sanitize(content))?;
+
+    let mut extracted_map = Map::new();
+    visit_texts(&mut root, false, &mut |text| {
+        extracted_map.insert(format!("{:05}", extracted_map.len()), Value::String(text.clone()));
+    });
+    if extracted_map.is_empty() {
+        log_info!("未发现可翻译内容: {}", source_name);
+        ctx.stats.record_skip(SkipReason::Empty);
+        return Ok(());
+    }
+    if ctx.exceeds_entry_limit(source_name, extracted_map.len()) {
+        return Ok(());
+    }
+    log_info!("提取到 {} 条条目，开始翻译 [{}]", extracted_map.len(), source_name);
+
+    let (translated_map, _) = execute_translation_batches(
+        extracted_map,
+        client,
+        &format!("Description_{}", file_stem),
+        &ctx,
+        token,
+    ).await;
+
+    if token.is_cancelled() {
+        return Ok(());
+    }
+
+    // 按提取时的顺序回填
+    let mut counter = 0;
+    visit_texts(&mut root, false, &mut |text| {
+        if let Some(trans_val) = translated_map.get(&format!("{:05}", counter)).and_then(|v| v.as_str())
+            && !trans_val.is_empty()
+        {
+            *text = trans_val.to_string();
+        }
+        counter += 1;
+    });
+
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(output_path, serde_json::to_string_pretty(&root)?)?;
+    write_pack_meta(output_path, ctx.mc_version)?;
+
+    log_success!("描述数据翻译完成: {:?}", output_path);
+    Ok(())
+}
+
+/// 遍历 TEXT_KEYS 字段下的字符串，`in_text` 表示当前已处于文本字段内
+fn visit_texts(value: &mut Value, in_text: bool, f: &mut dyn FnMut(&mut String)) {
+    match value {
+        Value::String(text) if in_text && text.chars().any(|c| c.is_alphabetic()) => f(text),
+        Value::Array(items) => {
+            for item in items {
+                visit_texts(item, in_text, f);
+            }
+        }
+        Value::Object(obj) => {
+            for (key, child) in obj.iter_mut() {
+                visit_texts(child, TEXT_KEYS.contains(&key.as_str()), f);
+            }
+        }
+        _ => {}
+    }
+}
@@ -1,44 +1,231 @@
 use crate::{log_info, log_warn, log_err};
-use crate::logic::common::{FileFormat, TranslationContext, core_translation_pipeline};
+use crate::logic::common::{
+    FileFormat, SkipReason, TranslationContext, core_translation_pipeline, datapack_output_path, is_lang_file,
+    localize_segment, parse_lang_lines, pick_source_lang_file,
+};
+use crate::logic::tolerant_json;
+use crate::logic::formats::descriptions::{is_description_file, translate_description_json};
+use crate::logic::formats::snbt::translate_snbt_content;
+use crate::logic::formats::structure::{is_structure_file, translate_structure_bytes};
+use crate::logic::error::{Result, TranslatorError, describe};
+use crate::logic::mod_metadata::{read_display_names, read_versions};
 use crate::logic::openai::OpenAIClient;
+use crate::logic::pack_manifest;
+use crate::config::AppConfig;
+use crate::logic::formats::{FormatHandler, has_extension, ProcessFuture};
+use sha1::{Digest, Sha1};
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
-use std::io::Read;
-use std::path::Path;
+use serde_json::{Map, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
 use tokio_util::sync::CancellationToken;
-use zip::ZipArchive;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
 use std::sync::Arc;
 
+/// 模组 JAR，读取其中的语言文件、任务、结构与描述数据
+pub struct JarFormat;
+
+impl FormatHandler for JarFormat {
+    fn matches(&self, path: &Path, _config: &AppConfig, _is_ftb_1_21: bool) -> bool {
+        has_extension(path, "jar")
+    }
+
+    fn is_lang_format(&self) -> bool {
+        true
+    }
+
+    fn process<'a>(
+        &'a self,
+        path: &'a Path,
+        output_root: &'a str,
+        client: &'a OpenAIClient,
+        ctx: Arc<TranslationContext>,
+        token: &'a CancellationToken,
+    ) -> ProcessFuture<'a> {
+        Box::pin(process_jar(path, output_root, client, ctx, token))
+    }
+}
+
+/// 内容相同的 JAR (如同一模组同时放在 mods/ 与备份目录中)，返回 (重复的, 保留的)。
+/// 先按文件大小分组，只对大小相同的计算哈希；优先保留 mods 目录中路径最短的一个
+pub fn find_duplicates(jars: &[PathBuf]) -> Vec<(PathBuf, PathBuf)> {
+    let mut by_size: HashMap<u64, Vec<&PathBuf>> = HashMap::new();
+    for jar in jars {
+        if let Ok(meta) = fs::metadata(jar) {
+            by_size.entry(meta.len()).or_default().push(jar);
+        }
+    }
+    let mut by_hash: HashMap<Vec<u8>, Vec<&PathBuf>> = HashMap::new();
+    for jar in by_size.into_values().filter(|group| group.len() > 1).flatten() {
+        let Ok(mut file) = fs::File::open(jar) else {
+            continue;
+        };
+        let mut hasher = Sha1::new();
+        if std::io::copy(&mut file, &mut hasher).is_ok() {
+            by_hash.entry(hasher.finalize().to_vec()).or_default().push(jar);
+        }
+    }
+
+    let in_mods = |p: &Path| p.parent().and_then(|d| d.file_name()).is_some_and(|n| n.eq_ignore_ascii_case("mods"));
+    let mut duplicates = Vec::new();
+    for mut group in by_hash.into_values().filter(|group| group.len() > 1) {
+        group.sort_by_key(|p| (!in_mods(p), p.as_os_str().len(), p.to_path_buf()));
+        let kept = group[0].clone();
+        duplicates.extend(group[1..].iter().map(|p| ((*p).clone(), kept.clone())));
+    }
+    duplicates.sort();
+    duplicates
+}
+
+/// 同一模组的多个版本合并后的原文，由 owner 负责翻译，其余版本跳过该命名空间
+#[derive(Debug)]
+pub struct VersionMerge {
+    pub owner: PathBuf,
+    pub source: Map<String, Value>,
+}
+
+/// JAR 中语言文件所属的命名空间，只读取压缩包目录
+fn lang_namespaces(jar_path: &Path, source_lang: &str, fallbacks: &[String]) -> Vec<String> {
+    let Some(archive) = fs::File::open(jar_path).ok().and_then(|f| ZipArchive::new(f).ok()) else {
+        return Vec::new();
+    };
+    let mut namespaces: Vec<String> = archive
+        .file_names()
+        .filter(|n| is_lang_entry(n, source_lang, fallbacks))
+        .filter_map(|n| {
+            let parts: Vec<&str> = n.split('/').collect();
+            let i = parts.iter().position(|&x| x == "assets")?;
+            parts.get(i + 1).filter(|&&ns| ns != "minecraft").map(|ns| ns.to_string())
+        })
+        .collect();
+    namespaces.sort();
+    namespaces.dedup();
+    namespaces
+}
+
+/// 语言文件出现在多个 JAR 中的命名空间 (通常是同一模组的不同版本)，命名空间 -> JAR 列表
+pub fn find_shared_namespaces(jars: &[PathBuf], source_lang: &str, fallbacks: &[String]) -> BTreeMap<String, Vec<PathBuf>> {
+    let mut owners: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    for jar in jars {
+        for namespace in lang_namespaces(jar, source_lang, fallbacks) {
+            owners.entry(namespace).or_default().push(jar.clone());
+        }
+    }
+    owners.retain(|_, jars| jars.len() > 1);
+    owners
+}
+
+/// 合并各版本的条目：以条目最多的版本为基础并由它负责翻译，其余版本独有的键追加在后面
+pub fn merge_versions(
+    shared: &BTreeMap<String, Vec<PathBuf>>,
+    source_lang: &str,
+    fallbacks: &[String],
+) -> HashMap<String, VersionMerge> {
+    let mut merges = HashMap::new();
+    for (namespace, jars) in shared {
+        let mut versions: Vec<(&PathBuf, Map<String, Value>)> = jars
+            .iter()
+            .filter_map(|jar| {
+                let maps = read_source_maps(jar, source_lang, fallbacks).ok()?;
+                let mut merged = Map::new();
+                for (_, map) in maps.into_iter().filter(|(mod_id, _)| mod_id == namespace) {
+                    merged.extend(map);
+                }
+                Some((jar, merged))
+            })
+            .collect();
+        versions.sort_by_key(|(jar, map)| (std::cmp::Reverse(map.len()), jar.to_path_buf()));
+        let Some(((owner, mut source), others)) = versions.split_first().map(|(first, rest)| (first.clone(), rest)) else {
+            continue;
+        };
+        let base = source.len();
+        for (_, map) in others {
+            for (key, value) in map {
+                if !source.contains_key(key) {
+                    source.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        log_info!(
+            "合并模组 {} 的 {} 个版本: 以 {} 为准，另补充 {} 条其他版本独有的条目",
+            namespace,
+            jars.len(),
+            owner.file_name().unwrap_or_default().to_string_lossy(),
+            source.len() - base
+        );
+        merges.insert(namespace.clone(), VersionMerge { owner: owner.clone(), source });
+    }
+    merges
+}
+
 pub async fn process_jar(
     jar_path: &Path,
     output_root: &str,
     client: &OpenAIClient,
     ctx: Arc<TranslationContext>,
     token: &CancellationToken,
-) -> anyhow::Result<()> {
+) -> Result<()> {
     let jar_name = jar_path.file_name().unwrap_or_default().to_string_lossy();
-    log_info!("扫描 JAR: {}", jar_name);
-
     let file = fs::File::open(jar_path)?;
     let mut archive = ZipArchive::new(file)?;
+    let names = read_display_names(&mut archive);
+    if names.is_empty() {
+        log_info!("扫描 JAR: {}", jar_name);
+    } else {
+        let mut listed: Vec<&str> = names.values().map(|s| s.as_str()).collect();
+        listed.sort();
+        log_info!("扫描 JAR: {} ({})", jar_name, listed.join(", "));
+    }
+    ctx.register_mod_names(names);
+    let versions = read_versions(&mut archive);
 
     // 收集目标文件 (避免借用冲突，先收集文件名)
-    let mut targets = Vec::new();
+    // (条目序号, 路径)，之后按序号读取，不再按名称查找
+    let mut lang_candidates: Vec<(usize, String)> = Vec::new();
+    let mut snbt_targets: Vec<(usize, String)> = Vec::new();
+    let mut structure_targets: Vec<(usize, String)> = Vec::new();
+    let mut description_targets: Vec<(usize, String)> = Vec::new();
     for i in 0..archive.len() {
         let file = archive.by_index(i)?;
         let fname = file.name();
-        if fname.contains("assets") && fname.contains(&ctx.source_lang) {
-            if fname.ends_with(".json") || fname.ends_with(".lang") {
-                targets.push(fname.to_string());
-            }
+        let is_lang = is_lang_entry(fname, &ctx.source_lang, &ctx.source_fallbacks);
+        // 仅复用模式只处理语言文件
+        if ctx.reuse_only && !is_lang {
+            continue;
+        }
+        let is_target = is_lang
+            || fname.ends_with(".snbt")
+            || (ctx.translate_structures && is_structure_file(fname))
+            || (ctx.translate_description_data && is_description_file(fname));
+        if is_target && ctx.exceeds_size_limit(&format!("{}/{}", jar_path.display(), fname), file.size()) {
+            continue;
+        }
+        if is_lang {
+            lang_candidates.push((i, fname.to_string()));
+        } else if fname.ends_with(".snbt") {
+            // 模组自带的任务 / 思索文件
+            snbt_targets.push((i, fname.to_string()));
+        } else if ctx.translate_structures && is_structure_file(fname) {
+            structure_targets.push((i, fname.to_string()));
+        } else if ctx.translate_description_data && is_description_file(fname) {
+            description_targets.push((i, fname.to_string()));
         }
     }
 
-    if targets.is_empty() {
+    let targets = select_lang_entries(&lang_candidates, &ctx.source_lang, &ctx.source_fallbacks);
+
+    if targets.is_empty() && snbt_targets.is_empty() && structure_targets.is_empty() && description_targets.is_empty() {
         return Ok(());
     }
+    ctx.register_mod_versions(&jar_name, pack_manifest::jar_sha1(jar_path), versions);
+
+    // JAR 内路径 -> 输出目录中对应的译文文件，用于修补模式
+    let mut injections: BTreeMap<String, PathBuf> = BTreeMap::new();
 
     // 遍历处理
-    for target_path in targets {
+    for (index, target_path) in targets {
         if token.is_cancelled() {
             break;
         }
@@ -51,6 +238,7 @@ pub async fn process_jar(
             .unwrap_or(&"unknown")
             .to_string();
         if mod_id == "minecraft" {
+            ctx.stats.record_skip(SkipReason::Vanilla);
             continue;
         }
         
@@ -60,85 +248,58 @@ pub async fn process_jar(
             .to_string_lossy()
             .to_string();
 
-        // 读取 ZIP 内的源内容
-        let mut content = String::new();
-        {
-            let mut zf = archive.by_name(&target_path)?;
-            zf.read_to_string(&mut content)?;
-        }
-
-        if content.trim().is_empty() {
-            log_warn!("跳过空文件: {} -> {}", jar_name, target_path);
-            continue;
-        }
-
         let is_lang_file = target_path.ends_with(".lang");
         let format = if is_lang_file { FileFormat::Lang } else { FileFormat::Json };
 
-        let src_map = if is_lang_file {
-            let mut map = serde_json::Map::new();
-            for line in content.lines() {
-                let line = line.trim();
-                if line.is_empty() || line.starts_with('#') {
-                    continue;
-                }
-                if let Some((k, v)) = line.split_once('=') {
-                    map.insert(
-                        k.trim().to_string(),
-                        serde_json::Value::String(v.trim().to_string()),
-                    );
-                }
+        let src_map = match read_lang_entry(&mut archive, index, format) {
+            Ok(map) if map.is_empty() => {
+                log_info!("跳过空文件: {} -> {}", jar_name, target_path);
+                ctx.stats.record_skip(SkipReason::Empty);
+                continue;
             }
-            map
-        } else {
-            let mut sanitized = crate::logic::common::sanitize_json_content(&content);
-            if sanitized.trim().is_empty() {
-                 sanitized = "{}".to_string();
+            Ok(map) => map,
+            Err(e) if e.is_unparsable() => {
+                log_warn!("跳过无法解析的文件: {} -> {} ({})", jar_name, target_path, describe(&e));
+                ctx.stats.record_unparsable(format!("{} [{}] -> {}", jar_name, ctx.mod_label(&mod_id), target_path));
+                continue;
             }
-            match serde_json::from_str(&sanitized) {
-                Ok(serde_json::Value::Object(map)) => map,
-                Ok(_) => continue,
-                Err(e) => {
-                    log_err!("JSON 解析失败: {} -> {} (Error: {})", jar_name, target_path, e);
-                    continue;
-                }
+            Err(e) => {
+                log_err!("解析失败: {} -> {} (Error: {})", jar_name, target_path, e);
+                continue;
             }
         };
 
-        let target_filename = crate::logic::common::get_target_filename(&file_name, &ctx.source_lang, &ctx.target_lang);
-        
+        let target_filename = ctx.target_filename(&file_name);
+        let output_filename = ctx.output_filename(&file_name, format);
+        let lang_dir = Path::new(&target_path).parent();
+
         // 尝试从 JAR 中读取内置汉化 (e.g. assets/modid/lang/zh_cn.json / .lang)
-        let builtin_path = Path::new(&target_path)
-            .parent()
+        let builtin_path = lang_dir
             .map(|p| p.join(&target_filename))
             .map(|p| p.to_string_lossy().replace('\\', "/")); 
 
-        let mut builtin_map = None;
-        if let Some(bp) = builtin_path {
-            if let Ok(mut zf) = archive.by_name(&bp) {
-                let mut content = String::new();
-                if zf.read_to_string(&mut content).is_ok() {
-                    if is_lang_file {
-                         // Parse built-in lang
-                        let mut map = serde_json::Map::new();
-                        for line in content.lines() {
-                            if let Some((k, v)) = line.split_once('=') {
-                                map.insert(k.trim().to_string(), serde_json::Value::String(v.trim().to_string()));
-                            }
-                        }
-                        builtin_map = Some(map);
-                    } else {
-                        // Parse built-in json, assume it's is standard
-                        if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
-                            if let Some(map) = json.as_object() {
-                                builtin_map = Some(map.clone());
-                            }
-                        }
-                    }
-                }
-            }
+        // 译文按整合包版本可能换了格式，注入时使用写出的文件名
+        if let Some(dir) = lang_dir {
+            let inject_path = dir.join(&output_filename).to_string_lossy().replace('\\', "/");
+            let translated = ctx.lang_output_path(Path::new(output_root), &mod_id, &output_filename);
+            injections.insert(inject_path, translated);
         }
 
+        // 多版本合并时只由负责的 JAR 翻译合并后的条目，修补模式下其余版本仍注入同一份译文
+        let src_map = match ctx.version_merges.get(&mod_id) {
+            Some(merge) if merge.owner != jar_path => {
+                let owner = merge.owner.file_name().unwrap_or_default().to_string_lossy();
+                log_info!("{} 中 {} 的条目已合并到 {} 一并翻译", jar_name, mod_id, owner);
+                continue;
+            }
+            Some(merge) => merge.source.clone(),
+            None => src_map,
+        };
+
+        let builtin_map = builtin_path
+            .and_then(|bp| archive.index_for_name(&bp))
+            .and_then(|i| read_lang_entry(&mut archive, i, format).ok());
+
         core_translation_pipeline(
             src_map,
             &mod_id,
@@ -152,5 +313,241 @@ pub async fn process_jar(
         )
         .await?;
     }
+
+    for (index, target_path) in snbt_targets {
+        if token.is_cancelled() {
+            break;
+        }
+
+        // 按 JAR 内路径输出，路径中的源语言替换为目标语言
+        let relative = target_path
+            .split('/')
+            .map(|c| localize_segment(c, &ctx.source_lang, &ctx.target_lang))
+            .collect::<Vec<_>>()
+            .join("/");
+        let output_path = Path::new(output_root).join(&relative);
+        let output_path = ctx.map_output_path(Path::new(output_root), output_path);
+        if ctx.patch_jars {
+            injections.insert(relative.clone(), output_path.clone());
+        }
+        if ctx.skip_existing_output(&output_path) {
+            continue;
+        }
+
+        let mut content = String::new();
+        if let Err(e) = archive.by_index(index)?.read_to_string(&mut content) {
+            log_err!("读取失败: {} -> {} ({})", jar_name, target_path, e);
+            continue;
+        }
+
+        let file_stem = Path::new(&target_path)
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let source_name = format!("{} -> {}", jar_name, target_path);
+        translate_snbt_content(&content, &source_name, &file_stem, &output_path, client, ctx.clone(), token, None).await?;
+    }
+
+    for (index, target_path) in structure_targets {
+        if token.is_cancelled() {
+            break;
+        }
+
+        let output_path = datapack_output_path(Path::new(output_root), &target_path);
+        let output_path = ctx.map_output_path(Path::new(output_root), output_path);
+        if ctx.patch_jars {
+            injections.insert(target_path.clone(), output_path.clone());
+        }
+        if ctx.skip_existing_output(&output_path) {
+            continue;
+        }
+
+        let mut data = Vec::new();
+        archive.by_index(index)?.read_to_end(&mut data)?;
+        let file_stem = Path::new(&target_path)
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let source_name = format!("{} -> {}", jar_name, target_path);
+        if let Err(e) = translate_structure_bytes(&data, &source_name, &file_stem, &output_path, client, ctx.clone(), token).await {
+            log_err!("结构文件处理失败: {} ({})", source_name, describe(&e));
+        }
+    }
+
+    for (index, target_path) in description_targets {
+        if token.is_cancelled() {
+            break;
+        }
+
+        let output_path = datapack_output_path(Path::new(output_root), &target_path);
+        let output_path = ctx.map_output_path(Path::new(output_root), output_path);
+        if ctx.patch_jars {
+            injections.insert(target_path.clone(), output_path.clone());
+        }
+        if ctx.skip_existing_output(&output_path) {
+            continue;
+        }
+
+        let mut content = String::new();
+        archive.by_index(index)?.read_to_string(&mut content)?;
+        let file_stem = Path::new(&target_path)
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let source_name = format!("{} -> {}", jar_name, target_path);
+        if let Err(e) = translate_description_json(&content, &source_name, &file_stem, &output_path, client, ctx.clone(), token).await {
+            log_err!("描述数据处理失败: {} ({})", source_name, describe(&e));
+        }
+    }
+
+    if ctx.patch_jars && !injections.is_empty() && !token.is_cancelled() {
+        let patched = Path::new(output_root).join("mods_patched").join(jar_path.file_name().unwrap_or_default());
+        match patch_jar(jar_path, &patched, &injections) {
+            Ok(0) => {}
+            Ok(count) => log_info!("已修补 JAR: {} (注入 {} 个译文文件)", jar_name, count),
+            Err(e) => log_err!("修补 JAR 失败: {} ({})", jar_name, describe(&e)),
+        }
+    }
     Ok(())
 }
+
+/// 复制 JAR 并写入译文文件，同名条目会被替换，返回注入的文件数
+fn patch_jar(
+    jar_path: &Path,
+    patched_path: &Path,
+    injections: &BTreeMap<String, PathBuf>,
+) -> Result<usize> {
+    let files: Vec<(&String, Vec<u8>)> = injections
+        .iter()
+        .filter_map(|(entry, path)| fs::read(path).ok().map(|data| (entry, data)))
+        .collect();
+    if files.is_empty() {
+        return Ok(0);
+    }
+
+    let mut archive = ZipArchive::new(fs::File::open(jar_path)?)?;
+    if archive.file_names().any(|n| n.starts_with("META-INF/") && n.ends_with(".SF")) {
+        log_warn!("{:?} 带有签名，修补后签名将失效，部分加载器可能拒绝加载", jar_path.file_name().unwrap_or_default());
+    }
+
+    if let Some(parent) = patched_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // 先写临时文件，避免中途失败留下损坏的 JAR
+    let tmp_path = patched_path.with_extension("jar.tmp");
+    let mut writer = ZipWriter::new(fs::File::create(&tmp_path)?);
+    for i in 0..archive.len() {
+        let entry = archive.by_index_raw(i)?;
+        if files.iter().any(|(name, _)| name.as_str() == entry.name()) {
+            continue;
+        }
+        writer.raw_copy_file(entry)?;
+    }
+
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (entry, data) in &files {
+        writer.start_file(entry.as_str(), options)?;
+        writer.write_all(data)?;
+    }
+    writer.finish()?;
+    fs::rename(&tmp_path, patched_path)?;
+    Ok(files.len())
+}
+
+/// JAR 内 assets/<modid>/lang/<源语言或备选语言>.json|.lang
+fn is_lang_entry(name: &str, source_lang: &str, fallbacks: &[String]) -> bool {
+    let file_name = name.rsplit('/').next().unwrap_or_default();
+    name.contains("assets")
+        && std::iter::once(source_lang)
+            .chain(fallbacks.iter().map(String::as_str))
+            .any(|lang| is_lang_file(file_name, lang))
+}
+
+/// 每个语言目录只保留一个源文件，优先源语言，其次按顺序取备选语言
+fn select_lang_entries(
+    candidates: &[(usize, String)],
+    source_lang: &str,
+    fallbacks: &[String],
+) -> Vec<(usize, String)> {
+    let mut by_dir: BTreeMap<&str, Vec<(&str, usize)>> = BTreeMap::new();
+    for (index, name) in candidates {
+        let (dir, file_name) = name.rsplit_once('/').unwrap_or(("", name));
+        by_dir.entry(dir).or_default().push((file_name, *index));
+    }
+    by_dir
+        .into_iter()
+        .filter_map(|(dir, entries)| {
+            let names: Vec<&str> = entries.iter().map(|(n, _)| *n).collect();
+            let picked = pick_source_lang_file(&names, source_lang, fallbacks)?;
+            let (_, index) = entries.iter().find(|(n, _)| *n == picked)?;
+            let path = if dir.is_empty() { picked.to_string() } else { format!("{}/{}", dir, picked) };
+            Some((*index, path))
+        })
+        .collect()
+}
+
+/// 流式解析 JAR 内的语言文件：.lang 逐行读取，JSON 直接从解压流反序列化，
+/// 仅在 JSON 不规范 (注释、BOM 等) 时才整体读出清洗后重试。根不是对象时返回空表
+fn read_lang_entry(archive: &mut ZipArchive<fs::File>, index: usize, format: FileFormat) -> Result<Map<String, Value>> {
+    if format == FileFormat::Lang {
+        return Ok(parse_lang_lines(BufReader::new(archive.by_index(index)?).lines())?);
+    }
+
+    match serde_json::from_reader::<_, Value>(BufReader::new(archive.by_index(index)?)) {
+        Ok(Value::Object(map)) => return Ok(map),
+        Ok(_) => return Ok(Map::new()),
+        Err(e) if e.is_io() => return Err(e.into()),
+        Err(_) => {}
+    }
+    let mut content = String::new();
+    archive.by_index(index)?.read_to_string(&mut content)?;
+    let sanitized = tolerant_json::sanitize(&content);
+    if sanitized.trim().is_empty() {
+        return Ok(Map::new());
+    }
+    match serde_json::from_str(&sanitized) {
+        Ok(Value::Object(map)) => Ok(map),
+        Ok(_) => Ok(Map::new()),
+        Err(e) => Err(TranslatorError::Unparsable(format!("JSON 解析失败: {}", e))),
+    }
+}
+
+/// 读取 JAR 内所有源语言文件的条目，返回 (ModID, 条目) 列表
+pub fn read_source_maps(
+    jar_path: &Path,
+    source_lang: &str,
+    fallbacks: &[String],
+) -> Result<Vec<(String, serde_json::Map<String, serde_json::Value>)>> {
+    let file = fs::File::open(jar_path)?;
+    let mut archive = ZipArchive::new(file)?;
+
+    let candidates: Vec<(usize, String)> = (0..archive.len())
+        .filter_map(|i| archive.name_for_index(i).map(|n| (i, n.to_string())))
+        .filter(|(_, n)| is_lang_entry(n, source_lang, fallbacks))
+        .collect();
+    let targets = select_lang_entries(&candidates, source_lang, fallbacks);
+
+    let mut result = Vec::new();
+    for (index, target_path) in targets {
+        let parts: Vec<&str> = target_path.split('/').collect();
+        let mod_id = parts
+            .iter()
+            .position(|&x| x == "assets")
+            .and_then(|i| parts.get(i + 1))
+            .unwrap_or(&"unknown")
+            .to_string();
+        if mod_id == "minecraft" {
+            continue;
+        }
+
+        let format = if target_path.ends_with(".lang") { FileFormat::Lang } else { FileFormat::Json };
+        match read_lang_entry(&mut archive, index, format) {
+            Ok(map) if !map.is_empty() => result.push((mod_id, map)),
+            _ => continue,
+        }
+    }
+    Ok(result)
+}
@@ -1,38 +1,66 @@
 use crate::log_info;
 use crate::logic::common::{
-    FileFormat, TranslationContext, core_translation_pipeline, extract_mod_id, read_map_from_file
+    FileFormat, SkipReason, TranslationContext, core_translation_pipeline, extract_mod_id, is_source_lang_file, read_map_from_file
 };
+use crate::logic::error::Result;
 use crate::logic::openai::OpenAIClient;
+use crate::config::AppConfig;
+use crate::logic::formats::{FormatHandler, has_extension, ProcessFuture};
 use std::path::Path;
 use std::sync::Arc;
 use tokio_util::sync::CancellationToken;
 
+/// 旧版本的 <源语言>.lang
+pub struct LangFormat;
+
+impl FormatHandler for LangFormat {
+    fn matches(&self, path: &Path, config: &AppConfig, _is_ftb_1_21: bool) -> bool {
+        has_extension(path, "lang") && is_source_lang_file(path, &config.source_lang, &config.source_lang_fallbacks)
+    }
+
+    fn is_lang_format(&self) -> bool {
+        true
+    }
+
+    fn process<'a>(
+        &'a self,
+        path: &'a Path,
+        output_root: &'a str,
+        client: &'a OpenAIClient,
+        ctx: Arc<TranslationContext>,
+        token: &'a CancellationToken,
+    ) -> ProcessFuture<'a> {
+        Box::pin(process_lang(path, output_root, client, ctx, token))
+    }
+}
+
 pub async fn process_lang(
     file_path: &Path,
     output_root: &str,
     client: &OpenAIClient,
     ctx: Arc<TranslationContext>,
     token: &CancellationToken,
-) -> anyhow::Result<()> {
+) -> Result<()> {
     log_info!("处理 LANG: {}", file_path.display());
 
     let src_map = read_map_from_file(file_path, FileFormat::Lang)?;
     if src_map.is_empty() {
+        ctx.stats.record_skip(SkipReason::Empty);
         return Ok(());
     }
 
     let mod_id = extract_mod_id(file_path);
     let file_name = file_path.file_name().unwrap_or_default().to_string_lossy();
 
-    let target_filename = crate::logic::common::get_target_filename(&file_name, &ctx.source_lang, &ctx.target_lang);
+    let target_filename = ctx.target_filename(&file_name);
 
     // 检查是否有同目录的内置汉化文件 (e.g. zh_cn.lang)
     let builtin_path = file_path.with_file_name(&target_filename);
     let mut builtin_map = None;
-    if builtin_path.exists() {
-         if let Ok(map) = read_map_from_file(&builtin_path, FileFormat::Lang) {
-            builtin_map = Some(map);
-        }
+    if builtin_path.exists()
+         && let Ok(map) = read_map_from_file(&builtin_path, FileFormat::Lang)
+    {
+        builtin_map = Some(map);
     }
 
     core_translation_pipeline(
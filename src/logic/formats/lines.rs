@@ -0,0 +1,120 @@
+use crate::config::{AppConfig, FailedEntryMode, OutputLayout};
+use crate::logic::common::{
+    FileFormat, SkipReason, TranslationContext, execute_translation_batches, localize_segment, read_map_from_file,
+    write_map_to_file,
+};
+use crate::logic::error::Result;
+use crate::logic::openai::OpenAIClient;
+use crate::logic::formats::{FormatHandler, ProcessFuture, has_extension};
+use crate::{log_info, log_success};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+
+/// 是否为按行存储的文本资源：assets/<命名空间>/texts/splashes.txt 或 tips 目录下的文本。
+/// 只认这两处，避免改写 config 等目录下碰巧同名的用户文件
+pub fn is_lines_file(path: &Path) -> bool {
+    if !has_extension(path, "txt") {
+        return false;
+    }
+    let dirs: Vec<_> = path.components().rev().skip(1).take(3).map(|c| c.as_os_str().to_string_lossy()).collect();
+    let is_splashes = path.file_name().is_some_and(|n| n.eq_ignore_ascii_case("splashes.txt"))
+        && dirs.len() == 3
+        && dirs[0].eq_ignore_ascii_case("texts")
+        && dirs[2].eq_ignore_ascii_case("assets");
+    is_splashes || dirs.first().is_some_and(|dir| dir.eq_ignore_ascii_case("tips"))
+}
+
+/// 每行一条的文本，如 splashes.txt
+pub struct LinesFormat;
+
+impl FormatHandler for LinesFormat {
+    fn matches(&self, path: &Path, _config: &AppConfig, _is_ftb_1_21: bool) -> bool {
+        is_lines_file(path)
+    }
+
+    fn process<'a>(
+        &'a self,
+        path: &'a Path,
+        output_root: &'a str,
+        client: &'a OpenAIClient,
+        ctx: Arc<TranslationContext>,
+        token: &'a CancellationToken,
+    ) -> ProcessFuture<'a> {
+        Box::pin(process_lines(path, output_root, client, ctx, token))
+    }
+}
+
+pub async fn process_lines(
+    file_path: &Path,
+    output_root: &str,
+    client: &OpenAIClient,
+    ctx: Arc<TranslationContext>,
+    token: &CancellationToken,
+) -> Result<()> {
+    log_info!("处理文本: {}", file_path.display());
+
+    // 保留 assets 之后的路径，资源包中同路径的文件会覆盖原文件
+    let output_path = if let Some(idx) = file_path
+        .components()
+        .position(|c| c.as_os_str() == "assets")
+    {
+        let relative_path: PathBuf = file_path
+            .components()
+            .skip(idx)
+            .map(|c| OsString::from(localize_segment(&c.as_os_str().to_string_lossy(), &ctx.source_lang, &ctx.target_lang)))
+            .collect();
+        match ctx.output_layout {
+            OutputLayout::ResourcePack => Path::new(output_root).join(relative_path),
+            OutputLayout::Kubejs => Path::new(output_root).join("kubejs").join(relative_path),
+        }
+    } else {
+        // 不在 assets 下时保留相对输入目录的路径，避免不同模组的同名文件互相覆盖
+        match file_path.strip_prefix(&ctx.input_root) {
+            Ok(relative) => Path::new(output_root).join(relative),
+            Err(_) => Path::new(output_root).join(file_path.file_name().unwrap_or_default()),
+        }
+    };
+    let output_path = ctx.map_output_path(Path::new(output_root), output_path);
+    if ctx.skip_existing_output(&output_path) {
+        return Ok(());
+    }
+
+    let src_map = read_map_from_file(file_path, FileFormat::Lines)?;
+    if src_map.is_empty() {
+        log_info!("未发现可翻译内容: {}", file_path.display());
+        ctx.stats.record_skip(SkipReason::Empty);
+        return Ok(());
+    }
+    if ctx.exceeds_entry_limit(&file_path.to_string_lossy(), src_map.len()) {
+        return Ok(());
+    }
+
+    // 失败的行被丢弃时需用原文补回，保证行数与顺序不变；其他模式下失败的行已保留原文
+    let sources = (ctx.failed_entries == FailedEntryMode::Drop).then(|| src_map.clone());
+    let file_stem = file_path.file_stem().unwrap_or_default().to_string_lossy();
+    let (mut translated, failed_keys) = execute_translation_batches(
+        src_map,
+        client,
+        &format!("Text_{}", file_stem),
+        &ctx,
+        token,
+    ).await;
+
+    if token.is_cancelled() {
+        return Ok(());
+    }
+
+    if let Some(mut sources) = sources {
+        for key in failed_keys {
+            if let Some(source) = sources.remove(&key) {
+                translated.insert(key, source);
+            }
+        }
+    }
+    write_map_to_file(&output_path, &translated, FileFormat::Lines)?;
+
+    log_success!("文本翻译完成: {:?}", output_path);
+    Ok(())
+}
@@ -1,4 +1,48 @@
+pub mod config_comments;
+pub mod descriptions;
 pub mod jar;
 pub mod json;
 pub mod lang;
-pub mod snbt;
\ No newline at end of file
+pub mod lines;
+pub mod origins;
+pub mod script;
+pub mod snbt;
+pub mod structure;
+
+use crate::config::AppConfig;
+use crate::logic::common::TranslationContext;
+use crate::logic::error::Result;
+use crate::logic::openai::OpenAIClient;
+use std::future::Future;
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+
+/// 扩展名是否为 `ext`，不区分大小写
+pub fn has_extension(path: &Path, ext: &str) -> bool {
+    path.extension().is_some_and(|e| e.eq_ignore_ascii_case(ext))
+}
+
+pub type ProcessFuture<'a> = Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+
+/// 一种输入格式：扫描时决定是否收集文件，处理时负责翻译并写出译文。
+/// 新增格式实现该 trait 后加入 processor 中的 HANDLERS 即可
+pub trait FormatHandler: Sync {
+    /// 扫描输入目录时是否由该格式处理，`is_ftb_1_21` 表示输入为 1.21+ 的 FTB Quests 结构
+    fn matches(&self, path: &Path, config: &AppConfig, is_ftb_1_21: bool) -> bool;
+
+    /// 是否为语言文件格式，仅复用模式下只处理这些格式
+    fn is_lang_format(&self) -> bool {
+        false
+    }
+
+    fn process<'a>(
+        &'a self,
+        path: &'a Path,
+        output_root: &'a str,
+        client: &'a OpenAIClient,
+        ctx: Arc<TranslationContext>,
+        token: &'a CancellationToken,
+    ) -> ProcessFuture<'a>;
+}
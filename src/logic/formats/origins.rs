@@ -0,0 +1,214 @@
+use crate::config::{AppConfig, OriginsMode};
+use crate::logic::common::{
+    FileFormat, TranslationContext, datapack_output_path, execute_translation_batches, get_target_filename,
+    read_map_from_file, write_map_to_file, write_pack_meta,
+};
+use crate::logic::error::Result;
+use crate::logic::openai::OpenAIClient;
+use crate::logic::tolerant_json;
+use std::sync::Arc;
+use crate::logic::formats::{FormatHandler, ProcessFuture};
+use crate::{log_err, log_info, log_success};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tokio_util::sync::CancellationToken;
+
+const FIELDS: [&str; 2] = ["name", "description"];
+
+/// Origins 数据包中的起源/能力定义: data/<命名空间>/(origins|powers)/**/*.json
+pub fn is_origins_file(path: &str) -> bool {
+    let path = path.replace('\\', "/");
+    let parts: Vec<&str> = path.split('/').collect();
+    path.ends_with(".json")
+        && parts
+            .iter()
+            .enumerate()
+            .any(|(i, &c)| c == "data" && matches!(parts.get(i + 2), Some(&"origins") | Some(&"powers")))
+}
+
+/// 返回 (命名空间, 能力/起源 ID)，用于日志与批次标识
+fn origins_id(path: &Path) -> (String, String) {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let parts: Vec<&str> = path.split('/').collect();
+    let idx = parts.iter().rposition(|&c| c == "data").unwrap_or(0);
+    let namespace = parts.get(idx + 1).copied().unwrap_or("unknown").to_string();
+    let id = parts
+        .get(idx + 3..)
+        .map(|rest| rest.join("/"))
+        .unwrap_or_default()
+        .trim_end_matches(".json")
+        .to_string();
+    (namespace, id)
+}
+
+/// 字段中的可翻译文本：字面量字符串或 {"text": "..."} 组件，翻译键直接跳过
+fn field_text(value: &Value) -> Option<&str> {
+    let text = match value {
+        Value::String(s) => s.as_str(),
+        Value::Object(obj) => obj.get("text")?.as_str()?,
+        _ => return None,
+    };
+    let looks_like_key = !text.contains(' ') && text.contains('.');
+    (!text.trim().is_empty() && !looks_like_key).then_some(text)
+}
+
+fn read_definition(path: &Path) -> Option<Map<String, Value>> {
+    let content = fs::read_to_string(path).ok()?;
+    match serde_json::from_str(&tolerant_json::sanitize(&content)) {
+        Ok(Value::Object(map)) => Some(map),
+        _ => None,
+    }
+}
+
+/// Origins 起源与能力定义，目录输入时由 processor 按命名空间汇总处理
+pub struct OriginsFormat;
+
+impl FormatHandler for OriginsFormat {
+    fn matches(&self, path: &Path, config: &AppConfig, _is_ftb_1_21: bool) -> bool {
+        config.origins_mode != OriginsMode::Off && is_origins_file(&path.to_string_lossy())
+    }
+
+    fn process<'a>(
+        &'a self,
+        path: &'a Path,
+        output_root: &'a str,
+        client: &'a OpenAIClient,
+        ctx: Arc<TranslationContext>,
+        token: &'a CancellationToken,
+    ) -> ProcessFuture<'a> {
+        Box::pin(async move { process_origins(&[path.to_path_buf()], output_root, client, &ctx, token).await })
+    }
+}
+
+/// 翻译 Origins 数据包中的名称与描述
+/// - Copy: 输出翻译后的定义副本到 datapack/，覆盖原数据包
+/// - Lang: 以原文为翻译键写入 assets/<命名空间>/lang/，Origins 会将字符串名称作为可翻译文本解析
+pub async fn process_origins(
+    files: &[PathBuf],
+    output_root: &str,
+    client: &OpenAIClient,
+    ctx: &TranslationContext,
+    token: &CancellationToken,
+) -> Result<()> {
+    log_info!("处理 Origins 定义: {} 个文件", files.len());
+    match ctx.origins_mode {
+        OriginsMode::Off => Ok(()),
+        OriginsMode::Copy => translate_copies(files, output_root, client, ctx, token).await,
+        OriginsMode::Lang => write_lang_overrides(files, output_root, client, ctx, token).await,
+    }
+}
+
+async fn translate_copies(
+    files: &[PathBuf],
+    output_root: &str,
+    client: &OpenAIClient,
+    ctx: &TranslationContext,
+    token: &CancellationToken,
+) -> Result<()> {
+    for path in files {
+        if token.is_cancelled() {
+            break;
+        }
+
+        let output_path = datapack_output_path(Path::new(output_root), &path.to_string_lossy());
+        let output_path = ctx.map_output_path(Path::new(output_root), output_path);
+        if ctx.skip_existing_output(&output_path) {
+            continue;
+        }
+        let Some(mut definition) = read_definition(path) else {
+            log_err!("JSON 解析失败: {}", path.display());
+            continue;
+        };
+
+        let mut src_map = Map::new();
+        for field in FIELDS {
+            if let Some(text) = definition.get(field).and_then(field_text) {
+                src_map.insert(field.to_string(), Value::String(text.to_string()));
+            }
+        }
+        if src_map.is_empty() {
+            continue;
+        }
+
+        let (namespace, id) = origins_id(path);
+        let (translated, _) = execute_translation_batches(src_map, client, &format!("{}:{}", namespace, id), ctx, token).await;
+        if token.is_cancelled() {
+            break;
+        }
+
+        for (field, value) in translated {
+            let Some(trans_val) = value.as_str() else { continue };
+            match definition.get_mut(&field) {
+                Some(Value::Object(obj)) => {
+                    obj.insert("text".to_string(), Value::String(trans_val.to_string()));
+                }
+                Some(slot) => *slot = Value::String(trans_val.to_string()),
+                None => {}
+            }
+        }
+
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&output_path, serde_json::to_string_pretty(&definition)?)?;
+        write_pack_meta(&output_path, ctx.mc_version)?;
+        log_success!("Origins 定义翻译完成: {:?}", output_path);
+    }
+    Ok(())
+}
+
+async fn write_lang_overrides(
+    files: &[PathBuf],
+    output_root: &str,
+    client: &OpenAIClient,
+    ctx: &TranslationContext,
+    token: &CancellationToken,
+) -> Result<()> {
+    // 按命名空间汇总，每个命名空间只写一个语言文件
+    let mut by_namespace: BTreeMap<String, Map<String, Value>> = BTreeMap::new();
+    for path in files {
+        let Some(definition) = read_definition(path) else {
+            log_err!("JSON 解析失败: {}", path.display());
+            continue;
+        };
+        let (namespace, _) = origins_id(path);
+        let entries = by_namespace.entry(namespace).or_default();
+        for field in FIELDS {
+            if let Some(value @ Value::String(text)) = definition.get(field)
+                && field_text(value).is_some()
+            {
+                entries.insert(text.clone(), Value::String(text.clone()));
+            }
+        }
+    }
+
+    let source_name = format!("{}.json", ctx.source_lang);
+    let target_name = get_target_filename(&source_name, &ctx.source_lang, &ctx.target_lang);
+    for (namespace, src_map) in by_namespace {
+        if token.is_cancelled() {
+            break;
+        }
+
+        // 与同命名空间已生成的语言文件合并，已有的条目不再翻译
+        let final_path = ctx.lang_output_path(Path::new(output_root), &namespace, &target_name);
+        let mut base_map = read_map_from_file(&final_path, FileFormat::Json).unwrap_or_default();
+        let pending: Map<String, Value> = src_map
+            .into_iter()
+            .filter(|(k, _)| !base_map.contains_key(k))
+            .collect();
+        if pending.is_empty() || ctx.exceeds_entry_limit(&final_path.to_string_lossy(), pending.len()) {
+            continue;
+        }
+
+        let (translated, _) = execute_translation_batches(pending, client, &namespace, ctx, token).await;
+        if token.is_cancelled() {
+            break;
+        }
+        base_map.extend(translated);
+        write_map_to_file(&final_path, &base_map, FileFormat::Json)?;
+        log_success!("Origins 语言键生成完成 (ModID: {}): {:?}", namespace, final_path);
+    }
+    Ok(())
+}
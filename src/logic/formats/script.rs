@@ -0,0 +1,172 @@
+use std::path::{Path, PathBuf};
+use std::fs;
+use std::io::Write;
+use std::ops::Range;
+use std::sync::Arc;
+use regex::Regex;
+use std::ffi::OsString;
+use tokio_util::sync::CancellationToken;
+use crate::logic::error::Result;
+use crate::logic::openai::OpenAIClient;
+use crate::config::AppConfig;
+use crate::logic::formats::{FormatHandler, has_extension, ProcessFuture};
+use crate::logic::common::{SkipReason, TranslationContext, execute_translation_batches, localize_segment};
+use crate::{log_info, log_success};
+
+/// JS 字符串字面量（单引号或双引号）
+const STR_LITERAL: &str = r#""(?:[^"\\\n]|\\.)*"|'(?:[^'\\\n]|\\.)*'"#;
+
+/// KubeJS 客户端脚本中的思索文本与 JEI 信息页
+pub struct ScriptFormat;
+
+impl FormatHandler for ScriptFormat {
+    fn matches(&self, path: &Path, _config: &AppConfig, _is_ftb_1_21: bool) -> bool {
+        has_extension(path, "js") && path.components().any(|c| c.as_os_str().eq_ignore_ascii_case("client_scripts"))
+    }
+
+    fn process<'a>(
+        &'a self,
+        path: &'a Path,
+        output_root: &'a str,
+        client: &'a OpenAIClient,
+        ctx: Arc<TranslationContext>,
+        token: &'a CancellationToken,
+    ) -> ProcessFuture<'a> {
+        Box::pin(process_script(path, output_root, client, ctx, token))
+    }
+}
+
+/// 处理 KubeJS 客户端脚本中的思索 (Ponder) 文本与 JEI 信息页
+pub async fn process_script(
+    file_path: &Path,
+    output_root: &str,
+    client: &OpenAIClient,
+    ctx: Arc<TranslationContext>,
+    token: &CancellationToken,
+) -> Result<()> {
+    let file_stem = file_path.file_stem().unwrap_or_default().to_string_lossy();
+
+    let output_path = if let Some(idx) = file_path
+        .components()
+        .position(|c| c.as_os_str() == "kubejs")
+    {
+        let relative_path: PathBuf = file_path.components().skip(idx).collect();
+        let localized_path: PathBuf = relative_path
+            .iter()
+            .map(|c| {
+                let s = localize_segment(&c.to_string_lossy(), &ctx.source_lang, &ctx.target_lang);
+                OsString::from(s)
+            })
+            .collect();
+        Path::new(output_root).join(localized_path)
+    } else {
+        Path::new(output_root).join(file_path.file_name().unwrap())
+    };
+    let output_path = ctx.map_output_path(Path::new(output_root), output_path);
+    if ctx.skip_existing_output(&output_path) {
+        return Ok(());
+    }
+
+    let content = fs::read_to_string(file_path)?;
+    let literals = extract_literals(&content);
+    if literals.is_empty() {
+        log_info!("未发现可翻译内容: {}", file_path.display());
+        ctx.stats.record_skip(SkipReason::Empty);
+        return Ok(());
+    }
+
+    let mut extracted_map = serde_json::Map::new();
+    for (i, (_, text)) in literals.iter().enumerate() {
+        extracted_map.insert(i.to_string(), serde_json::Value::String(text.clone()));
+    }
+    if ctx.exceeds_entry_limit(&file_path.to_string_lossy(), extracted_map.len()) {
+        return Ok(());
+    }
+    log_info!("提取到 {} 条条目，开始翻译 [{:?}]", extracted_map.len(), file_path);
+
+    let (translated_map, _) = execute_translation_batches(
+        extracted_map,
+        client,
+        &format!("Script_{}", file_stem),
+        &ctx,
+        token,
+    ).await;
+
+    if token.is_cancelled() {
+        return Ok(());
+    }
+
+    // 从后往前回填，整个字面量替换为双引号字符串，避免引号转义问题
+    let mut new_content = content.clone();
+    for (i, (range, _)) in literals.iter().enumerate().rev() {
+        if let Some(trans_val) = translated_map.get(&i.to_string()).and_then(|v| v.as_str())
+            && !trans_val.is_empty()
+        {
+            let literal = serde_json::to_string(trans_val).unwrap_or_default();
+            new_content.replace_range(range.clone(), &literal);
+        }
+    }
+
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut out_file = fs::File::create(&output_path)?;
+    out_file.write_all(new_content.as_bytes())?;
+
+    log_success!("脚本翻译完成: {:?}", output_path);
+    Ok(())
+}
+
+/// 提取思索场景标题/文本与 JEI 信息页的字符串，返回 (字面量位置, 解码后的文本)，按位置排序
+fn extract_literals(content: &str) -> Vec<(Range<usize>, String)> {
+    // scene.text(60, "...")、scene.overlay.showText(60).text("...")、scene.scene("id", "标题", ...)
+    let re_ponder = Regex::new(&format!(
+        r#"\.(?:text|sharedText|showText)\s*\(\s*(?:\d+\s*,\s*)?({0})|\.scene\s*\(\s*(?:{0})\s*,\s*({0})"#,
+        STR_LITERAL
+    )).unwrap();
+    // JEIEvents.information(e => e.addItem('id', ['...']))、onEvent('jei.information', e => e.add(...))
+    let re_jei = Regex::new(&format!(
+        r#"\b(?:addInformation|addItem|addFluid|add)\s*\([^\[\)]*\[((?:[^\]"']|{0})*)\]"#,
+        STR_LITERAL
+    )).unwrap();
+    let re_str = Regex::new(STR_LITERAL).unwrap();
+
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for caps in re_ponder.captures_iter(content) {
+        if let Some(m) = caps.get(1).or_else(|| caps.get(2)) {
+            ranges.push(m.range());
+        }
+    }
+    if content.contains("information") {
+        for caps in re_jei.captures_iter(content) {
+            if let Some(block) = caps.get(1) {
+                for m in re_str.find_iter(block.as_str()) {
+                    ranges.push(block.start() + m.start()..block.start() + m.end());
+                }
+            }
+        }
+    }
+    ranges.sort_by_key(|r| r.start);
+    ranges.dedup_by_key(|r| r.start);
+
+    let re_id = Regex::new(r"^[a-z0-9_.\-]+:[a-z0-9_/.\-]+$").unwrap();
+    ranges
+        .into_iter()
+        .filter_map(|range| {
+            let text = decode_literal(&content[range.clone()])?;
+            let translatable = text.chars().any(|c| c.is_alphabetic()) && !re_id.is_match(&text);
+            translatable.then_some((range, text))
+        })
+        .collect()
+}
+
+/// 将 JS 字符串字面量解码为文本，无法解析时返回 None
+fn decode_literal(literal: &str) -> Option<String> {
+    let inner = &literal[1..literal.len() - 1];
+    let json = if literal.starts_with('\'') {
+        format!("\"{}\"", inner.replace("\\'", "'").replace('"', "\\\""))
+    } else {
+        format!("\"{}\"", inner.replace("\\'", "'"))
+    };
+    serde_json::from_str(&json).ok()
+}
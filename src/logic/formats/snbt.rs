@@ -1,32 +1,113 @@
 use std::path::{Path, PathBuf};
 use std::fs;
 use std::io::Write;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use regex::Regex;
 use std::ffi::OsString;
 use tokio_util::sync::CancellationToken;
+use crate::logic::error::Result;
 use crate::logic::openai::OpenAIClient;
-use crate::logic::common::{TranslationContext, execute_translation_batches};
+use crate::config::{AppConfig, QuestMode};
+use crate::logic::formats::{FormatHandler, has_extension, ProcessFuture};
+use crate::logic::common::{
+    FileFormat, SkipReason, TranslationContext, execute_translation_batches, get_target_filename, localize_segment,
+    read_map_from_file, write_map_to_file,
+};
 use crate::{log_info, log_success};
 
+/// 1.21+ 的 FTB Quests 将文本放在 lang/<语言>.snbt 或 lang/<语言>/ 目录下
+pub fn is_quest_lang_file(path: &Path, source_lang: &str) -> bool {
+    let components: Vec<_> = path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect();
+    if let Some(idx) = components.iter().rposition(|c| c.eq_ignore_ascii_case("lang"))
+        && let Some(next_comp) = components.get(idx + 1)
+    {
+        if idx + 2 == components.len() {
+            return path.file_stem().is_some_and(|s| s.eq_ignore_ascii_case(source_lang));
+        }
+        return next_comp.eq_ignore_ascii_case(source_lang);
+    }
+    false
+}
+
+/// 语言键模式下任务文本所在的命名空间
+const QUEST_NAMESPACE: &str = "ftbquests";
+// 各章节并发处理，共用同一组语言文件，读改写期间互斥
+static QUEST_LANG_LOCK: Mutex<()> = Mutex::new(());
+
+/// 语言键模式下的键前缀与语言文件位置
+pub struct QuestKeys<'a> {
+    prefix: String,
+    output_root: &'a Path,
+}
+
+/// 键前缀取任务目录 (quests 或 ftbquests) 之后的相对路径，如 ftbquests.chapters.getting_started，
+/// 不同目录下的同名章节不会冲突
+fn quest_key_prefix(file_path: &Path) -> String {
+    let components: Vec<String> = file_path
+        .with_extension("")
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_lowercase())
+        .collect();
+    let start = components
+        .iter()
+        .rposition(|c| c == "quests" || c == "ftbquests")
+        .map_or(components.len().saturating_sub(1), |i| i + 1);
+    let parts: Vec<String> = components[start..]
+        .iter()
+        .map(|c| c.chars().map(|ch| if ch.is_ascii_alphanumeric() { ch } else { '_' }).collect())
+        .collect();
+    format!("{}.{}", QUEST_NAMESPACE, parts.join("."))
+}
+
+/// 把条目合并进已有的语言文件，同名键以本次为准
+fn merge_into_lang_file(path: &Path, entries: serde_json::Map<String, serde_json::Value>) -> Result<()> {
+    let _guard = QUEST_LANG_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut map = read_map_from_file(path, FileFormat::Json).unwrap_or_default();
+    map.extend(entries);
+    write_map_to_file(path, &map, FileFormat::Json)
+}
+
+/// FTB Quests 任务文件，1.21+ 结构下只处理 lang 目录中的源语言文件
+pub struct SnbtFormat;
+
+impl FormatHandler for SnbtFormat {
+    fn matches(&self, path: &Path, config: &AppConfig, is_ftb_1_21: bool) -> bool {
+        has_extension(path, "snbt") && (!is_ftb_1_21 || is_quest_lang_file(path, &config.source_lang))
+    }
+
+    fn process<'a>(
+        &'a self,
+        path: &'a Path,
+        output_root: &'a str,
+        client: &'a OpenAIClient,
+        ctx: Arc<TranslationContext>,
+        token: &'a CancellationToken,
+    ) -> ProcessFuture<'a> {
+        Box::pin(process_snbt(path, output_root, client, ctx, token))
+    }
+}
+
 pub async fn process_snbt(
     file_path: &Path,
     output_root: &str,
     client: &OpenAIClient,
     ctx: Arc<TranslationContext>,
     token: &CancellationToken,
-) -> anyhow::Result<()> {
+) -> Result<()> {
     let file_stem = file_path.file_stem().unwrap_or_default().to_string_lossy();
 
+    // 保留 config/ 或 kubejs/ (KubeJS 数据包中的任务) 之后的相对路径
     let output_path = if let Some(idx) = file_path
         .components()
-        .position(|c| c.as_os_str() == "config")
+        .position(|c| c.as_os_str() == "config" || c.as_os_str() == "kubejs")
     {
         let relative_path: PathBuf = file_path.components().skip(idx).collect();
         let locaized_path: PathBuf = relative_path
             .iter()
             .map(|c| {
-                let s = c.to_string_lossy().replace(&ctx.source_lang, &ctx.target_lang);
+                let s = localize_segment(&c.to_string_lossy(), &ctx.source_lang, &ctx.target_lang);
                 OsString::from(s)
             })
             .collect();
@@ -34,13 +115,32 @@ pub async fn process_snbt(
     } else {
         Path::new(output_root).join(file_path.file_name().unwrap())
     };
-    if ctx.skip_existing && output_path.exists() {
-        log_success!("跳过已存在的文件: {:?}", output_path);
+    let output_path = ctx.map_output_path(Path::new(output_root), output_path);
+    if ctx.skip_existing_output(&output_path) {
         return Ok(());
     }
 
     let content = fs::read_to_string(file_path)?;
-    
+    // 1.21+ 的 lang/*.snbt 本身就是语言文件，始终直接翻译
+    let keys = (ctx.quest_mode == QuestMode::LangKeys && !is_quest_lang_file(file_path, &ctx.source_lang))
+        .then(|| QuestKeys { prefix: quest_key_prefix(file_path), output_root: Path::new(output_root) });
+    translate_snbt_content(&content, &file_path.display().to_string(), &file_stem, &output_path, client, ctx, token, keys)
+        .await
+}
+
+/// 提取 SNBT 文本中的标题与描述，翻译后回填并写入 `output_path`；
+/// 传入 `keys` 时改为回填 {键}，原文与译文写入语言文件
+#[allow(clippy::too_many_arguments)]
+pub async fn translate_snbt_content(
+    content: &str,
+    source_name: &str,
+    file_stem: &str,
+    output_path: &Path,
+    client: &OpenAIClient,
+    ctx: Arc<TranslationContext>,
+    token: &CancellationToken,
+    keys: Option<QuestKeys<'_>>,
+) -> Result<()> {
     let mut extracted_map = serde_json::Map::new();
     let mut replacements = Vec::new(); // 存储 (Range, KeyIndex) 以便回填
 
@@ -55,10 +155,10 @@ pub async fn process_snbt(
     let mut counter = 0;
 
     // 提取 Title/Subtitle
-    for caps in re_kv.captures_iter(&content) {
+    for caps in re_kv.captures_iter(content) {
         if let Some(val_match) = caps.get(2) {
             if counter == 0 && re_trans_key.is_match(val_match.as_str()) {
-                log_info!("检测到本地化键值 '{}'，跳过文件: {:?}", val_match.as_str(), file_path);
+                log_info!("检测到本地化键值 '{}'，跳过文件: {}", val_match.as_str(), source_name);
                 return Ok(());
             }
 
@@ -73,14 +173,14 @@ pub async fn process_snbt(
     }
 
     // 提取 Description
-    for caps in re_desc_block.captures_iter(&content) {
+    for caps in re_desc_block.captures_iter(content) {
         if let Some(block) = caps.get(1) {
             let block_start = block.start();
             // 在 description 列表内部再次查找字符串
             for str_caps in re_str.captures_iter(block.as_str()) {
                 if let Some(inner_match) = str_caps.get(1) {
                     if counter == 0 && re_trans_key.is_match(inner_match.as_str()) {
-                         log_info!("检测到本地化键值 '{}'，跳过文件: {:?}", inner_match.as_str(), file_path);
+                         log_info!("检测到本地化键值 '{}'，跳过文件: {}", inner_match.as_str(), source_name);
                          return Ok(());
                     }
 
@@ -101,15 +201,20 @@ pub async fn process_snbt(
     }
 
     if extracted_map.is_empty() {
-        log_info!("未发现可翻译内容: {}", file_path.display());
+        log_info!("未发现可翻译内容: {}", source_name);
+        ctx.stats.record_skip(SkipReason::Empty);
         return Ok(());
     }
 
-    log_info!("提取到 {} 条条目，开始翻译 [{:?}]", extracted_map.len(), file_path);
+    if ctx.exceeds_entry_limit(source_name, extracted_map.len()) {
+        return Ok(());
+    }
+
+    log_info!("提取到 {} 条条目，开始翻译 [{}]", extracted_map.len(), source_name);
 
     // 这里 mod_id 传入 "ftbquests" 或文件名作为标识
-    let translated_map = execute_translation_batches(
-        &extracted_map, 
+    let (translated_map, _) = execute_translation_batches(
+        extracted_map, 
         client, 
         &format!("Quest_{}", file_stem), 
         &ctx, 
@@ -120,12 +225,43 @@ pub async fn process_snbt(
         return Ok(());
     }
 
-    // 回填内容，根据 Range 的 start 从大到小排序
-    replacements.sort_by(|a, b| b.0.start.cmp(&a.0.start));
-
-    let mut new_content = content.clone();
+    // 原文即文件中对应位置的文本，不必另外保留提取时的表
+    let mut new_content = content.to_string();
+    if let Some(keys) = keys {
+        // 任务文件中只留 {键}，翻译失败的条目按 failed_entries 以原文写入或不写入目标语言文件
+        let lang_key = |key: &str| format!("{}.{}", keys.prefix, key);
+        // 提取的文本仍带有 SNBT 转义 (如 \")，写入语言文件前还原
+        let unescape = |raw: &str| {
+            let text = serde_json::from_str::<String>(&format!("\"{}\"", raw)).unwrap_or_else(|_| raw.to_string());
+            serde_json::Value::String(text)
+        };
+        let source_entries = replacements
+            .iter()
+            .map(|(range, k)| (lang_key(k), unescape(&content[range.clone()])))
+            .collect();
+        let target_entries = translated_map
+            .iter()
+            .map(|(k, v)| (lang_key(k), unescape(v.as_str().unwrap_or_default())))
+            .collect();
+        let count = replacements.len();
+        // 回填内容，根据 Range 的 start 从大到小排序
+        replacements.sort_by_key(|r| std::cmp::Reverse(r.0.start));
+        for (range, key) in replacements.drain(..) {
+            new_content.replace_range(range, &format!("{{{}}}", lang_key(&key)));
+        }
+        let source_name = format!("{}.json", ctx.source_lang);
+        let target_name = get_target_filename(&source_name, &ctx.source_lang, &ctx.target_lang);
+        let source_path = ctx.lang_output_path(keys.output_root, QUEST_NAMESPACE, &source_name);
+        let target_path = ctx.lang_output_path(keys.output_root, QUEST_NAMESPACE, &target_name);
+        merge_into_lang_file(&source_path, source_entries)?;
+        merge_into_lang_file(&target_path, target_entries)?;
+        log_info!("已将 {} 条任务文本替换为语言键 ({}.*)", count, keys.prefix);
+    }
+    replacements.sort_by_key(|r| std::cmp::Reverse(r.0.start));
     for (range, key) in replacements {
-        if let Some(trans_val) = translated_map.get(&key).and_then(|v| v.as_str()) {
+        // 以原文保留的失败条目不再转义写回，保持文件原样
+        let translated = translated_map.get(&key).filter(|v| v.as_str() != Some(&content[range.clone()]));
+        if let Some(trans_val) = translated.and_then(|v| v.as_str()) {
             let escaped_json_string = serde_json::to_string(trans_val).unwrap_or_default();
             // 仅当翻译结果不为空时替换
             if escaped_json_string.len() >= 2 {
@@ -139,7 +275,7 @@ pub async fn process_snbt(
     if let Some(parent) = output_path.parent() {
         fs::create_dir_all(parent)?;
     }
-    let mut out_file = fs::File::create(&output_path)?;
+    let mut out_file = fs::File::create(output_path)?;
     out_file.write_all(new_content.as_bytes())?;
 
     log_success!("SNBT 翻译完成: {:?}", output_path);
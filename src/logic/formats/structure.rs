@@ -0,0 +1,217 @@
+use crate::logic::common::{SkipReason, TranslationContext, datapack_output_path, execute_translation_batches, write_pack_meta};
+use crate::logic::nbt::{NbtFile, Tag, fits_string};
+use crate::logic::error::Result;
+use crate::logic::openai::OpenAIClient;
+use crate::config::AppConfig;
+use crate::logic::formats::{FormatHandler, ProcessFuture};
+use crate::{log_info, log_success, log_warn};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+
+/// 结构文件: data/<命名空间>/structure(s)/**/*.nbt
+pub fn is_structure_file(path: &str) -> bool {
+    let path = path.replace('\\', "/");
+    path.ends_with(".nbt") && path.split('/').any(|c| c == "structures" || c == "structure")
+}
+
+/// 结构文件中的书与告示牌
+pub struct StructureFormat;
+
+impl FormatHandler for StructureFormat {
+    fn matches(&self, path: &Path, config: &AppConfig, _is_ftb_1_21: bool) -> bool {
+        config.translate_structures && is_structure_file(&path.to_string_lossy())
+    }
+
+    fn process<'a>(
+        &'a self,
+        path: &'a Path,
+        output_root: &'a str,
+        client: &'a OpenAIClient,
+        ctx: Arc<TranslationContext>,
+        token: &'a CancellationToken,
+    ) -> ProcessFuture<'a> {
+        Box::pin(process_structure(path, output_root, client, ctx, token))
+    }
+}
+
+pub async fn process_structure(
+    file_path: &Path,
+    output_root: &str,
+    client: &OpenAIClient,
+    ctx: Arc<TranslationContext>,
+    token: &CancellationToken,
+) -> Result<()> {
+    let output_path = datapack_output_path(Path::new(output_root), &file_path.to_string_lossy());
+    let output_path = ctx.map_output_path(Path::new(output_root), output_path);
+    if ctx.skip_existing_output(&output_path) {
+        return Ok(());
+    }
+
+    let data = fs::read(file_path)?;
+    let file_stem = file_path.file_stem().unwrap_or_default().to_string_lossy();
+    translate_structure_bytes(&data, &file_path.display().to_string(), &file_stem, &output_path, client, ctx, token).await
+}
+
+/// 翻译结构中成书/书与笔的书页与告示牌文字，写入 `output_path`
+pub async fn translate_structure_bytes(
+    data: &[u8],
+    source_name: &str,
+    file_stem: &str,
+    output_path: &Path,
+    client: &OpenAIClient,
+    ctx: Arc<TranslationContext>,
+    token: &CancellationToken,
+) -> Result<()> {
+    let mut nbt = NbtFile::from_bytes(data)?;
+
+    let mut extracted_map = serde_json::Map::new();
+    visit_texts(&mut nbt.root, &mut |s| {
+        map_component(s, &mut |text| {
+            extracted_map.insert(extracted_map.len().to_string(), Value::String(text.clone()));
+        })
+    });
+
+    if extracted_map.is_empty() {
+        log_info!("未发现可翻译内容: {}", source_name);
+        ctx.stats.record_skip(SkipReason::Empty);
+        return Ok(());
+    }
+    if ctx.exceeds_entry_limit(source_name, extracted_map.len()) {
+        return Ok(());
+    }
+    log_info!("提取到 {} 条条目，开始翻译 [{}]", extracted_map.len(), source_name);
+
+    let (translated_map, _) = execute_translation_batches(
+        extracted_map,
+        client,
+        &format!("Structure_{}", file_stem),
+        &ctx,
+        token,
+    ).await;
+
+    if token.is_cancelled() {
+        return Ok(());
+    }
+
+    // 按提取时的顺序回填
+    let mut counter = 0;
+    let mut too_long = 0;
+    visit_texts(&mut nbt.root, &mut |s| {
+        let original = s.clone();
+        map_component(s, &mut |text| {
+            if let Some(trans_val) = translated_map.get(&counter.to_string()).and_then(|v| v.as_str())
+                && !trans_val.is_empty()
+            {
+                *text = trans_val.to_string();
+            }
+            counter += 1;
+        });
+        // 超出 NBT 字符串长度上限的译文无法写入，保留原标签
+        if !fits_string(s) {
+            *s = original;
+            too_long += 1;
+        }
+    });
+    if too_long > 0 {
+        log_warn!("{} 处译文超出 NBT 字符串长度上限，保留原文 [{}]", too_long, source_name);
+    }
+
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(output_path, nbt.to_bytes()?)?;
+    write_pack_meta(output_path, ctx.mc_version)?;
+
+    log_success!("结构翻译完成: {:?}", output_path);
+    Ok(())
+}
+
+/// 遍历告示牌文字 (Text1-4、front_text/back_text.messages) 与书页 (pages、title)
+fn visit_texts(tag: &mut Tag, f: &mut dyn FnMut(&mut String)) {
+    match tag {
+        Tag::Compound(entries) => {
+            for (key, value) in entries.iter_mut() {
+                match (key.as_str(), value) {
+                    ("Text1" | "Text2" | "Text3" | "Text4" | "title", Tag::String(s)) => f(s),
+                    ("messages" | "pages" | "title", value) => visit_raw_strings(value, f),
+                    (_, value) => visit_texts(value, f),
+                }
+            }
+        }
+        Tag::List(_, items) => {
+            for item in items {
+                visit_texts(item, f);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 字符串列表，或 1.20.5+ 组件中的 {raw: "..."} 结构
+fn visit_raw_strings(tag: &mut Tag, f: &mut dyn FnMut(&mut String)) {
+    match tag {
+        Tag::String(s) => f(s),
+        Tag::List(_, items) => {
+            for item in items {
+                visit_raw_strings(item, f);
+            }
+        }
+        Tag::Compound(entries) => {
+            for (key, value) in entries.iter_mut() {
+                if key == "raw"
+                    && let Tag::String(s) = value
+                {
+                    f(s);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 对文本组件中每段可翻译文字调用 `f`；非 JSON 的字符串视为纯文本整体处理
+fn map_component(s: &mut String, f: &mut dyn FnMut(&mut String)) {
+    match serde_json::from_str::<Value>(s) {
+        Ok(mut value @ (Value::String(_) | Value::Object(_) | Value::Array(_))) => {
+            let before = value.clone();
+            map_component_value(&mut value, f);
+            if value != before {
+                *s = serde_json::to_string(&value).unwrap_or_else(|_| s.clone());
+            }
+        }
+        _ => {
+            if is_translatable(s) {
+                f(s);
+            }
+        }
+    }
+}
+
+fn map_component_value(value: &mut Value, f: &mut dyn FnMut(&mut String)) {
+    match value {
+        Value::String(text) if is_translatable(text) => f(text),
+        Value::Array(items) => {
+            for item in items {
+                map_component_value(item, f);
+            }
+        }
+        Value::Object(obj) => {
+            if let Some(Value::String(text)) = obj.get_mut("text")
+                && is_translatable(text)
+            {
+                f(text);
+            }
+            if let Some(extra) = obj.get_mut("extra") {
+                map_component_value(extra, f);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_translatable(text: &str) -> bool {
+    text.chars().any(|c| c.is_alphabetic())
+}
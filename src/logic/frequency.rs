@@ -0,0 +1,80 @@
+use crate::config::AppConfig;
+use crate::logic::common::{FileFormat, extract_mod_id, read_map_from_file};
+use crate::logic::formats::jar;
+use crate::logic::processor::scan_input_files;
+use crate::log_warn;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+const FREQUENT_LIMIT: usize = 200;
+const LONGEST_LIMIT: usize = 50;
+
+#[derive(Debug, Clone)]
+pub struct LongString {
+    pub mod_id: String,
+    pub key: String,
+    pub text: String,
+}
+
+/// 整个整合包源语言字符串的统计结果
+#[derive(Debug, Clone, Default)]
+pub struct StringStats {
+    pub total: usize,
+    pub unique: usize,
+    pub frequent: Vec<(String, usize)>, // 出现两次以上的字符串，按次数降序
+    pub longest: Vec<LongString>,
+}
+
+/// 读取输入目录中所有源语言文件，返回 (ModID, 条目) 列表
+pub fn collect_source_maps(config: &AppConfig) -> Vec<(String, Map<String, Value>)> {
+    let mut maps: Vec<(String, Map<String, Value>)> = Vec::new();
+    for path in scan_input_files(config) {
+        let ext = path.extension().unwrap_or_default().to_string_lossy().to_lowercase();
+        match ext.as_str() {
+            "jar" => match jar::read_source_maps(&path, &config.source_lang, &config.source_lang_fallbacks) {
+                Ok(found) => maps.extend(found),
+                Err(e) => log_warn!("读取 JAR 失败 {:?}: {}", path, e),
+            },
+            "json" | "lang" => {
+                let format = if ext == "lang" { FileFormat::Lang } else { FileFormat::Json };
+                if let Ok(map) = read_map_from_file(&path, format) {
+                    maps.push((extract_mod_id(&path), map));
+                }
+            }
+            _ => {}
+        }
+    }
+    maps
+}
+
+/// 扫描输入目录，统计所有源语言文件中的高频字符串与最长字符串
+pub fn analyze_strings(config: &AppConfig) -> StringStats {
+    let maps = collect_source_maps(config);
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    let mut longest: Vec<LongString> = Vec::new();
+    let mut total = 0;
+    for (mod_id, map) in &maps {
+        for (key, value) in map {
+            let Some(text) = value.as_str().map(str::trim).filter(|t| !t.is_empty()) else {
+                continue;
+            };
+            total += 1;
+            *counts.entry(text).or_default() += 1;
+            longest.push(LongString { mod_id: mod_id.clone(), key: key.clone(), text: text.to_string() });
+        }
+    }
+
+    let unique = counts.len();
+    let mut frequent: Vec<(String, usize)> = counts
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|(text, count)| (text.to_string(), count))
+        .collect();
+    frequent.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    frequent.truncate(FREQUENT_LIMIT);
+
+    longest.sort_by_key(|s| std::cmp::Reverse(s.text.chars().count()));
+    longest.truncate(LONGEST_LIMIT);
+
+    StringStats { total, unique, frequent, longest }
+}
@@ -0,0 +1,120 @@
+use crate::config::AppConfig;
+use crate::logic::frequency::collect_source_maps;
+use crate::logic::merge::read_lang_files;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// 至少在这么多条译文中出现才会被推荐
+const MIN_SUPPORT: usize = 3;
+/// 含该词的译文中，候选译名需要覆盖的比例
+const MIN_COVERAGE: f32 = 0.8;
+const MAX_SUGGESTIONS: usize = 100;
+const STOP_WORDS: [&str; 12] = ["the", "and", "for", "with", "from", "this", "that", "your", "you", "are", "not", "can"];
+
+#[derive(Debug, Clone)]
+pub struct GlossarySuggestion {
+    pub source: String,
+    pub target: String,
+    pub support: usize, // 支持该译名的条目数
+    pub accepted: bool,
+}
+
+/// 提示词中已有的术语表行，用于过滤重复推荐
+pub fn existing_terms(prompt: &str) -> HashSet<String> {
+    prompt
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .filter_map(|(src, _)| serde_json::from_str::<String>(src.trim()).ok())
+        .map(|s| s.to_lowercase())
+        .collect()
+}
+
+/// 对照源文件与输出目录中的译文，挖掘反复出现且译法一致的术语
+pub fn suggest_terms(config: &AppConfig) -> Vec<GlossarySuggestion> {
+    let mut sources: HashMap<(String, String), String> = HashMap::new();
+    for (mod_id, map) in collect_source_maps(config) {
+        for (key, value) in map {
+            if let Some(text) = value.as_str() {
+                sources.insert((mod_id.clone(), key), text.to_string());
+            }
+        }
+    }
+
+    let mut pairs: Vec<(String, String)> = Vec::new();
+    for (file_key, _, map) in read_lang_files(Path::new(&config.output_path), &config.target_lang).unwrap_or_default() {
+        let mod_id = file_key.split('/').next().unwrap_or_default().to_string();
+        for (key, value) in map {
+            if let (Some(src), Some(dst)) = (sources.get(&(mod_id.clone(), key)), value.as_str())
+                && src != dst
+            {
+                pairs.push((src.clone(), dst.to_string()));
+            }
+        }
+    }
+
+    // 单词 -> 含该词的短条目译文
+    let mut by_word: HashMap<String, (String, Vec<&str>)> = HashMap::new();
+    for (src, dst) in &pairs {
+        let words: Vec<&str> = src.split_whitespace().collect();
+        if words.len() > 4 {
+            continue;
+        }
+        let unique: HashSet<&str> = words.into_iter().collect();
+        for word in unique {
+            let word = word.trim_matches(|c: char| !c.is_alphanumeric());
+            if word.chars().count() < 3
+                || !word.chars().all(|c| c.is_alphabetic())
+                || STOP_WORDS.contains(&word.to_lowercase().as_str())
+            {
+                continue;
+            }
+            by_word
+                .entry(word.to_lowercase())
+                .or_insert_with(|| (word.to_string(), Vec::new()))
+                .1
+                .push(dst.as_str());
+        }
+    }
+
+    let known = existing_terms(&config.prompt);
+    let mut suggestions: Vec<GlossarySuggestion> = by_word
+        .into_iter()
+        .filter(|(lower, (_, targets))| targets.len() >= MIN_SUPPORT && !known.contains(lower))
+        .filter_map(|(_, (word, targets))| {
+            let (target, support) = common_substring(&targets)?;
+            Some(GlossarySuggestion { source: word, target, support, accepted: true })
+        })
+        .collect();
+    suggestions.sort_by(|a, b| b.support.cmp(&a.support).then_with(|| a.source.cmp(&b.source)));
+    suggestions.truncate(MAX_SUGGESTIONS);
+    suggestions
+}
+
+/// 在多数译文中都出现的最长片段，返回 (片段, 出现次数)
+fn common_substring(targets: &[&str]) -> Option<(String, usize)> {
+    let threshold = ((targets.len() as f32) * MIN_COVERAGE).ceil() as usize;
+    let mut best: Option<(String, usize)> = None;
+    // 候选片段取自较短的译文
+    let shortest = targets.iter().min_by_key(|t| t.chars().count())?;
+    let chars: Vec<char> = shortest.chars().collect();
+    for start in 0..chars.len() {
+        for len in (1..=6.min(chars.len() - start)).rev() {
+            let candidate: String = chars[start..start + len].iter().collect();
+            if !candidate.chars().any(|c| c.is_alphabetic()) || candidate.trim() != candidate {
+                continue;
+            }
+            let support = targets.iter().filter(|t| t.contains(&candidate)).count();
+            if support < threshold {
+                continue;
+            }
+            let better = best
+                .as_ref()
+                .is_none_or(|(b, s)| len > b.chars().count() || (len == b.chars().count() && support > *s));
+            if better {
+                best = Some((candidate, support));
+            }
+            break;
+        }
+    }
+    best
+}
@@ -0,0 +1,106 @@
+// 脚本钩子：加载用户提供的 rhai 脚本，在不修改程序的情况下自定义规则。
+// 脚本中可定义以下函数，未定义的钩子保持默认行为：
+//   on_key_filter(key, value) -> bool        返回 false 时该条目保持原文，不发送翻译
+//   post_translate(key, src, dst) -> String  返回替换后的译文
+//   map_output_path(path) -> String          输出文件相对输出目录的路径，使用 / 分隔
+use crate::{log_info, log_warn};
+use crate::logic::error::{Result, TranslatorError};
+use rhai::{AST, CallFnOptions, Dynamic, Engine, FuncArgs, Scope};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// 单次调用的运算步数上限，避免脚本死循环卡住任务
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+pub struct Hooks {
+    path: String,
+    engine: Engine,
+    ast: AST,
+    scope: Scope<'static>, // 加载时执行顶层语句得到的全局变量
+}
+
+impl fmt::Debug for Hooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Hooks").field("path", &self.path).finish()
+    }
+}
+
+impl Hooks {
+    /// 编译脚本并执行一次顶层语句，语法或执行错误时返回带行号的错误
+    pub fn load(path: &str) -> Result<Self> {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.on_print(|text| log_info!("[脚本] {}", text));
+        engine.on_debug(|text, _, pos| log_info!("[脚本] {} ({})", text, pos));
+        let ast = engine
+            .compile_file(PathBuf::from(path))
+            .map_err(|e| TranslatorError::Other(format!("无法加载脚本 {}: {}", path, e)))?;
+        let mut scope = Scope::new();
+        engine
+            .run_ast_with_scope(&mut scope, &ast)
+            .map_err(|e| TranslatorError::Other(format!("脚本 {} 执行出错: {}", path, e)))?;
+
+        let defined: Vec<&str> = ast.iter_functions().map(|f| f.name).collect();
+        log_info!(
+            "已加载脚本钩子 {} ({})",
+            path,
+            if defined.is_empty() { "未定义任何函数".to_string() } else { defined.join(", ") }
+        );
+        Ok(Self { path: path.to_string(), engine, ast, scope })
+    }
+
+    fn defines(&self, name: &str, params: usize) -> bool {
+        self.ast.iter_functions().any(|f| f.name == name && f.params.len() == params)
+    }
+
+    /// 调用脚本函数，未定义或出错时返回 None，出错时记录警告。
+    /// 顶层语句只在加载时执行一次，每次调用使用其结果的副本
+    fn call<T: Clone + Send + Sync + 'static>(&self, name: &str, params: usize, args: impl FuncArgs) -> Option<T> {
+        if !self.defines(name, params) {
+            return None;
+        }
+        let options = CallFnOptions::new().eval_ast(false).rewind_scope(false);
+        match self.engine.call_fn_with_options::<Dynamic>(options, &mut self.scope.clone(), &self.ast, name, args) {
+            Ok(value) => match value.try_cast::<T>() {
+                Some(value) => Some(value),
+                None => {
+                    log_warn!("脚本函数 {} 的返回值类型不正确，已忽略", name);
+                    None
+                }
+            },
+            Err(e) => {
+                log_warn!("脚本函数 {} 执行出错，已忽略: {}", name, e);
+                None
+            }
+        }
+    }
+
+    /// 条目是否需要翻译
+    pub fn key_filter(&self, key: &str, value: &str) -> bool {
+        self.call::<bool>("on_key_filter", 2, (key.to_string(), value.to_string())).unwrap_or(true)
+    }
+
+    pub fn post_translate(&self, key: &str, src: &str, dst: String) -> String {
+        self.call::<rhai::ImmutableString>("post_translate", 3, (key.to_string(), src.to_string(), dst.clone()))
+            .map(|s| s.to_string())
+            .unwrap_or(dst)
+    }
+
+    /// 重写输出路径，结果不能离开输出目录
+    pub fn map_output_path(&self, output_root: &Path, path: PathBuf) -> PathBuf {
+        let Ok(relative) = path.strip_prefix(output_root) else {
+            return path;
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        let Some(mapped) = self.call::<rhai::ImmutableString>("map_output_path", 1, (relative.clone(),)) else {
+            return path;
+        };
+        let mapped = Path::new(mapped.as_str());
+        let inside = mapped.components().all(|c| matches!(c, std::path::Component::Normal(_)));
+        if !inside || mapped.as_os_str().is_empty() {
+            log_warn!("脚本返回的输出路径 {:?} 无效 (须为输出目录内的相对路径)，沿用 {}", mapped, relative);
+            return path;
+        }
+        output_root.join(mapped)
+    }
+}
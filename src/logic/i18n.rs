@@ -0,0 +1,470 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// 界面语言，与翻译的源/目标语言无关
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum UiLanguage {
+    #[default]
+    #[serde(rename = "zh_cn")]
+    ZhCn,
+    #[serde(rename = "en_us")]
+    EnUs,
+}
+
+impl UiLanguage {
+    pub const ALL: [UiLanguage; 2] = [UiLanguage::ZhCn, UiLanguage::EnUs];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            UiLanguage::ZhCn => "简体中文",
+            UiLanguage::EnUs => "English",
+        }
+    }
+}
+
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+pub fn set_language(lang: UiLanguage) {
+    CURRENT.store(lang as u8, Ordering::Relaxed);
+}
+
+fn current() -> UiLanguage {
+    match CURRENT.load(Ordering::Relaxed) {
+        1 => UiLanguage::EnUs,
+        _ => UiLanguage::ZhCn,
+    }
+}
+
+/// 以中文原文作为键查表，缺失的条目回退为中文
+pub fn tr(key: &'static str) -> &'static str {
+    match current() {
+        UiLanguage::ZhCn => key,
+        UiLanguage::EnUs => en_us(key).unwrap_or(key),
+    }
+}
+
+/// 带参数的文本，按顺序替换其中的 `{}`
+pub fn trf(key: &'static str, args: &[&dyn Display]) -> String {
+    let mut result = String::with_capacity(key.len());
+    let mut rest = tr(key);
+    for arg in args {
+        match rest.find("{}") {
+            Some(idx) => {
+                result.push_str(&rest[..idx]);
+                result.push_str(&arg.to_string());
+                rest = &rest[idx + 2..];
+            }
+            None => break,
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+fn en_us(key: &str) -> Option<&'static str> {
+    let text = match key {
+        // 主界面
+        "Minecraft Mod 汉化助手（支持任务、模组、资源包）" => "Minecraft Mod Translator (quests, mods, resource packs)",
+        "交换语言" => "Swap languages",
+        "扫描:" => "Scan:",
+        "最大深度" => "Max depth",
+        "0 为不限制" => "0 means unlimited",
+        "跟随符号链接" => "Follow symlinks",
+        "进入符号链接指向的目录，如链接到其他位置的 mods/" => "Descend into symlinked directories, e.g. a mods/ folder linked from elsewhere",
+        "跳过隐藏目录" => "Skip hidden folders",
+        "单文件上限:" => "File limits:",
+        "超出则跳过并警告，0 为不限制；JAR 按其中的单个文件计算" => "Larger files are skipped with a warning, 0 means unlimited; JARs are checked per contained file",
+        " 条" => " entries",
+        "待翻译条目超出则跳过并警告，0 为不限制" => "Files with more entries to translate are skipped with a warning, 0 means unlimited",
+        "白名单:" => "Whitelist:",
+        "路径包含其中任一项的文件不受上限限制，逗号分隔" => "Files whose path contains any of these are exempt from the limits (comma separated)",
+        "跳过以 . 开头的目录，启动器实例中的 .minecraft 除外" => "Skip folders starting with a dot, except .minecraft in launcher instances",
+        "备选源语言:" => "Fallback source:",
+        "目录中没有源语言文件时，按顺序改用这些语言的文件作为原文，逗号分隔" => "When a folder has no source language file, use the first of these languages that exists instead (comma separated)",
+        "选择模型:" => "Model:",
+        "🔄 检查 & 刷新" => "🔄 Check & Refresh",
+        "最近一次测试请求的往返延迟" => "Round-trip latency of the last test request",
+        "输入路径:" => "Input path:",
+        "📂 打开文件夹" => "📂 Open folder",
+        "📄 打开文件" => "📄 Open file",
+        "输出目录:" => "Output dir:",
+        "输出结构:" => "Output layout:",
+        "资源包 (assets/)" => "Resource pack (assets/)",
+        "KubeJS (kubejs/assets/)" => "KubeJS (kubejs/assets/)",
+        "KubeJS 结构可直接放入整合包根目录，玩家无需手动启用资源包" => "The KubeJS layout can be dropped into the modpack root; players do not need to enable a resource pack",
+        "📂 选择文件夹" => "📂 Choose folder",
+        "📝 编辑提示词" => "📝 Edit prompt",
+        "自定义发送给 AI 的系统提示词" => "Customize the system prompt sent to the AI",
+        "批次大小:" => "Batch size:",
+        "影响上下文的处理" => "Affects how much context each request sees",
+        "跳过已翻译的文件" => "Skip translated files",
+        "跳过 snbt" => "Skip snbt",
+        "勾选后将不再检查config/ftbquests，只检查kubejs下的本地化文件" => "Skip config/ftbquests and only check localization files under kubejs",
+        "离线伪翻译" => "Offline pseudo",
+        "不请求 API，生成带重音和填充的伪译文，用于测试输出路径和格式代码" => "Generate accented, padded pseudo-translations without calling the API, to test output paths and format codes",
+        "批量模式" => "Batch API",
+        "通过 OpenAI Batch API 提交，最长 24 小时完成，费用约减半；中断后重新运行会继续等待已提交的任务" => "Submit through the OpenAI Batch API: up to 24h latency for about half the cost; rerunning resumes submitted jobs",
+        "完成通知" => "Notify",
+        "任务结束时发送系统通知" => "Send a desktop notification when a run ends",
+        "提示音" => "Sound",
+        "调试日志" => "Debug dump",
+        "将每个批次的原始请求和响应保存到输出目录的 debug 文件夹" => "Save raw requests and responses of every batch to the debug folder in the output directory",
+        "查看长度异常或丢失换行的译文" => "Review translations with abnormal length or lost line breaks",
+        "📋 任务队列 ({})" => "📋 Queue ({})",
+        "添加多个整合包或目录，依次或并行处理" => "Add several packs or folders and process them in sequence or in parallel",
+        "⚖ 模型对比" => "⚖ Compare models",
+        "抽样对比两个模型的翻译效果" => "Compare two models on a sample",
+        "⏳ 正在停止…" => "⏳ Stopping…",
+        "⏳ 处理中..." => "⏳ Processing...",
+        "⏳ 正在检查输出目录…" => "⏳ Checking output directory…",
+        "❌ 取消任务" => "❌ Cancel",
+        "🚀 开始翻译" => "🚀 Translate",
+        "🔄 更新翻译" => "🔄 Update translation",
+        "任务启动..." => "Run started...",
+        "更新任务启动..." => "Update run started...",
+        "批次进度 ({})" => "Batch progress ({})",
+        "⚙ 设置" => "⚙ Settings",
+        "📁 文件" => "📁 Files",
+        "📊 进度" => "📊 Progress",
+        "📜 日志" => "📜 Log",
+        "🔍 审校" => "🔍 Review",
+        "当前没有进行中的批次" => "No batches in flight",
+        "并发 {}/{}" => "Workers {}/{}",
+        "占位符保护" => "Protect placeholders",
+        "{} 个文件存在语法错误，未被处理" => "{} files have syntax errors and were not processed",
+        "行列号等详细原因见日志，修复后使用“更新翻译”重试" => "See the log for line and column details, then fix them and use \"Update translation\" to retry",
+        "该文件已跳过，请按提示的行列号修复语法错误后使用“更新翻译”重试" => "The file was skipped; fix the syntax error at the reported line and column, then use \"Update translation\" to retry",
+        "颜色代码:" => "Color codes:",
+        "保持原样" => "Keep as is",
+        "译文标点:" => "Punctuation:",
+        "任务文件:" => "Quest files:",
+        "直接替换文本" => "Replace text in place",
+        "改为语言键" => "Use lang keys",
+        "改为语言键时，任务文件中的文本替换为 {键}，原文与译文写入 ftbquests 语言文件，任务文件对各语言通用；1.21+ 的 lang/*.snbt 不受影响" => "With lang keys, quest text is replaced by {key} and the source and translated text go into ftbquests lang files, so the quest files work for every language; 1.21+ lang/*.snbt files are not affected",
+        "统一为全角" => "Full-width",
+        "统一为半角" => "Half-width",
+        "按目标语言分别设置，当前为 {}；写出前先做 Unicode 规范化，再转换紧跟中日文字的标点，... 转为 ……" => "Set per target language, currently {}; before writing, values are Unicode-normalized, then punctuation next to CJK text is converted and ... becomes ……",
+        "空值条目:" => "Empty values:",
+        "不写入" => "Drop",
+        "以键名作为值" => "Use key name",
+        "值为空或只含空白的条目不会发给模型；部分模组有意使用空字符串，不写入时游戏会显示原文" => "Empty or whitespace-only values are never sent to the model; some mods use empty strings on purpose, and dropped entries fall back to the source text in game",
+        "{} 个条目的值为空或只含空白，未发给模型" => "{} entries were empty or whitespace-only and were not sent to the model",
+        "失败条目:" => "Failed entries:",
+        "以原文写入" => "Write source text",
+        "批次翻译失败的条目会在运行汇总的“未翻译条目”中列出，“更新翻译”时重新翻译" => "Entries from failed batches are listed under \"Untranslated entries\" in the run summary and are translated again by \"Update translation\"",
+        "&a 转为 §a" => "&a to §a",
+        "§a 转为 &a" => "§a to &a",
+        "全部移除" => "Strip all",
+        "文件名大小写:" => "Filename casing:",
+        "脚本钩子:" => "Script hooks:",
+        "结束后执行:" => "Run after finish:",
+        "游戏目录:" => "Game directory:",
+        "可选择 .minecraft、resourcepacks 或 Prism / CurseForge 的实例目录" => "Choose .minecraft, resourcepacks, or a Prism / CurseForge instance folder",
+        "📂 选择" => "📂 Browse",
+        "写入 options.txt 启用" => "Enable in options.txt",
+        "游戏运行时会覆盖 options.txt，请先关闭游戏" => "The game overwrites options.txt while running, so close it first",
+        "🎮 安装到游戏" => "🎮 Install to game",
+        "把输出目录打包为资源包放入游戏的 resourcepacks 文件夹" => "Pack the output directory as a resource pack into the game's resourcepacks folder",
+        "✅ 已安装 {} 个文件到 {}" => "✅ Installed {} files to {}",
+        "已在 options.txt 中启用资源包" => "Enabled the resource pack in options.txt",
+        "❌ 安装到游戏失败: {}" => "❌ Failed to install to game: {}",
+        "🎮 实例" => "🎮 Instances",
+        "未找到启动器实例" => "No launcher instances found",
+        "🔄 重新查找" => "🔄 Search again",
+        "从官方启动器、Prism/MultiMC 与 CurseForge 的实例中选择输入路径" => "Pick the input path from official launcher, Prism/MultiMC or CurseForge instances",
+        "已选择实例 {}，未找到 mods、kubejs 或 config 目录" => "Selected instance {}; no mods, kubejs or config folder found",
+        "已选择实例 {}，将扫描: {}" => "Selected instance {}; will scan: {}",
+        "服务端分发包:" => "Server bundle:",
+        "留空不生成" => "Leave empty to skip",
+        "输出路径模板:" => "Output path template:",
+        "🗄 原文快照" => "🗄 Source snapshots",
+        "浏览和清理增量更新用于检测原文改动的快照" => "Browse and clean up the snapshots incremental updates use to detect changed source text",
+        "增量更新时与快照比较，原文有改动的条目会重新翻译；删除快照后对应文件只补翻新增条目。" => "Incremental updates compare against these snapshots and retranslate entries whose source changed; without a snapshot only new entries are translated.",
+        "共 {} 个快照，{} KB，位于 {}" => "{} snapshots, {} KB, in {}",
+        "保留天数:" => "Retention days:",
+        "超过该天数未更新的快照在任务开始时清理，0 为永久保留" => "Snapshots not updated for this many days are removed when a task starts; 0 keeps them forever",
+        "🧹 清理过期" => "🧹 Remove expired",
+        "🗑 全部删除" => "🗑 Delete all",
+        "更新时间" => "Updated",
+        "已删除 {} 个原文快照" => "Deleted {} source snapshots",
+        "🔄 刷新" => "🔄 Refresh",
+        "文件" => "File",
+        "大小" => "Size",
+        "输出已存在" => "output exists",
+        "原版命名空间" => "vanilla namespace",
+        "无可翻译内容" => "nothing to translate",
+        "不支持的类型" => "unsupported type",
+        "语法错误" => "syntax error",
+        "超出上限" => "over limit",
+        "重复的模组" => "duplicate mod",
+        "合并模组多版本" => "Merge mod versions",
+        "沿用内置汉化" => "Keep built-in translations",
+        "全量翻译时也沿用模组 JAR 自带的目标语言译文，只翻译缺失的条目" => "In full mode, also keep the target-language translations shipped inside mod jars and translate only the missing entries",
+        "重译未翻译占位" => "Retranslate placeholders",
+        "沿用内置或社区汉化时，与英文原文相同的条目视为未翻译，仍交给模型翻译" => "When reusing built-in or community translations, treat entries identical to the English source as untranslated and send them to the model",
+        "同一模组出现在多个 JAR 中时，合并各版本的条目后只翻译一次，输出对各版本都适用" => "When the same mod appears in several jars, merge the entries of all versions and translate them once so the output works for every version",
+        "跳过原因: {}" => "Skip reasons: {}",
+        "留空按输出结构；可用 {output} {modid} {target} {ext} {filename}，须包含 {modid}" => "Leave empty to follow the output layout; supports {output} {modid} {target} {ext} {filename} and must contain {modid}",
+        "任务完成后生成服务端语言数据包、资源包 zip 与强制加载资源包的 server.properties 片段" => "After the task, generate a server-side language datapack, a resource pack zip and a server.properties snippet that forces the pack",
+        "资源包上传后的直链下载地址，写入 server.properties 的 resource-pack" => "Direct download URL of the uploaded resource pack, written to resource-pack in server.properties",
+        "任务结束后执行的命令，可读取 MCT_STATUS、MCT_OUTPUT、MCT_TRANSLATED_ENTRIES 等环境变量，输出写入日志" => "Command run when the task ends; it can read environment variables such as MCT_STATUS, MCT_OUTPUT and MCT_TRANSLATED_ENTRIES, and its output goes to the log",
+        "rhai 脚本，可定义 on_key_filter(key, value)、post_translate(key, src, dst)、map_output_path(path)，留空不启用" => "A rhai script that may define on_key_filter(key, value), post_translate(key, src, dst) and map_output_path(path); leave empty to disable",
+        "📄 选择" => "📄 Browse",
+        "📈 统计" => "📈 Stats",
+        "记录运行统计" => "Record run statistics",
+        "每次运行结束后在本机记录耗时、token 用量与失败率，不会上传" => "Record duration, token usage and failure rate locally after each run; nothing is uploaded",
+        "清空记录" => "Clear history",
+        "❌ 清空运行统计失败: {}" => "❌ Failed to clear run statistics: {}",
+        "暂无记录。开启后，每次调用 API 的运行结束时会记录一条统计" => "No records yet. Once enabled, every run that calls the API adds one record when it finishes",
+        "按模型汇总 (按每条 token 从低到高)" => "Per model (sorted by tokens per entry, lowest first)",
+        "模型" => "Model",
+        "运行次数" => "Runs",
+        "已翻译条目" => "Translated entries",
+        "失败率" => "Failure rate",
+        "每条 token" => "Tokens / entry",
+        "条目/分钟" => "Entries / min",
+        "服务商未返回用量，按字符数估算" => "The provider did not report usage; estimated from character counts",
+        "最近的运行 ({})" => "Recent runs ({})",
+        "{} · {} · {} · 已翻译 {}，失败 {} · {} token · {} 秒" => "{} · {} · {} · {} translated, {} failed · {} tokens · {} s",
+        "额外输出:" => "Extra outputs:",
+        "➕ 添加输出目标" => "➕ Add output target",
+        "任务完成后把输出目录中的 assets/ 同步到这些目录，路径以 .zip 结尾时打包为资源包" => "After the run, copy assets/ from the output folder to these targets; paths ending in .zip are packed as a resource pack",
+        "自动" => "Auto",
+        "自动：按模组元数据推断的整合包版本决定，无法推断时 .json (1.13+) 一律小写，.lang 沿用源文件的大小写" => "Auto: follow the Minecraft version inferred from mod metadata; if unknown, .json (1.13+) is always lowercase and .lang follows the source file's casing",
+        "写出语言文件前统一颜色代码，便于把插件文本移入资源包或清理杂乱的源文件" => "Normalize color codes before writing lang files, useful when moving plugin strings into resource packs or cleaning up noisy sources",
+        "发送前把 §a、%s、{0} 等格式代码替换为 ⟦P1⟧ 标记，收到译文后还原" => "Replace format codes such as §a, %s and {0} with ⟦P1⟧ tokens before sending and restore them afterwards",
+        "排队 {} 批次" => "{} batches queued",
+        "限流等待 {}" => "{} rate-limited",
+        "服务商返回 429，请求正在等待重试，提高并发不会加快速度" => "The provider returned 429 and requests are waiting to retry; raising concurrency will not help",
+        "已达并发上限" => "Concurrency limit reached",
+        "所有并发许可都在使用中，可在配置文件中提高 max_network_concurrency" => "All concurrency slots are busy; raise max_network_concurrency in the config file",
+        "日志 ({})" => "Log ({})",
+        "复制全部" => "Copy all",
+        "复制此行" => "Copy line",
+        "复制所有错误" => "Copy all errors",
+        "GitHub 主页" => "GitHub",
+        "检查更新" => "Check updates",
+        "启动时从 GitHub 检查是否有新版本" => "Check GitHub for a newer version on startup",
+        "🎉 发现新版本 {}" => "🎉 New version {} available",
+        "前往下载" => "Download",
+        "忽略" => "Dismiss",
+        "关于作者" => "Author",
+        "界面语言" => "Interface language",
+        "界面主题" => "Theme",
+        "跟随系统" => "System",
+        "深色" => "Dark",
+        "浅色" => "Light",
+        "界面缩放" => "UI scale",
+        "● 未保存" => "● Unsaved",
+        "设置修改后会自动保存" => "Settings are saved automatically shortly after a change",
+        // 提示与日志
+        "请先填写 API Key" => "Please fill in the API key first",
+        "请先填写输入路径" => "Please fill in the input path first",
+        "正在连接 API 获取模型列表..." => "Connecting to the API to fetch models...",
+        "✅ 连接成功！获取到 {} 个模型" => "✅ Connected! Fetched {} models",
+        "❌ 连接失败: {}" => "❌ Connection failed: {}",
+        "✅ 模型 {} 测试请求成功，往返延迟 {} ms" => "✅ Test request to {} succeeded, round trip {} ms",
+        "限流信息: {}" => "Rate limits: {}",
+        "❌ 模型 {} 测试请求失败: {}" => "❌ Test request to {} failed: {}",
+        "正在抽取 {} 条样本对比 {} 与 {}..." => "Sampling {} entries to compare {} and {}...",
+        "✅ 模型对比完成，共 {} 条样本" => "✅ Comparison finished, {} samples",
+        "❌ 模型对比失败: {}" => "❌ Comparison failed: {}",
+        "上一个任务仍在运行或停止中，请稍候" => "The previous run is still running or stopping, please wait",
+        "任务队列为空" => "The job queue is empty",
+        "所有更新任务已完成" => "All update jobs finished",
+        "所有翻译任务已完成" => "All translation jobs finished",
+        "正在停止，等待进行中的批次结束..." => "Stopping, waiting for in-flight batches...",
+        "任务已被用户取消" => "Run cancelled by user",
+        // 任务队列
+        "📋 任务队列" => "📋 Job queue",
+        "每个任务使用独立的输入和输出路径，其余设置与主界面相同。" => "Each job has its own input and output paths; all other settings come from the main window.",
+        "➕ 添加当前输入/输出路径" => "➕ Add current input/output paths",
+        "并行执行" => "Run in parallel",
+        "修补 JAR" => "Patch JARs",
+        "翻译结构" => "Translate structures",
+        "翻译配置注释" => "Translate config comments",
+        "📊 字符串分析" => "📊 String analysis",
+        "统计整合包中出现最多和最长的原文，可在正式翻译前把高频术语加入提示词" => "Find the most frequent and longest source strings in the pack so frequent terms can be added to the prompt before the main run",
+        "⏳ 扫描中..." => "⏳ Scanning...",
+        "开始分析" => "Analyze",
+        "加入提示词术语表 ({})" => "Add to prompt glossary ({})",
+        "共 {} 条原文，{} 条不重复" => "{} source strings, {} unique",
+        "高频原文 ({})" => "Most frequent ({})",
+        "最长原文 ({})" => "Longest ({})",
+        "次数" => "Count",
+        "译名" => "Translation",
+        "字数" => "Length",
+        "🔄 检查模组更新" => "🔄 Check mod updates",
+        "列出自上次翻译以来版本或 JAR 内容有变化的模组，可一键只更新这些模组的翻译" => "List mods whose version or JAR changed since the last run, and update the translation of just those mods in one click",
+        "与输出目录中 pack_manifest.json 记录的版本比较，上次未翻译过的模组不会列出。" => "Compared against the versions recorded in pack_manifest.json in the output folder; mods not translated last time are not listed.",
+        "更新这些模组的翻译 ({})" => "Update translations of these mods ({})",
+        "以更新模式只处理这些模组，补翻新增条目并重译原文有改动的条目" => "Process only these mods in update mode, translating new entries and re-translating entries whose source changed",
+        "所有模组均与上次翻译时一致" => "All mods match the last run",
+        "模组" => "Mod",
+        "上次版本" => "Last version",
+        "当前版本" => "Current version",
+        "版本相同，JAR 内容有变化" => "Same version, JAR contents changed",
+        "输出目录中没有 pack_manifest.json，请先完成一次翻译" => "No pack_manifest.json in the output folder; complete a translation run first",
+        "✅ 已将 {} 条术语加入提示词" => "✅ Added {} terms to the prompt",
+        "📖 从译文中发现 {} 条候选术语，请在弹出的窗口中确认" => "📖 Found {} candidate terms in the output, review them in the popup",
+        "📖 术语建议" => "📖 Suggested terms",
+        "术语建议" => "Suggest terms",
+        "任务完成后从译文中挖掘反复出现的术语，确认后加入提示词" => "After a run, mine repeated terms from the output and offer to add them to the prompt",
+        "模糊翻译记忆" => "Fuzzy translation memory",
+        "通过 /embeddings 接口为每批原文检索语义相近的历史译文，附在提示词中作为参考；批量模式下不生效" => "Use the /embeddings endpoint to find similar past translations for each batch and attach them to the prompt as references; not used in batch mode",
+        "向量模型:" => "Embedding model:",
+        "相似度阈值:" => "Similarity threshold:",
+        "以下术语在本次译文中反复出现且译法一致，加入提示词后可提高后续翻译的一致性。" => "These terms repeat with a consistent translation in this run. Adding them to the prompt keeps later runs consistent.",
+        "翻译描述数据" => "Translate description data",
+        "翻译附魔描述、物品描述等模组放在 data/ 下的描述文件 (含 JAR 内)，输出到 datapack/ 数据包" => "Translate description files kept under data/ by mods such as Enchantment Descriptions or Item Descriptions (including inside JARs) into a datapack under datapack/",
+        "将 config 目录下 .toml/.hjson/.json5 的注释翻译后输出到 config_docs/，用于整合包文档，不修改原配置" => "Translate comments in .toml/.hjson/.json5 files under config/ into config_docs/ for pack documentation; the original configs are untouched",
+        "不处理" => "Off",
+        "条目顺序:" => "Entry order:",
+        "与源文件一致" => "Source order",
+        "按字母排序" => "Alphabetical",
+        "按键前缀分组" => "Grouped by key prefix",
+        "按键前缀分组时，.lang 文件的各组之间以空行分隔" => "When grouped by key prefix, .lang files get a blank line between groups",
+        "翻译定义副本 (datapack/)" => "Translated copies (datapack/)",
+        "生成语言键覆盖" => "Lang key overrides",
+        "翻译 data/*/origins 与 data/*/powers 中的名称和描述" => "Translate names and descriptions in data/*/origins and data/*/powers",
+        "翻译结构文件 (.nbt) 中的书页与告示牌文字，输出到 datapack/ 数据包" => "Translate book pages and sign text inside structure files (.nbt) into a datapack under datapack/",
+        "⚠ 将译文直接写入模组 JAR 的副本，输出到 mods_patched/。签名会失效，更新模组后需重新生成，推荐优先使用资源包" => "⚠ Write translations into copies of the mod JARs under mods_patched/. Signatures are invalidated and patches must be regenerated after mod updates; a resource pack is preferred",
+        "清空" => "Clear",
+        "输入路径" => "Input path",
+        "输出目录" => "Output dir",
+        "状态" => "Status",
+        "🚀 运行队列" => "🚀 Run queue",
+        "🔄 更新队列翻译" => "🔄 Update queue",
+        "⏸ 等待" => "⏸ Pending",
+        "⏳ 运行中" => "⏳ Running",
+        "✅ 完成" => "✅ Done",
+        "⏹ 已取消" => "⏹ Cancelled",
+        "❌ 失败" => "❌ Failed",
+        "✔ 校验输出" => "✔ Validate output",
+        "检查输出目录中的语言文件能否被游戏正常加载，并自动修复尾逗号、控制字符等问题" => "Check that the language files in the output folder will load in game and auto-fix trailing commas, control characters and similar issues",
+        // 合并译文
+        "🔀 合并译文" => "🔀 Merge outputs",
+        "将多个译文目录或资源包合并为一个" => "Combine several output folders or resource packs into one",
+        "按添加顺序合并多个译文目录或资源包 zip，先添加的视为现有译文。" => "Sources are merged in the order they were added; earlier ones count as the existing translation.",
+        "📂 添加文件夹" => "📂 Add folder",
+        "📄 添加资源包" => "📄 Add resource pack",
+        "冲突处理:" => "On conflict:",
+        "保留现有" => "Keep existing",
+        "使用新的" => "Use new",
+        "逐条选择" => "Ask",
+        "⏳ 读取中..." => "⏳ Reading...",
+        "开始合并" => "Merge",
+        "共 {} 处冲突，勾选的条目使用新的译文" => "{} conflicts; checked entries use the new translation",
+        "写入" => "Write",
+        "全部使用新的" => "Use all new",
+        "全部保留现有" => "Keep all existing",
+        "现有译文" => "Existing",
+        "新的译文" => "New",
+        "❌ 读取合并来源失败: {}" => "❌ Failed to read merge sources: {}",
+        "✅ 合并完成，写入 {} 个文件，{} 处冲突，输出到 {}" => "✅ Merge finished: {} files, {} conflicts, written to {}",
+        "❌ 写入合并结果失败: {}" => "❌ Failed to write merge result: {}",
+        // 译文对比
+        "🆚 译文对比" => "🆚 Diff translations",
+        "对比两份译文，或译文与源模组，找出缺失和不一致的条目" => "Compare two translations, or a translation against the source mods, to find missing and differing entries",
+        "B 为源模组（按源语言读取，只对比键）" => "B is the source mods (read in the source language, keys only)",
+        "💾 导出报告" => "💾 Export report",
+        "✅ 对比报告已导出到 {}" => "✅ Report exported to {}",
+        "❌ 导出失败: {}" => "❌ Export failed: {}",
+        "❌ 译文对比失败: {}" => "❌ Diff failed: {}",
+        "仅在 A 中 ({})" => "Only in A ({})",
+        "仅在 B 中 ({})" => "Only in B ({})",
+        "译文不同 ({})" => "Different ({})",
+        "仅显示前 {} 条，完整内容请导出报告" => "Showing the first {} entries; export the report for the full list",
+        // 取消确认
+        "确定要取消任务吗？" => "Cancel the run?",
+        "任务已运行 {} 分 {} 秒，进行中的文件不会被保存。" => "The run has been going for {} min {} s. Files in progress will not be saved.",
+        "确定取消" => "Cancel run",
+        "❌ 输出目录不可写，任务未启动: {}" => "❌ The output folder is not writable; the run was not started: {}",
+        "{}: 剩余 {} MB，预计需要 {} MB" => "{}: {} MB free, about {} MB needed",
+        "输出目录空间可能不足" => "The output folder may run out of space",
+        "写出时空间不足会导致已翻译的内容丢失，建议先清理磁盘或更换输出目录。" => "Running out of space while writing loses translated content; free up disk space or choose another output folder first.",
+        "仍然开始" => "Start anyway",
+        "取消" => "Cancel",
+        "继续运行" => "Keep running",
+        // 提示词
+        "📝 自定义系统提示词 (System Prompt)" => "📝 Custom System Prompt",
+        "在此设置发送给 AI 的系统级指令，可用于控制翻译风格、保留特定术语等。" => "System instructions sent to the AI, e.g. translation style or terms to keep.",
+        "请输入 System Prompt..." => "Enter the system prompt...",
+        "保存并关闭" => "Save & close",
+        "恢复默认" => "Reset to default",
+        "约 {} tokens（每个批次都会发送）" => "About {} tokens (sent with every batch)",
+        "⚠ 缺少保持顺序的要求，译文可能与原文错位" => "⚠ No instruction to keep the order; translations may be misaligned",
+        "⚠ 缺少保持数组长度的要求，批次可能因数量不符而失败" => "⚠ No instruction to keep the array length; batches may fail on count mismatch",
+        "⚠ 缺少保留格式代码的要求，§a、%s 等可能被翻译或删除" => "⚠ No instruction to keep format codes; §a, %s etc. may be translated or dropped",
+        "⚠ 没有要求返回 JSON 数组，结果可能无法解析" => "⚠ No instruction to return a JSON array; responses may not parse",
+        "⚠ 未知占位符 {}，不会被替换" => "⚠ Unknown placeholder {} will not be replaced",
+        // 模型选择与对比
+        "模型名称" => "Model name",
+        "可直接输入列表中没有的模型" => "You can type a model that is not in the list",
+        "过滤模型..." => "Filter models...",
+        "从输入路径中抽取样本，分别用两个模型翻译，便于在全量翻译前挑选模型。" => "Translate a sample from the input path with two models to pick one before the full run.",
+        "模型 A:" => "Model A:",
+        "模型 B:" => "Model B:",
+        "样本数:" => "Samples:",
+        "开始对比" => "Compare",
+        "⏳ 对比中..." => "⏳ Comparing...",
+        "{} 用时 {}s | {} 用时 {}s" => "{} took {}s | {} took {}s",
+        "键名" => "Key",
+        "原文" => "Source",
+        // QA
+        "共 {} 条可疑译文，请人工复核" => "{} suspicious translations to review",
+        "位置" => "Location",
+        "原因" => "Reason",
+        "译文" => "Translation",
+        // 上下文窗口
+        "上下文 {}K" => "Context {}K",
+        "上下文未知" => "Context unknown",
+        "按模型上下文窗口自动缩小过长的批次并精简参考译文；窗口大小可在配置文件的 context_windows 中按模型名片段修改" => "Long batches are shrunk and reference translations trimmed to fit the model's context window; edit sizes per model-name fragment under context_windows in the config file",
+        // 请求模板
+        "🧩 请求模板" => "🧩 Request template",
+        "自定义接口路径与请求体，适配请求格式略有不同的兼容网关" => "Customize the endpoint path and request body for compatible gateways with slightly different formats",
+        "用于请求格式与 OpenAI 略有不同的网关，如需额外字段 \"enable_thinking\": false。响应仍需为 OpenAI 兼容的流式格式。" => "For gateways whose requests differ slightly from OpenAI, e.g. needing an extra \"enable_thinking\": false field. Responses must still use the OpenAI-compatible streaming format.",
+        "接口路径:" => "Endpoint path:",
+        "拼接在 BASE URL 之后" => "Appended to the BASE URL",
+        "留空使用内置请求体" => "Leave empty to use the built-in request body",
+        "占位符: {}。整个字符串为占位符时替换为对应类型，如 \"{STREAM}\" 替换为 true/false" => "Placeholders: {}. A string that is exactly a placeholder is replaced with a typed value, e.g. \"{STREAM}\" becomes true/false",
+        "⚠ 模板中没有 {}" => "⚠ The template has no {}",
+        "❌ 模板无效: {}" => "❌ Invalid template: {}",
+        "填入内置模板" => "Insert built-in template",
+        // 仅复用
+        "仅复用" => "Reuse only",
+        "不调用 API，只用内置汉化、社区汉化包和翻译记忆中原文相同的译文填充语言文件，并统计仍需翻译的条目" => "Make no API calls; fill lang files only from built-in translations, community packs and exact translation-memory matches, and count what still needs translating",
+        "社区汉化包:" => "Community packs:",
+        "目录或资源包 zip，逗号分隔，靠前的优先" => "Folders or resource pack zips, comma-separated; earlier ones take priority",
+        "仅复用：已填充 {} 条，仍需翻译 {} 条 (约 {} 字符)" => "Reuse only: {} entries filled, {} still need translating (~{} characters)",
+        // 文件列表
+        "文件 ({})：等待 {}，翻译中 {}，完成 {}，失败 {}，跳过 {}" => "Files ({}): {} queued, {} translating, {} done, {} failed, {} skipped",
+        "任务文件 ({})：完成 {}，失败 {}，剩余 {}" => "Quest files ({}): {} done, {} failed, {} remaining",
+        "⏳ 翻译中" => "⏳ Translating",
+        "⏭ 跳过" => "⏭ Skipped",
+        "🔁 重试" => "🔁 Retry",
+        "以更新模式重新处理该文件，语言文件只补翻缺失的条目" => "Process this file again in update mode; lang files only translate missing entries",
+        // 错误提示
+        "请检查 API Key 是否填写正确、是否已过期，以及是否与 API 地址对应的服务商匹配" => "Check that the API key is correct, not expired, and belongs to the provider of the API URL",
+        "请检查模型名称与 API 地址，可点击“检查 & 刷新”获取可用模型列表" => "Check the model name and API URL; click \"Check & Refresh\" to list available models",
+        "请检查 API 地址与对话接口路径 (chat_path) 是否与服务商文档一致" => "Check that the API URL and chat endpoint path (chat_path) match the provider's documentation",
+        "账户额度或余额已用尽，请充值或更换 API Key 后使用“更新翻译”补翻" => "The account quota or balance is used up; top up or switch API keys, then use \"Update translation\" to fill in the gaps",
+        "请求过于频繁，可降低网络并发数或增大重试间隔后使用“更新翻译”补翻" => "Too many requests; lower the network concurrency or raise the retry delay, then use \"Update translation\" to fill in the gaps",
+        "模型未按要求返回 JSON 数组，可减小批次大小、更换模型或检查自定义提示词" => "The model did not return a JSON array; try a smaller batch size, another model, or check the custom prompt",
+        "没有读写权限，请检查输出目录是否被占用或只读，或将输出目录改到有权限的位置" => "Permission denied; check whether the output folder is locked or read-only, or choose a writable output folder",
+        // 运行汇总
+        "文件: 成功 {}，跳过 {}，失败 {}；条目: 已翻译 {}，失败 {}；耗时 {} 分 {} 秒" => "Files: {} done, {} skipped, {} failed; entries: {} translated, {} failed; took {}m {}s",
+        "跳过的文件输出已存在或超出单文件上限，可关闭“跳过已翻译的文件”或调整上限后重跑" => "Skipped files already had output or exceeded the per-file limit; disable \"Skip translated files\" or raise the limit and run again",
+        "未翻译条目 ({})" => "Untranslated entries ({})",
+        "键" => "Key",
+        "失败的文件未写入译文，失败的条目暂以原文写入，请在日志中查看原因后使用“更新翻译”补翻" => "Failed files were not written and failed entries were written as source text; check the log for the cause, then use \"Update translation\" to fill them in",
+        "失败的文件与条目未写入译文，请在日志中查看原因后使用“更新翻译”补翻" => "Failed files and entries were not written; check the log for the cause, then use \"Update translation\" to fill them in",
+        // 通知
+        "翻译任务已完成" => "Translation finished",
+        "翻译任务已取消" => "Translation cancelled",
+        "翻译任务失败，请查看日志" => "Translation failed, see the log",
+        _ => return None,
+    };
+    Some(text)
+}
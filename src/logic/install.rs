@@ -0,0 +1,112 @@
+// 安装到游戏：把输出目录打包为资源包放入实例的 resourcepacks/，并可在 options.txt 中启用。
+// 支持直接选择 .minecraft、Prism/MultiMC 实例目录 (含 .minecraft 或 minecraft 子目录) 与 CurseForge 实例目录
+use crate::config::OutputLayout;
+use crate::logic::export;
+use crate::logic::mc_version;
+use crate::logic::error::{Result, TranslatorError};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 1.13 (数据版本 1519) 起 options.txt 中的资源包条目带 file/ 前缀
+const FLATTENING_DATA_VERSION: u64 = 1519;
+
+#[derive(Debug, Clone)]
+pub struct InstallReport {
+    pub pack_path: PathBuf,
+    pub files: usize,
+    pub enabled: bool, // 本次写入了 options.txt
+}
+
+fn is_game_dir(path: &Path) -> bool {
+    path.join("options.txt").is_file() || path.join("resourcepacks").is_dir() || path.join("mods").is_dir()
+}
+
+/// 由用户选择的目录定位游戏目录 (options.txt 与 resourcepacks/ 所在目录)
+pub fn resolve_game_dir(selected: &Path) -> PathBuf {
+    if selected.file_name().is_some_and(|n| n == "resourcepacks")
+        && let Some(parent) = selected.parent()
+    {
+        return parent.to_path_buf();
+    }
+    if is_game_dir(selected) {
+        return selected.to_path_buf();
+    }
+    [".minecraft", "minecraft"]
+        .iter()
+        .map(|sub| selected.join(sub))
+        .find(|dir| is_game_dir(dir))
+        .unwrap_or_else(|| selected.to_path_buf())
+}
+
+/// 把资源包加入 options.txt 的 resourcePacks 列表末尾 (优先级最高)，已启用时返回 false
+fn enable_in_options(game_dir: &Path, pack_name: &str) -> Result<bool> {
+    let options_path = game_dir.join("options.txt");
+    let content = fs::read_to_string(&options_path).unwrap_or_default();
+    let data_version = content
+        .lines()
+        .find_map(|l| l.strip_prefix("version:"))
+        .and_then(|v| v.trim().parse::<u64>().ok());
+
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    let index = lines.iter().position(|l| l.starts_with("resourcePacks:"));
+    let mut packs: Vec<Value> = index
+        .and_then(|i| serde_json::from_str::<Value>(&lines[i]["resourcePacks:".len()..]).ok())
+        .and_then(|v| v.as_array().cloned())
+        .unwrap_or_default();
+
+    // 旧版本直接使用文件名；没有版本信息时参考已有条目的写法
+    let new_style = match data_version {
+        Some(version) => version >= FLATTENING_DATA_VERSION,
+        None => packs.is_empty() || packs.iter().any(|p| p.as_str().is_some_and(|s| s == "vanilla" || s.starts_with("file/"))),
+    };
+    let entry = if new_style { format!("file/{}", pack_name) } else { pack_name.to_string() };
+    if packs.iter().any(|p| p.as_str() == Some(entry.as_str())) {
+        return Ok(false);
+    }
+    if new_style && packs.is_empty() {
+        packs.push(Value::String("vanilla".to_string()));
+    }
+    packs.push(Value::String(entry));
+
+    let line = format!("resourcePacks:{}", Value::Array(packs));
+    match index {
+        Some(i) => lines[i] = line,
+        None => lines.push(line),
+    }
+    fs::write(&options_path, lines.join("\n") + "\n")?;
+    Ok(true)
+}
+
+/// 打包输出目录并安装到游戏目录，`enable` 为 true 时同时在 options.txt 中启用
+pub fn install(
+    output_root: &str,
+    layout: OutputLayout,
+    target_lang: &str,
+    selected: &Path,
+    enable: bool,
+) -> Result<InstallReport> {
+    let game_dir = resolve_game_dir(selected);
+    if !game_dir.is_dir() {
+        return Err(TranslatorError::Other(format!("游戏目录不存在: {}", game_dir.display())));
+    }
+    // 按实例中的模组推断版本，写出匹配的 pack_format
+    let jars: Vec<PathBuf> = fs::read_dir(game_dir.join("mods"))
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| p.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("jar")))
+                .collect()
+        })
+        .unwrap_or_default();
+    let version = mc_version::detect(&jars).map(|(version, _)| version);
+
+    let pack_name = format!("mc_translator_{}.zip", target_lang.to_ascii_lowercase());
+    let pack_path = game_dir.join("resourcepacks").join(&pack_name);
+    let files = export::pack_zip(output_root, layout, &pack_path, version)?;
+    if files == 0 {
+        return Err(TranslatorError::Other("输出目录中没有可安装的资源文件".to_string()));
+    }
+    let enabled = enable && enable_in_options(&game_dir, &pack_name)?;
+    Ok(InstallReport { pack_path, files, enabled })
+}
@@ -0,0 +1,150 @@
+// 启动器实例发现：在官方启动器、Prism/MultiMC 与 CurseForge 的默认位置查找游戏实例，
+// 供界面作为输入路径的快捷方式
+use serde_json::Value;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Launcher {
+    Official,
+    Prism,
+    MultiMc,
+    CurseForge,
+}
+
+impl fmt::Display for Launcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Launcher::Official => "Minecraft",
+            Launcher::Prism => "Prism",
+            Launcher::MultiMc => "MultiMC",
+            Launcher::CurseForge => "CurseForge",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Instance {
+    pub launcher: Launcher,
+    pub name: String,
+    pub game_dir: PathBuf, // mods/、kubejs/、config/ 所在目录
+}
+
+impl Instance {
+    /// 实例中存在的可扫描目录
+    pub fn scan_dirs(&self) -> Vec<&'static str> {
+        ["mods", "kubejs", "config", "resourcepacks"]
+            .into_iter()
+            .filter(|d| self.game_dir.join(d).is_dir())
+            .collect()
+    }
+}
+
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| entries.filter_map(|e| e.ok().map(|e| e.path())).filter(|p| p.is_dir()).collect())
+        .unwrap_or_default();
+    dirs.sort();
+    dirs
+}
+
+fn dir_name(path: &Path) -> String {
+    path.file_name().unwrap_or_default().to_string_lossy().to_string()
+}
+
+/// 官方启动器：默认 .minecraft 以及 launcher_profiles.json 中自定义了 gameDir 的配置
+fn official(out: &mut Vec<Instance>) {
+    #[cfg(target_os = "linux")]
+    let root = dirs::home_dir().map(|h| h.join(".minecraft"));
+    #[cfg(target_os = "macos")]
+    let root = dirs::config_dir().map(|d| d.join("minecraft"));
+    #[cfg(windows)]
+    let root = dirs::config_dir().map(|d| d.join(".minecraft"));
+    #[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+    let root: Option<PathBuf> = None;
+
+    let Some(root) = root.filter(|r| r.is_dir()) else {
+        return;
+    };
+    out.push(Instance { launcher: Launcher::Official, name: dir_name(&root), game_dir: root.clone() });
+
+    let profiles = fs::read_to_string(root.join("launcher_profiles.json"))
+        .ok()
+        .and_then(|s| serde_json::from_str::<Value>(&s).ok());
+    let Some(Value::Object(profiles)) = profiles.as_ref().map(|v| &v["profiles"]) else {
+        return;
+    };
+    for profile in profiles.values() {
+        let Some(game_dir) = profile["gameDir"].as_str().map(PathBuf::from) else {
+            continue;
+        };
+        if !game_dir.is_dir() || out.iter().any(|i| i.game_dir == game_dir) {
+            continue;
+        }
+        let name = profile["name"].as_str().filter(|n| !n.is_empty()).map(String::from).unwrap_or_else(|| dir_name(&game_dir));
+        out.push(Instance { launcher: Launcher::Official, name, game_dir });
+    }
+}
+
+/// Prism/MultiMC：instances/<实例>/ 下的 .minecraft 或 minecraft 为游戏目录，名称取自 instance.cfg
+fn multimc_like(launcher: Launcher, instances_dir: &Path, out: &mut Vec<Instance>) {
+    for dir in subdirs(instances_dir) {
+        let Ok(cfg) = fs::read_to_string(dir.join("instance.cfg")) else {
+            continue;
+        };
+        let Some(game_dir) = [".minecraft", "minecraft"].iter().map(|d| dir.join(d)).find(|d| d.is_dir()) else {
+            continue;
+        };
+        let name = cfg
+            .lines()
+            .find_map(|l| l.strip_prefix("name="))
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| dir_name(&dir));
+        out.push(Instance { launcher, name, game_dir });
+    }
+}
+
+/// CurseForge：Instances/<实例>/ 本身就是游戏目录，名称取自 minecraftinstance.json
+fn curseforge(instances_dir: &Path, out: &mut Vec<Instance>) {
+    for dir in subdirs(instances_dir) {
+        let Ok(meta) = fs::read_to_string(dir.join("minecraftinstance.json")) else {
+            continue;
+        };
+        let name = serde_json::from_str::<Value>(&meta)
+            .ok()
+            .and_then(|v| v["name"].as_str().map(String::from))
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| dir_name(&dir));
+        out.push(Instance { launcher: Launcher::CurseForge, name, game_dir: dir });
+    }
+}
+
+/// 在各启动器的默认位置查找实例，找不到的启动器直接忽略
+pub fn discover() -> Vec<Instance> {
+    let mut out = Vec::new();
+    official(&mut out);
+
+    let data_dirs: Vec<PathBuf> = [dirs::data_dir(), dirs::config_dir()].into_iter().flatten().collect();
+    let mut prism_roots: Vec<PathBuf> = data_dirs.iter().map(|d| d.join("PrismLauncher")).collect();
+    if let Some(home) = dirs::home_dir() {
+        prism_roots.push(home.join(".var/app/org.prismlauncher.PrismLauncher/data/PrismLauncher")); // Flatpak
+    }
+    prism_roots.dedup();
+    for root in prism_roots {
+        multimc_like(Launcher::Prism, &root.join("instances"), &mut out);
+    }
+    let mut multimc_roots: Vec<PathBuf> = data_dirs.iter().map(|d| d.join("multimc")).collect();
+    multimc_roots.dedup();
+    for root in multimc_roots {
+        multimc_like(Launcher::MultiMc, &root.join("instances"), &mut out);
+    }
+
+    if let Some(home) = dirs::home_dir() {
+        for root in [home.join("curseforge"), home.join("Documents").join("curseforge")] {
+            curseforge(&root.join("minecraft").join("Instances"), &mut out);
+        }
+    }
+    out
+}
@@ -0,0 +1,190 @@
+// 整合包 Minecraft 版本：从模组元数据中声明的依赖版本推断，
+// 用于决定数据包 pack_format、语言文件名大小写以及 .lang / .json 格式
+use crate::logic::mod_metadata::read_entry;
+use crate::logic::tolerant_json;
+use regex::Regex;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io::{Read, Seek};
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use zip::ZipArchive;
+
+// 范围写法 ([1.20.1,1.21)、>=1.20.1、~1.20、1.20.x) 取第一个版本，即下限
+static VERSION_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b1\.(\d+)(?:\.(\d+))?").unwrap());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct McVersion {
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl fmt::Display for McVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.patch == 0 {
+            write!(f, "1.{}", self.minor)
+        } else {
+            write!(f, "1.{}.{}", self.minor, self.patch)
+        }
+    }
+}
+
+impl McVersion {
+    pub fn parse(text: &str) -> Option<Self> {
+        let caps = VERSION_RE.captures(text)?;
+        Some(Self {
+            minor: caps[1].parse().ok()?,
+            patch: caps.get(2).and_then(|m| m.as_str().parse().ok()).unwrap_or(0),
+        })
+    }
+
+    /// 1.13 起语言文件改为 JSON，文件名全小写；之前为 .lang，地区部分大写 (zh_CN.lang)
+    pub fn uses_json_lang(&self) -> bool {
+        self.minor >= 13
+    }
+
+    /// 该版本资源包的 pack_format，未收录的更新版本按已知最新值处理
+    pub fn resource_pack_format(&self) -> u32 {
+        match (self.minor, self.patch) {
+            (..=8, _) => 1,
+            (9..=10, _) => 2,
+            (11..=12, _) => 3,
+            (13..=14, _) => 4,
+            (15, _) | (16, 0..=1) => 5,
+            (16, _) => 6,
+            (17, _) => 7,
+            (18, _) => 8,
+            (19, 0..=2) => 9,
+            (19, 3) => 12,
+            (19, _) => 13,
+            (20, 0..=1) => 15,
+            (20, 2) => 18,
+            (20, 3..=4) => 22,
+            (20, _) => 32,
+            (21, 0..=1) => 34,
+            (21, 2..=3) => 42,
+            (21, 4) => 46,
+            (21, 5) => 55,
+            (21, 6) => 63,
+            _ => 64,
+        }
+    }
+
+    /// 该版本数据包的 pack_format，未收录的更新版本按已知最新值处理
+    pub fn datapack_format(&self) -> u32 {
+        match (self.minor, self.patch) {
+            (..=14, _) => 4,
+            (15, _) | (16, 0..=1) => 5,
+            (16, _) => 6,
+            (17, _) => 7,
+            (18, 0..=1) => 8,
+            (18, _) => 9,
+            (19, 0..=3) => 10,
+            (19, _) => 12,
+            (20, 0..=1) => 15,
+            (20, 2) => 18,
+            (20, 3..=4) => 26,
+            (20, _) => 41,
+            (21, 0..=1) => 48,
+            (21, 2..=3) => 57,
+            (21, 4) => 61,
+            (21, 5) => 71,
+            (21, 6) => 80,
+            _ => 81,
+        }
+    }
+}
+
+/// mods.toml 中 modId = "minecraft" 的依赖段里的 versionRange
+fn parse_mods_toml(content: &str) -> Option<McVersion> {
+    let mut is_minecraft = false;
+    let mut range = None;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            if is_minecraft && let Some(version) = range.as_deref().and_then(McVersion::parse) {
+                return Some(version);
+            }
+            (is_minecraft, range) = (false, None);
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.split('#').next().unwrap_or_default().trim().trim_matches(['"', '\'']);
+        match key.trim() {
+            "modId" => is_minecraft = value == "minecraft",
+            "versionRange" => range = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    if is_minecraft { range.as_deref().and_then(McVersion::parse) } else { None }
+}
+
+/// 字符串或字符串数组形式的版本声明
+fn parse_json_versions(value: &Value) -> Option<McVersion> {
+    match value {
+        Value::String(s) => McVersion::parse(s),
+        Value::Array(items) => items.iter().find_map(parse_json_versions),
+        _ => None,
+    }
+}
+
+fn read_json<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str) -> Option<Value> {
+    read_entry(archive, name).and_then(|c| tolerant_json::parse_object(&c).ok()).map(Value::Object)
+}
+
+/// 读取单个 JAR 声明的 Minecraft 版本
+pub fn read_from_jar<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Option<McVersion> {
+    for file in ["META-INF/mods.toml", "META-INF/neoforge.mods.toml"] {
+        if let Some(version) = read_entry(archive, file).as_deref().and_then(parse_mods_toml) {
+            return Some(version);
+        }
+    }
+    if let Some(json) = read_json(archive, "fabric.mod.json")
+        && let Some(version) = parse_json_versions(&json["depends"]["minecraft"])
+    {
+        return Some(version);
+    }
+    if let Some(json) = read_json(archive, "quilt.mod.json") {
+        let depends = json["quilt_loader"]["depends"].as_array().cloned().unwrap_or_default();
+        if let Some(version) = depends
+            .iter()
+            .filter(|d| d["id"] == "minecraft")
+            .find_map(|d| parse_json_versions(&d["versions"]))
+        {
+            return Some(version);
+        }
+    }
+    // mcmod.info：数组，或 { "modList": [...] }
+    let content = read_entry(archive, "mcmod.info")?;
+    let json: Value = serde_json::from_str(&tolerant_json::sanitize(&content)).ok()?;
+    let list = json.get("modList").unwrap_or(&json);
+    list.as_array()?.iter().find_map(|item| item["mcversion"].as_str().and_then(McVersion::parse))
+}
+
+/// 按多数模组声明的版本推断整合包版本，次版本号相同的合并计数，取其中最常见的完整版本。
+/// 没有任何 JAR 声明版本时返回 None
+pub fn detect(jars: &[PathBuf]) -> Option<(McVersion, usize)> {
+    let mut counts: HashMap<McVersion, usize> = HashMap::new();
+    for path in jars {
+        let Ok(file) = fs::File::open(path) else { continue };
+        let Ok(mut archive) = ZipArchive::new(file) else { continue };
+        if let Some(version) = read_from_jar(&mut archive) {
+            *counts.entry(version).or_default() += 1;
+        }
+    }
+    let mut by_minor: HashMap<u32, usize> = HashMap::new();
+    for (version, count) in &counts {
+        *by_minor.entry(version.minor).or_default() += count;
+    }
+    let (&minor, &total) = by_minor.iter().max_by_key(|&(minor, count)| (*count, *minor))?;
+    let version = counts
+        .iter()
+        .filter(|(v, _)| v.minor == minor)
+        .max_by_key(|&(v, count)| (*count, *v))
+        .map(|(v, _)| *v)?;
+    Some((version, total))
+}
@@ -0,0 +1,89 @@
+use crate::config::data_dir;
+use crate::logic::error::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs;
+use std::path::PathBuf;
+
+/// 最多保留的历史条目，超出后丢弃最早的
+const MAX_ENTRIES: usize = 5000;
+const MEMORY_FILE: &str = "translation_memory.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemoryEntry {
+    pub source: String,
+    pub target: String,
+    pub embedding: Vec<f32>,
+}
+
+/// 带向量的翻译记忆，用于为新条目检索语义最接近的历史译文作为参考
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TranslationMemory {
+    model: String, // 生成向量的模型，更换模型后旧向量不可比较
+    entries: VecDeque<MemoryEntry>,
+}
+
+fn memory_path() -> PathBuf {
+    data_dir().join(MEMORY_FILE)
+}
+
+impl TranslationMemory {
+    /// 读取磁盘上的记忆，模型不一致时丢弃旧条目
+    pub fn load(model: &str) -> Self {
+        let memory = fs::read_to_string(memory_path())
+            .ok()
+            .and_then(|s| serde_json::from_str::<Self>(&s).ok())
+            .filter(|m| m.model == model);
+        memory.unwrap_or_else(|| Self { model: model.to_string(), entries: VecDeque::new() })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = memory_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// 记录新的译文，同一原文只保留最新的一条
+    pub fn insert(&mut self, source: String, target: String, embedding: Vec<f32>) {
+        if embedding.is_empty() || source == target {
+            return;
+        }
+        self.entries.retain(|e| e.source != source);
+        self.entries.push_back(MemoryEntry { source, target, embedding });
+        while self.entries.len() > MAX_ENTRIES {
+            self.entries.pop_front();
+        }
+    }
+
+    /// 原文完全相同的历史译文，不需要向量
+    pub fn exact(&self, source: &str) -> Option<&str> {
+        self.entries.iter().rev().find(|e| e.source == source).map(|e| e.target.as_str())
+    }
+
+    /// 返回相似度不低于 `threshold` 的最接近条目
+    pub fn nearest(&self, embedding: &[f32], threshold: f32) -> Option<(&MemoryEntry, f32)> {
+        self.entries
+            .iter()
+            .filter(|e| e.embedding.len() == embedding.len())
+            .map(|e| (e, cosine_similarity(&e.embedding, embedding)))
+            .filter(|(_, score)| *score >= threshold)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
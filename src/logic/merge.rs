@@ -0,0 +1,179 @@
+// 合并多个译文输出（目录或资源包 zip），例如社区汉化包 + AI 输出
+use crate::config::OutputLayout;
+use crate::logic::common::{FileFormat, lang_output_path, parse_map_content, write_map_to_file};
+use crate::log_warn;
+use crate::logic::error::Result;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use walkdir::WalkDir;
+use zip::ZipArchive;
+
+/// 同一键在不同来源中译文不同时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MergePolicy {
+    PreferExisting, // 保留先添加的来源
+    PreferNew,      // 使用后添加的来源
+    Ask,            // 逐条人工选择
+}
+
+#[derive(Debug, Clone)]
+pub struct MergeConflict {
+    pub file: String, // "<modid>/<文件名>"
+    pub key: String,
+    pub existing: Value,
+    pub incoming: Value,
+    pub use_incoming: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MergePlan {
+    pub files: BTreeMap<String, (FileFormat, Map<String, Value>)>,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// 从 "xxx/assets/<modid>/lang/<lang>.json" 形式的路径中取出 "<modid>/<文件名>"
+fn lang_file_key(path: &str, target_lang: &str) -> Option<(String, FileFormat)> {
+    let parts: Vec<&str> = path.split(['/', '\\']).collect();
+    let i = parts.iter().rposition(|&p| p == "assets")?;
+    let [mod_id, "lang", file_name] = parts.get(i + 1..)? else {
+        return None;
+    };
+    let (stem, ext) = file_name.rsplit_once('.')?;
+    if !stem.eq_ignore_ascii_case(target_lang) {
+        return None;
+    }
+    let format = match ext {
+        "json" => FileFormat::Json,
+        "lang" => FileFormat::Lang,
+        _ => return None,
+    };
+    Some((format!("{}/{}", mod_id, file_name), format))
+}
+
+// (<modid>/<文件名>, 格式, 条目)
+pub type LangFile = (String, FileFormat, Map<String, Value>);
+
+fn read_archive(path: &Path, lang: &str, result: &mut Vec<LangFile>) -> Result<()> {
+    let mut archive = ZipArchive::new(fs::File::open(path)?)?;
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        if let Some((key, format)) = lang_file_key(file.name(), lang) {
+            let mut content = String::new();
+            file.read_to_string(&mut content)?;
+            result.push((key, format, parse_map_content(&content, format)));
+        }
+    }
+    Ok(())
+}
+
+/// 读取目录、资源包 zip 或 JAR 中指定语言的所有语言文件，目录中的 zip/JAR 也会被读取
+pub fn read_lang_files(source: &Path, lang: &str) -> Result<Vec<LangFile>> {
+    let mut result = Vec::new();
+    if !source.is_dir() {
+        read_archive(source, lang, &mut result)?;
+        return Ok(result);
+    }
+    for entry in WalkDir::new(source).into_iter().filter_map(|e| e.ok()) {
+        let path = entry.path();
+        let is_archive = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("jar") || ext.eq_ignore_ascii_case("zip"));
+        if is_archive {
+            if let Err(e) = read_archive(path, lang, &mut result) {
+                log_warn!("无法读取 {:?}: {}", path, e);
+            }
+            continue;
+        }
+        let rel = path.strip_prefix(source).unwrap_or(path);
+        if let Some((key, format)) = lang_file_key(&rel.to_string_lossy(), lang) {
+            let content = fs::read_to_string(path)?;
+            result.push((key, format, parse_map_content(&content, format)));
+        }
+    }
+    Ok(result)
+}
+
+/// 读取社区汉化包并按 ModID 汇总译文，同一键以靠前的汉化包为准
+pub fn read_community_packs(packs: &[String], target_lang: &str) -> HashMap<String, Map<String, Value>> {
+    let mut entries: HashMap<String, Map<String, Value>> = HashMap::new();
+    for pack in packs {
+        let files = match read_lang_files(Path::new(pack), target_lang) {
+            Ok(files) => files,
+            Err(e) => {
+                log_warn!("无法读取社区汉化包 {}: {}", pack, e);
+                continue;
+            }
+        };
+        for (file, _, map) in files {
+            let mod_id = file.split('/').next().unwrap_or_default().to_string();
+            let merged = entries.entry(mod_id).or_default();
+            for (key, value) in map {
+                merged.entry(key).or_insert(value);
+            }
+        }
+    }
+    entries
+}
+
+/// 按来源顺序合并，先出现的译文作为现有值，之后不同的译文记为冲突
+pub fn plan_merge(sources: &[String], target_lang: &str) -> Result<MergePlan> {
+    let mut plan = MergePlan::default();
+    let mut conflict_index: BTreeMap<(String, String), usize> = BTreeMap::new();
+    for source in sources {
+        for (file, format, map) in read_lang_files(Path::new(source), target_lang)? {
+            let (_, merged) = plan.files.entry(file.clone()).or_insert((format, Map::new()));
+            for (key, value) in map {
+                match merged.get(&key) {
+                    None => {
+                        merged.insert(key, value);
+                    }
+                    Some(existing) if *existing == value => {}
+                    Some(existing) => {
+                        // 多个后续来源冲突时以最后一个为准
+                        let id = (file.clone(), key.clone());
+                        if let Some(&idx) = conflict_index.get(&id) {
+                            plan.conflicts[idx].incoming = value;
+                        } else {
+                            conflict_index.insert(id, plan.conflicts.len());
+                            plan.conflicts.push(MergeConflict {
+                                file: file.clone(),
+                                key,
+                                existing: existing.clone(),
+                                incoming: value,
+                                use_incoming: false,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok(plan)
+}
+
+impl MergePlan {
+    pub fn apply_policy(&mut self, policy: MergePolicy) {
+        match policy {
+            MergePolicy::PreferExisting => self.conflicts.iter_mut().for_each(|c| c.use_incoming = false),
+            MergePolicy::PreferNew => self.conflicts.iter_mut().for_each(|c| c.use_incoming = true),
+            MergePolicy::Ask => {}
+        }
+    }
+
+    /// 按冲突选择写出合并结果，返回写入的文件数
+    pub fn write(&self, output_root: &str, layout: OutputLayout) -> Result<usize> {
+        for (file, (format, map)) in &self.files {
+            let mut map = map.clone();
+            for c in self.conflicts.iter().filter(|c| &c.file == file && c.use_incoming) {
+                map.insert(c.key.clone(), c.incoming.clone());
+            }
+            let (mod_id, file_name) = file.split_once('/').unwrap_or(("unknown", file));
+            let path = lang_output_path(Path::new(output_root), mod_id, file_name, layout);
+            write_map_to_file(&path, &map, *format)?;
+        }
+        Ok(self.files.len())
+    }
+}
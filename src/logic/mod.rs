@@ -1,4 +1,40 @@
 pub mod processor;
 pub mod openai;
 pub mod common;
-pub mod formats;
\ No newline at end of file
+pub mod formats;
+pub mod qa;
+pub mod benchmark;
+pub mod pseudo;
+pub mod batch_api;
+pub mod prompt_lint;
+pub mod update;
+pub mod output_lock;
+pub mod merge;
+pub mod diff;
+pub mod validate;
+pub mod nbt;
+pub mod frequency;
+pub mod glossary;
+pub mod memory;
+pub mod error;
+pub mod i18n;
+pub mod request_template;
+pub mod context_window;
+pub mod concurrency;
+pub mod placeholder;
+pub mod color_codes;
+pub mod punctuation;
+pub mod tolerant_json;
+pub mod mod_metadata;
+pub mod mc_version;
+pub mod export;
+pub mod run_history;
+pub mod hooks;
+pub mod post_run;
+pub mod install;
+pub mod instances;
+pub mod raw_content;
+pub mod checkpoint;
+pub mod pack_manifest;
+pub mod preflight;
+pub mod paths;
@@ -0,0 +1,124 @@
+// 模组元数据：从 JAR 中的 mods.toml / fabric.mod.json 等读取显示名称与版本，
+// 日志中 cfm、ae2 之类的 ID 难以对应到具体模组
+use crate::logic::tolerant_json;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{Read, Seek};
+use zip::ZipArchive;
+
+const TOML_FILES: [&str; 2] = ["META-INF/mods.toml", "META-INF/neoforge.mods.toml"];
+
+pub fn read_entry<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str) -> Option<String> {
+    let mut content = String::new();
+    archive.by_name(name).ok()?.read_to_string(&mut content).ok()?;
+    Some(content)
+}
+
+/// 未经构建替换的 ${mod_name} 之类的值没有意义
+fn usable(name: &str) -> Option<String> {
+    let name = name.trim();
+    (!name.is_empty() && !name.contains("${")).then(|| name.to_string())
+}
+
+/// 逐行读取 [[mods]] 段中的 modId 与指定字段，不引入完整的 TOML 解析；
+/// 字段值无法使用时 (如 ${file.jarVersion}) 取 fallback
+fn parse_mods_toml(content: &str, field: &str, fallback: Option<&str>, names: &mut HashMap<String, String>) {
+    let mut current: (Option<String>, Option<String>) = (None, None);
+    let mut flush = |current: &mut (Option<String>, Option<String>)| {
+        if let (Some(id), Some(name)) = current.clone() {
+            names.insert(id, name);
+        }
+        *current = (None, None);
+    };
+    let mut in_mods = false;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            flush(&mut current);
+            in_mods = line == "[[mods]]";
+            continue;
+        }
+        if !in_mods {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.split('#').next().unwrap_or_default().trim().trim_matches(['"', '\'']);
+        match key.trim() {
+            "modId" => current.0 = usable(value),
+            key if key == field => current.1 = usable(value).or_else(|| fallback.map(str::to_string)),
+            _ => {}
+        }
+    }
+    flush(&mut current);
+}
+
+fn insert_json(names: &mut HashMap<String, String>, id: &Value, name: &Value) {
+    if let (Some(id), Some(name)) = (id.as_str(), name.as_str().and_then(usable)) {
+        names.insert(id.to_string(), name);
+    }
+}
+
+/// 读取 JAR 中所有模组的 ModID -> 显示名称，没有元数据时返回空表
+pub fn read_display_names<R: Read + Seek>(archive: &mut ZipArchive<R>) -> HashMap<String, String> {
+    let mut names = HashMap::new();
+    for file in TOML_FILES {
+        if let Some(content) = read_entry(archive, file) {
+            parse_mods_toml(&content, "displayName", None, &mut names);
+        }
+    }
+    if let Some(json) = read_entry(archive, "fabric.mod.json").and_then(|c| tolerant_json::parse_object(&c).ok()) {
+        insert_json(&mut names, &json["id"], &json["name"]);
+    }
+    if let Some(json) = read_entry(archive, "quilt.mod.json").and_then(|c| tolerant_json::parse_object(&c).ok()) {
+        let loader = &json["quilt_loader"];
+        insert_json(&mut names, &loader["id"], &loader["metadata"]["name"]);
+    }
+    // 1.12 及更早版本的 mcmod.info：数组，或 { "modList": [...] }
+    if let Some(content) = read_entry(archive, "mcmod.info")
+        && let Ok(json) = serde_json::from_str::<Value>(&tolerant_json::sanitize(&content))
+    {
+        let list = json.get("modList").unwrap_or(&json);
+        for item in list.as_array().into_iter().flatten() {
+            insert_json(&mut names, &item["modid"], &item["name"]);
+        }
+    }
+    names
+}
+
+/// MANIFEST.MF 中的 Implementation-Version，Forge 模组的 mods.toml 通常以 ${file.jarVersion} 引用它
+fn manifest_version<R: Read + Seek>(archive: &mut ZipArchive<R>) -> Option<String> {
+    let content = read_entry(archive, "META-INF/MANIFEST.MF")?;
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("Implementation-Version:"))
+        .and_then(usable)
+}
+
+/// 读取 JAR 中所有模组的 ModID -> 版本号，没有元数据时返回空表
+pub fn read_versions<R: Read + Seek>(archive: &mut ZipArchive<R>) -> HashMap<String, String> {
+    let mut versions = HashMap::new();
+    let fallback = manifest_version(archive);
+    for file in TOML_FILES {
+        if let Some(content) = read_entry(archive, file) {
+            parse_mods_toml(&content, "version", fallback.as_deref(), &mut versions);
+        }
+    }
+    if let Some(json) = read_entry(archive, "fabric.mod.json").and_then(|c| tolerant_json::parse_object(&c).ok()) {
+        insert_json(&mut versions, &json["id"], &json["version"]);
+    }
+    if let Some(json) = read_entry(archive, "quilt.mod.json").and_then(|c| tolerant_json::parse_object(&c).ok()) {
+        let loader = &json["quilt_loader"];
+        insert_json(&mut versions, &loader["id"], &loader["version"]);
+    }
+    if let Some(content) = read_entry(archive, "mcmod.info")
+        && let Ok(json) = serde_json::from_str::<Value>(&tolerant_json::sanitize(&content))
+    {
+        let list = json.get("modList").unwrap_or(&json);
+        for item in list.as_array().into_iter().flatten() {
+            insert_json(&mut versions, &item["modid"], &item["version"]);
+        }
+    }
+    versions
+}
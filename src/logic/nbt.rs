@@ -0,0 +1,290 @@
+//! 最小化的 NBT 二进制读写，仅用于结构文件中的文本替换，读写时保持标签顺序不变
+
+use crate::logic::error::{Result, TranslatorError};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use std::io::{Read, Write};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tag {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<i8>),
+    String(String),
+    List(u8, Vec<Tag>), // (元素类型, 元素)
+    Compound(Vec<(String, Tag)>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+impl Tag {
+    fn id(&self) -> u8 {
+        match self {
+            Tag::Byte(_) => 1,
+            Tag::Short(_) => 2,
+            Tag::Int(_) => 3,
+            Tag::Long(_) => 4,
+            Tag::Float(_) => 5,
+            Tag::Double(_) => 6,
+            Tag::ByteArray(_) => 7,
+            Tag::String(_) => 8,
+            Tag::List(..) => 9,
+            Tag::Compound(_) => 10,
+            Tag::IntArray(_) => 11,
+            Tag::LongArray(_) => 12,
+        }
+    }
+}
+
+/// 根标签及其名称，以及源文件是否经过 gzip 压缩
+pub struct NbtFile {
+    pub name: String,
+    pub root: Tag,
+    pub gzipped: bool,
+}
+
+impl NbtFile {
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        let gzipped = data.starts_with(&[0x1f, 0x8b]);
+        let raw = if gzipped {
+            let mut buf = Vec::new();
+            GzDecoder::new(data).read_to_end(&mut buf)?;
+            buf
+        } else {
+            data.to_vec()
+        };
+
+        let mut reader = Reader { data: &raw, pos: 0 };
+        let id = reader.u8()?;
+        if id != 10 {
+            return Err(TranslatorError::Other(format!("根标签不是 Compound (类型 {})", id)));
+        }
+        let name = reader.string()?;
+        let root = reader.payload(id, 0)?;
+        Ok(Self { name, root, gzipped })
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut raw = Vec::new();
+        raw.push(self.root.id());
+        write_string(&mut raw, &self.name)?;
+        write_payload(&mut raw, &self.root)?;
+        if !self.gzipped {
+            return Ok(raw);
+        }
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&raw)?;
+        Ok(encoder.finish()?)
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8]> {
+        if self.pos + len > self.data.len() {
+            return Err(TranslatorError::Other("NBT 数据意外结束".to_string()));
+        }
+        let slice = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut buf = [0; N];
+        buf.copy_from_slice(self.take(N)?);
+        Ok(buf)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn i16(&mut self) -> Result<i16> {
+        Ok(i16::from_be_bytes(self.array()?))
+    }
+
+    fn i32(&mut self) -> Result<i32> {
+        Ok(i32::from_be_bytes(self.array()?))
+    }
+
+    fn i64(&mut self) -> Result<i64> {
+        Ok(i64::from_be_bytes(self.array()?))
+    }
+
+    fn len(&mut self) -> Result<usize> {
+        Ok(self.i32()?.max(0) as usize)
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let len = self.i16()? as u16 as usize;
+        Ok(decode_mutf8(self.take(len)?))
+    }
+
+    fn payload(&mut self, id: u8, depth: usize) -> Result<Tag> {
+        if depth > 512 {
+            return Err(TranslatorError::Other("NBT 嵌套层级过深".to_string()));
+        }
+        Ok(match id {
+            1 => Tag::Byte(self.u8()? as i8),
+            2 => Tag::Short(self.i16()?),
+            3 => Tag::Int(self.i32()?),
+            4 => Tag::Long(self.i64()?),
+            5 => Tag::Float(f32::from_bits(self.i32()? as u32)),
+            6 => Tag::Double(f64::from_bits(self.i64()? as u64)),
+            7 => {
+                let len = self.len()?;
+                Tag::ByteArray(self.take(len)?.iter().map(|&b| b as i8).collect())
+            }
+            8 => Tag::String(self.string()?),
+            9 => {
+                let elem = self.u8()?;
+                let len = self.len()?;
+                let mut items = Vec::with_capacity(len.min(4096));
+                for _ in 0..len {
+                    items.push(self.payload(elem, depth + 1)?);
+                }
+                Tag::List(elem, items)
+            }
+            10 => {
+                let mut entries = Vec::new();
+                loop {
+                    let child = self.u8()?;
+                    if child == 0 {
+                        break;
+                    }
+                    let name = self.string()?;
+                    entries.push((name, self.payload(child, depth + 1)?));
+                }
+                Tag::Compound(entries)
+            }
+            11 => {
+                let len = self.len()?;
+                let mut items = Vec::with_capacity(len.min(4096));
+                for _ in 0..len {
+                    items.push(self.i32()?);
+                }
+                Tag::IntArray(items)
+            }
+            12 => {
+                let len = self.len()?;
+                let mut items = Vec::with_capacity(len.min(4096));
+                for _ in 0..len {
+                    items.push(self.i64()?);
+                }
+                Tag::LongArray(items)
+            }
+            _ => return Err(TranslatorError::Other(format!("未知的 NBT 标签类型: {}", id))),
+        })
+    }
+}
+
+/// 字符串编码后是否在 NBT 的 u16 长度前缀以内
+pub fn fits_string(s: &str) -> bool {
+    encode_mutf8(s).len() <= u16::MAX as usize
+}
+
+fn write_string(out: &mut Vec<u8>, s: &str) -> Result<()> {
+    let bytes = encode_mutf8(s);
+    let Ok(len) = u16::try_from(bytes.len()) else {
+        // 截断可能拆开多字节序列，宁可放弃写入
+        return Err(TranslatorError::Other(format!("NBT 字符串过长 ({} 字节)", bytes.len())));
+    };
+    out.extend_from_slice(&len.to_be_bytes());
+    out.extend_from_slice(&bytes);
+    Ok(())
+}
+
+fn write_payload(out: &mut Vec<u8>, tag: &Tag) -> Result<()> {
+    match tag {
+        Tag::Byte(v) => out.push(*v as u8),
+        Tag::Short(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::Int(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::Long(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::Float(v) => out.extend_from_slice(&v.to_bits().to_be_bytes()),
+        Tag::Double(v) => out.extend_from_slice(&v.to_bits().to_be_bytes()),
+        Tag::ByteArray(items) => {
+            out.extend_from_slice(&(items.len() as i32).to_be_bytes());
+            out.extend(items.iter().map(|&b| b as u8));
+        }
+        Tag::String(s) => write_string(out, s)?,
+        Tag::List(elem, items) => {
+            out.push(*elem);
+            out.extend_from_slice(&(items.len() as i32).to_be_bytes());
+            for item in items {
+                write_payload(out, item)?;
+            }
+        }
+        Tag::Compound(entries) => {
+            for (name, value) in entries {
+                out.push(value.id());
+                write_string(out, name)?;
+                write_payload(out, value)?;
+            }
+            out.push(0);
+        }
+        Tag::IntArray(items) => {
+            out.extend_from_slice(&(items.len() as i32).to_be_bytes());
+            for v in items {
+                out.extend_from_slice(&v.to_be_bytes());
+            }
+        }
+        Tag::LongArray(items) => {
+            out.extend_from_slice(&(items.len() as i32).to_be_bytes());
+            for v in items {
+                out.extend_from_slice(&v.to_be_bytes());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Java 的 Modified UTF-8：U+0000 编码为两字节，增补字符拆为代理对
+fn decode_mutf8(bytes: &[u8]) -> String {
+    let mut units = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i] as u16;
+        if b < 0x80 {
+            units.push(b);
+            i += 1;
+        } else if b & 0xE0 == 0xC0 && i + 1 < bytes.len() {
+            units.push(((b & 0x1F) << 6) | (bytes[i + 1] as u16 & 0x3F));
+            i += 2;
+        } else if b & 0xF0 == 0xE0 && i + 2 < bytes.len() {
+            units.push(((b & 0x0F) << 12) | ((bytes[i + 1] as u16 & 0x3F) << 6) | (bytes[i + 2] as u16 & 0x3F));
+            i += 3;
+        } else {
+            units.push(0xFFFD);
+            i += 1;
+        }
+    }
+    String::from_utf16_lossy(&units)
+}
+
+fn encode_mutf8(s: &str) -> Vec<u8> {
+    let mut out = Vec::with_capacity(s.len());
+    for unit in s.encode_utf16() {
+        match unit {
+            0x0001..=0x007F => out.push(unit as u8),
+            0x0000 | 0x0080..=0x07FF => {
+                out.push(0xC0 | (unit >> 6) as u8);
+                out.push(0x80 | (unit & 0x3F) as u8);
+            }
+            _ => {
+                out.push(0xE0 | (unit >> 12) as u8);
+                out.push(0x80 | ((unit >> 6) & 0x3F) as u8);
+                out.push(0x80 | (unit & 0x3F) as u8);
+            }
+        }
+    }
+    out
+}
@@ -1,13 +1,144 @@
 use crate::config::AppConfig;
-use crate::log_warn;
-use anyhow::{Result, anyhow};
-use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use crate::{log_info, log_warn};
+use crate::message::send_batch_progress;
+use crate::logic::concurrency::Throttled;
+use crate::logic::error::{Result, TranslatorError};
+use crate::logic::memory::TranslationMemory;
+use crate::logic::placeholder::{self, Masked};
+use crate::logic::pseudo::pseudo_translate;
+use crate::logic::request_template::{parse_template, render};
+use crate::logic::context_window;
+use crate::logic::prompt_lint::estimate_tokens;
+use reqwest::{Client, RequestBuilder, Response, StatusCode, multipart};
+use serde::Serialize;
 use serde_json::{Value, json};
-use std::time::Duration;
+use chrono::Local;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::select;
-use tokio::time::sleep;
+use tokio::time::{sleep, timeout};
 use tokio_util::sync::CancellationToken;
 
+/// 服务商在响应头中返回的请求 ID，按常见程度依次尝试
+const REQUEST_ID_HEADERS: [&str; 4] = ["x-request-id", "request-id", "apim-request-id", "x-amzn-requestid"];
+
+fn provider_request_id(resp: &Response) -> Option<String> {
+    REQUEST_ID_HEADERS
+        .iter()
+        .find_map(|name| resp.headers().get(*name)?.to_str().ok())
+        .map(|id| id.to_string())
+}
+
+fn with_request_id(text: String, request_id: Option<&str>) -> String {
+    match request_id {
+        Some(id) => format!("{} (请求 ID: {})", text, id),
+        None => text,
+    }
+}
+
+/// 重试前的等待，取消时立即返回 Cancelled，而不是等完整个退避时间
+async fn sleep_or_cancel(wait: Duration, token: &CancellationToken) -> Result<()> {
+    select! {
+        _ = sleep(wait) => Ok(()),
+        _ = token.cancelled() => Err(TranslatorError::Cancelled),
+    }
+}
+
+/// 日志前缀，带上批次标签与请求 ID 便于与调试文件及服务商日志对应
+fn log_prefix(batch_label: &str, request_id: Option<&str>) -> String {
+    match (batch_label.is_empty(), request_id) {
+        (true, None) => String::new(),
+        (true, Some(id)) => format!("[请求 ID: {}] ", id),
+        (false, None) => format!("[{}] ", batch_label),
+        (false, Some(id)) => format!("[{} | 请求 ID: {}] ", batch_label, id),
+    }
+}
+
+/// 连接测试的提示词，只要求一个词的回复
+const HEALTH_CHECK_PROMPT: &str = "Reply with the single word OK.";
+
+/// 输出因长度上限停止，部分兼容网关使用 max_tokens
+pub fn is_truncated(finish_reason: &Value) -> bool {
+    matches!(finish_reason.as_str(), Some("length" | "max_tokens"))
+}
+
+/// 统计流式输出中已完整接收的数组元素数量
+#[derive(Default)]
+struct ItemCounter {
+    depth: usize,
+    in_string: bool,
+    escape: bool,
+    count: usize,
+}
+
+impl ItemCounter {
+    fn feed(&mut self, s: &str) {
+        for c in s.chars() {
+            if self.in_string {
+                if self.escape {
+                    self.escape = false;
+                } else if c == '\\' {
+                    self.escape = true;
+                } else if c == '"' {
+                    self.in_string = false;
+                    if self.depth == 1 {
+                        self.count += 1;
+                    }
+                }
+                continue;
+            }
+            match c {
+                '"' => self.in_string = true,
+                '[' => self.depth += 1,
+                ']' => self.depth = self.depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// 本次运行消耗的 token，服务商未返回 usage 时按字符数估算
+#[derive(Debug, Default)]
+pub struct TokenUsage {
+    prompt: AtomicUsize,
+    completion: AtomicUsize,
+    estimated: AtomicBool, // 至少有一次请求使用了估算值
+}
+
+impl TokenUsage {
+    /// 记录一次请求的用量，`usage` 中缺少计数时使用 `estimate` 返回的 (输入, 输出)
+    pub fn record(&self, usage: &Value, estimate: impl FnOnce() -> (usize, usize)) {
+        let (prompt, completion) = match (usage["prompt_tokens"].as_u64(), usage["completion_tokens"].as_u64()) {
+            (Some(prompt), Some(completion)) => (prompt as usize, completion as usize),
+            _ => {
+                self.estimated.store(true, Ordering::Relaxed);
+                estimate()
+            }
+        };
+        self.prompt.fetch_add(prompt, Ordering::Relaxed);
+        self.completion.fetch_add(completion, Ordering::Relaxed);
+    }
+
+    /// (输入 token, 输出 token, 是否含估算值)
+    pub fn totals(&self) -> (usize, usize, bool) {
+        (
+            self.prompt.load(Ordering::Relaxed),
+            self.completion.load(Ordering::Relaxed),
+            self.estimated.load(Ordering::Relaxed),
+        )
+    }
+}
+
+/// 测试请求的结果
+#[derive(Debug, Clone)]
+pub struct HealthReport {
+    pub latency: Duration,
+    pub rate_limits: Vec<(String, String)>,
+}
+
 #[derive(Clone)]
 pub struct OpenAIClient {
     client: Client,
@@ -17,47 +148,111 @@ pub struct OpenAIClient {
     prompt: String,
     max_retries: u32,
     retry_delay: u64,
+    max_retry_delay: u64,
     source_lang: String,
     target_lang: String,
+    mock: bool,
+    debug_dump: bool,
+    output_root: String,
+    batch_timeout: Option<Duration>,
+    memory: Option<Arc<Mutex<TranslationMemory>>>, // 开启模糊记忆或仅复用模式时为 Some
+    embedding_model: String,
+    memory_threshold: f32,
+    chat_url: String,
+    batch_endpoint: String, // Batch API 中的对话接口路径，如 /v1/chat/completions
+    request_template: Option<Value>, // 自定义请求体模板，None 时使用内置请求体
+    context_window: Option<usize>, // 当前模型的上下文窗口，未知时不限制批次长度
+    mask_placeholders: bool,
+    usage: Arc<TokenUsage>, // 克隆的客户端共享同一份统计
 }
 
 impl OpenAIClient {
     pub fn new(config: AppConfig) -> Self {
+        // 不设置整体超时，流式读取的大批次可能远超普通请求的耗时；批次超时单独控制
         let client = Client::builder()
-            // .timeout(Duration::from_secs(config.timeout)) // 取消超时限制，改用流式读取防止大包中断
+            .connect_timeout(Duration::from_secs(config.connect_timeout.max(1)))
             .build()
             .unwrap_or_default();
+        let base_url = config.base_url.trim_end_matches('/').to_string();
+        let chat_path = config.chat_path.trim().trim_start_matches('/');
+        let chat_path = if chat_path.is_empty() { "chat/completions" } else { chat_path };
+        // Batch API 要求相对于域名的路径，取 base_url 中的路径部分拼接 chat_path
+        let base_path = reqwest::Url::parse(&base_url).map(|u| u.path().trim_end_matches('/').to_string()).unwrap_or_default();
+        let batch_endpoint = format!("{}/{}", base_path, chat_path);
+        let request_template = match config.request_template.trim() {
+            "" => None,
+            text => match parse_template(text) {
+                Ok(template) => Some(template),
+                Err(e) => {
+                    log_warn!("请求模板无效，改用内置请求体: {}", e);
+                    None
+                }
+            },
+        };
 
         Self {
             client,
             api_key: config.api_key,
-            base_url: config.base_url.trim_end_matches('/').to_string(),
+            chat_url: format!("{}/{}", base_url, chat_path),
+            batch_endpoint,
+            context_window: context_window::lookup(&config.context_windows, &config.model),
+            base_url,
             model: config.model,
             prompt: config.prompt,
             max_retries: config.max_retries,
             retry_delay: config.retry_delay,
+            max_retry_delay: config.max_retry_delay,
             source_lang: config.source_lang,
             target_lang: config.target_lang,
+            mock: config.mock_provider,
+            debug_dump: config.debug_dump,
+            output_root: config.output_path,
+            batch_timeout: (config.batch_timeout > 0).then(|| Duration::from_secs(config.batch_timeout)),
+            memory: ((config.fuzzy_memory || config.reuse_only) && !config.mock_provider)
+                .then(|| Arc::new(Mutex::new(TranslationMemory::load(&config.embedding_model)))),
+            embedding_model: config.embedding_model,
+            memory_threshold: config.fuzzy_memory_threshold,
+            request_template,
+            mask_placeholders: config.mask_placeholders,
+            usage: Arc::new(TokenUsage::default()),
         }
     }
 
+    pub fn usage(&self) -> &TokenUsage {
+        &self.usage
+    }
+
+    /// 指数回退：base * 2^attempt，不超过上限，并加入 ±25% 抖动避免并发请求同时重试
+    fn backoff(&self, base_secs: u64, attempt: u32) -> Duration {
+        let secs = base_secs
+            .saturating_mul(2_u64.saturating_pow(attempt))
+            .min(self.max_retry_delay.max(1));
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.subsec_nanos())
+            .unwrap_or(0);
+        let jitter = 0.75 + (nanos % 1000) as f64 / 2000.0;
+        Duration::from_secs_f64(secs as f64 * jitter)
+    }
+
     async fn send_with_retry(
         &self,
         builder_fn: impl Fn() -> RequestBuilder,
+        batch_label: &str,
         token: &CancellationToken,
     ) -> Result<Response> {
         let mut attempt = 0;
 
         loop {
             if token.is_cancelled() {
-                return Err(anyhow!("任务已被用户取消"));
+                return Err(TranslatorError::Cancelled);
             }
             let request = builder_fn();
 
             let result = select! {
                 res = request.send() => res,
                 _ = token.cancelled() => {
-                    return Err(anyhow!("任务被用户取消"));
+                    return Err(TranslatorError::Cancelled);
                 }
             };
 
@@ -68,60 +263,75 @@ impl OpenAIClient {
                     if status.is_success() {
                         return Ok(resp);
                     }
+                    let request_id = provider_request_id(&resp);
+                    let prefix = log_prefix(batch_label, request_id.as_deref());
+                    let url = resp.url().to_string();
 
-                    if status == StatusCode::UNAUTHORIZED || status == StatusCode::BAD_REQUEST {
-                        let text = resp.text().await.unwrap_or_default();
-                        return Err(anyhow!("API 错误 (HTTP {}): {}", status, text));
+                    if matches!(
+                        status,
+                        StatusCode::UNAUTHORIZED | StatusCode::BAD_REQUEST | StatusCode::NOT_FOUND | StatusCode::PAYMENT_REQUIRED
+                    ) {
+                        let text = with_request_id(resp.text().await.unwrap_or_default(), request_id.as_deref());
+                        return Err(TranslatorError::from_response(status, &url, &text)
+                            .unwrap_or_else(|| TranslatorError::Other(format!("API 错误 (HTTP {}): {}", status, text))));
                     }
 
                     if attempt >= self.max_retries {
-                        let text = resp.text().await.unwrap_or_default();
-                        return Err(anyhow!("重试耗尽 (HTTP {}): {}", status, text));
+                        let text = with_request_id(resp.text().await.unwrap_or_default(), request_id.as_deref());
+                        return Err(TranslatorError::from_response(status, &url, &text)
+                            .unwrap_or_else(|| TranslatorError::Other(format!("重试耗尽 (HTTP {}): {}", status, text))));
                     }
 
                     let wait_time = if status == StatusCode::TOO_MANY_REQUESTS {
-                        if let Some(retry_after) = resp.headers().get("Retry-After") {
-                            retry_after
-                                .to_str()
-                                .ok()
-                                .and_then(|s| s.parse::<u64>().ok())
-                                .map(Duration::from_secs)
-                                .unwrap_or(Duration::from_secs(
-                                    self.retry_delay * 2_u64.pow(attempt),
-                                )) // 解析失败则回退
-                        } else {
-                            Duration::from_secs(self.retry_delay * 2_u64.pow(attempt)) // 指数回退
+                        // 服务端指定的 Retry-After 优先（不超过等待上限），解析失败则指数回退
+                        let retry_after = resp
+                            .headers()
+                            .get("Retry-After")
+                            .and_then(|v| v.to_str().ok())
+                            .and_then(|s| s.parse::<u64>().ok())
+                            .map(|secs| Duration::from_secs(secs.min(self.max_retry_delay.max(1))));
+                        // 额度用尽时重试没有意义，直接返回
+                        let text = with_request_id(resp.text().await.unwrap_or_default(), request_id.as_deref());
+                        if let Some(quota @ TranslatorError::QuotaExhausted(_)) = TranslatorError::from_response(status, &url, &text) {
+                            return Err(quota);
                         }
+                        retry_after.unwrap_or_else(|| self.backoff(self.retry_delay, attempt))
                     } else if status.is_server_error() {
-                        Duration::from_secs(self.retry_delay)
+                        self.backoff(self.retry_delay, 0)
                     } else {
-                        let text = resp.text().await.unwrap_or_default();
-                        return Err(anyhow!("请求失败 (HTTP {}): {}", status, text));
+                        let text = with_request_id(resp.text().await.unwrap_or_default(), request_id.as_deref());
+                        return Err(TranslatorError::from_response(status, &url, &text)
+                            .unwrap_or_else(|| TranslatorError::Other(format!("请求失败 (HTTP {}): {}", status, text))));
                     };
 
                     log_warn!(
-                        "请求遇到 {}, 等待 {:?} 后重试 (第 {}/{} 次)...",
+                        "{}请求遇到 {}, 等待 {:.1?} 后重试 (第 {}/{} 次，剩余 {} 次)...",
+                        prefix,
                         status,
                         wait_time,
                         attempt + 1,
-                        self.max_retries
+                        self.max_retries,
+                        self.max_retries - attempt - 1
                     );
-                    sleep(wait_time).await;
+                    let _throttled = (status == StatusCode::TOO_MANY_REQUESTS).then(Throttled::begin);
+                    sleep_or_cancel(wait_time, token).await?;
                 }
                 Err(e) => {
                     if attempt >= self.max_retries {
-                        return Err(anyhow!("网络重试耗尽: {}", e));
+                        return Err(TranslatorError::Network(format!("重试耗尽: {}", e)));
                     }
 
-                    let wait_time = Duration::from_secs(2_u64.pow(attempt));
+                    let wait_time = self.backoff(1, attempt);
                     log_warn!(
-                        "网络错误: {}, 等待 {:?} 后重试 (第 {}/{} 次)...",
+                        "{}网络错误: {}, 等待 {:.1?} 后重试 (第 {}/{} 次，剩余 {} 次)...",
+                        log_prefix(batch_label, None),
                         e,
                         wait_time,
                         attempt + 1,
-                        self.max_retries
+                        self.max_retries,
+                        self.max_retries - attempt - 1
                     );
-                    sleep(wait_time).await;
+                    sleep_or_cancel(wait_time, token).await?;
                 }
             }
 
@@ -130,6 +340,9 @@ impl OpenAIClient {
     }
 
     pub async fn fetch_models(&self, token: &CancellationToken) -> Result<Vec<String>> {
+        if self.mock {
+            return Ok(vec!["mock-pseudo".to_string()]);
+        }
         let url = format!("{}/models", self.base_url);
 
         let resp = self
@@ -139,6 +352,7 @@ impl OpenAIClient {
                         .get(&url)
                         .header("Authorization", format!("Bearer {}", self.api_key))
                 },
+                "",
                 token,
             )
             .await?;
@@ -156,77 +370,544 @@ impl OpenAIClient {
         Ok(models)
     }
 
+    /// 发送一个极小的补全请求，验证 Key 与模型可用，并记录延迟和限流响应头。
+    /// 不设置输出上限 (推理模型只接受 max_completion_tokens，且过小的上限会得到空回复)，
+    /// 由提示词约束回复长度；只要返回 2xx 即视为可用，不检查回复内容
+    pub async fn health_check(&self, token: &CancellationToken) -> Result<HealthReport> {
+        if self.mock {
+            return Ok(HealthReport { latency: Duration::ZERO, rate_limits: Vec::new() });
+        }
+
+        let request_body = match &self.request_template {
+            Some(template) => self.render_template(template, "", HEALTH_CHECK_PROMPT, false),
+            None => json!({
+                "model": self.model,
+                "messages": [{"role": "user", "content": HEALTH_CHECK_PROMPT}],
+                "stream": false
+            }),
+        };
+
+        let start = Instant::now();
+        let resp = self
+            .send_with_retry(
+                || {
+                    self.client
+                        .post(&self.chat_url)
+                        .header("Authorization", format!("Bearer {}", self.api_key))
+                        .header("Content-Type", "application/json")
+                        .json(&request_body)
+                },
+                "",
+                token,
+            )
+            .await?;
+        let latency = start.elapsed();
+
+        let rate_limits = resp
+            .headers()
+            .iter()
+            .filter(|(name, _)| {
+                let name = name.as_str();
+                name.contains("ratelimit") || name == "retry-after"
+            })
+            .map(|(name, value)| (name.to_string(), value.to_str().unwrap_or_default().to_string()))
+            .collect();
+
+        Ok(HealthReport { latency, rate_limits })
+    }
+
     pub async fn translate_text_list(
         &self,
-        texts: Vec<String>,
+        texts: &[String],
         mod_id: &str,
+        batch_label: &str,
         token: &CancellationToken,
     ) -> Result<Vec<String>> {
-        let system_prompt = self
+        if self.mock {
+            if token.is_cancelled() {
+                return Err(TranslatorError::Cancelled);
+            }
+            return Ok(texts.iter().map(|t| pseudo_translate(t)).collect());
+        }
+
+        // 模糊记忆：为本批原文计算向量，检索相近的历史译文作为参考
+        let embeddings = match &self.memory {
+            Some(_) => match self.embed(texts, batch_label, token).await {
+                Ok(embeddings) => Some(embeddings),
+                Err(e) if e.is_cancelled() => return Err(e),
+                Err(e) => {
+                    log_warn!("[{}] 获取向量失败，跳过模糊记忆: {}", batch_label, e);
+                    None
+                }
+            },
+            None => None,
+        };
+        let masked = self.mask_texts(texts);
+        let sources: Vec<&str> = masked.iter().map(|m| m.text.as_str()).collect();
+        let mut request_body = self.build_chat_body(&sources, mod_id, true)?;
+        if let Some(embeddings) = &embeddings {
+            let mut references = self.memory_references(texts, embeddings);
+            self.fit_references(&mut references, texts, mod_id, batch_label);
+            if !references.is_empty()
+                && let Some(system) = request_body["messages"][0]["content"].as_str()
+            {
+                let block = references
+                    .iter()
+                    .map(|(src, dst)| format!("{} => {}", json!(src), json!(dst)))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                request_body["messages"][0]["content"] = Value::String(format!(
+                    "{}\n\n以下是历史翻译中与本批原文相近的条目，仅用于保持译法一致，不要输出这些条目：\n{}",
+                    system, block
+                ));
+            }
+        }
+
+        let mut request_id = None;
+        let mut raw = String::new();
+        let stream = self.stream_completion(&request_body, batch_label, texts.len(), &mut request_id, &mut raw, token);
+        let result = match self.batch_timeout {
+            Some(limit) => timeout(limit, stream)
+                .await
+                .unwrap_or_else(|_| Err(TranslatorError::Network(format!("批次请求超时 ({:?})", limit)))),
+            None => stream.await,
+        };
+        if self.debug_dump {
+            self.dump_debug(batch_label, request_id.as_deref(), &request_body, &raw, &result);
+        }
+        let full_content = result.map_err(|e| e.with_request_id(request_id.as_deref()))?;
+
+        let translated = self
+            .parse_translation(&full_content)
+            .map_err(|e| e.with_request_id(request_id.as_deref()))?;
+        let translated = self.restore_texts(&masked, translated, batch_label);
+        if let (Some(memory), Some(embeddings)) = (&self.memory, embeddings)
+            && translated.len() == texts.len()
+            && let Ok(mut memory) = memory.lock()
+        {
+            for ((src, dst), embedding) in texts.iter().zip(translated.iter()).zip(embeddings) {
+                memory.insert(src.clone(), dst.clone(), embedding);
+            }
+        }
+        Ok(translated)
+    }
+
+    /// 调用 /embeddings 获取文本向量，顺序与输入一致
+    async fn embed(&self, texts: &[String], batch_label: &str, token: &CancellationToken) -> Result<Vec<Vec<f32>>> {
+        let mut request_body = json!({
+            "model": self.embedding_model,
+            "input": texts,
+        });
+        // text-embedding-3 系列支持降维，缩小记忆文件体积
+        if self.embedding_model.starts_with("text-embedding-3") {
+            request_body["dimensions"] = json!(256);
+        }
+
+        let resp = self
+            .send_with_retry(
+                || {
+                    self.client
+                        .post(format!("{}/embeddings", self.base_url))
+                        .header("Authorization", format!("Bearer {}", self.api_key))
+                        .header("Content-Type", "application/json")
+                        .json(&request_body)
+                },
+                batch_label,
+                token,
+            )
+            .await?;
+
+        let json: Value = resp.json().await?;
+        let data = json["data"].as_array().ok_or_else(|| TranslatorError::Other("向量响应格式错误".to_string()))?;
+        let mut embeddings = vec![Vec::new(); texts.len()];
+        for item in data {
+            let index = item["index"].as_u64().unwrap_or_default() as usize;
+            if let (Some(slot), Some(values)) = (embeddings.get_mut(index), item["embedding"].as_array()) {
+                *slot = values.iter().filter_map(|v| v.as_f64()).map(|v| v as f32).collect();
+            }
+        }
+        Ok(embeddings)
+    }
+
+    /// 参考译文会挤占上下文窗口，超出时从最后一条开始丢弃
+    fn fit_references(&self, references: &mut Vec<(String, String)>, texts: &[String], mod_id: &str, batch_label: &str) {
+        let Some(window) = self.context_window else {
+            return;
+        };
+        let fixed = estimate_tokens(&self.system_prompt(mod_id));
+        let input: usize = texts.iter().map(|t| estimate_tokens(t)).sum();
+        let mut remaining = context_window::remaining_tokens(window, fixed, input);
+        let total = references.len();
+        let mut kept = 0;
+        for (src, dst) in references.iter() {
+            let cost = estimate_tokens(src) + estimate_tokens(dst) + 4;
+            if cost > remaining {
+                break;
+            }
+            remaining -= cost;
+            kept += 1;
+        }
+        if kept < total {
+            references.truncate(kept);
+            log_warn!("[{}] 上下文窗口不足，参考译文从 {} 条减少到 {} 条", batch_label, total, kept);
+        }
+    }
+
+    /// 每条原文最接近的历史译文，去重后返回 (原文, 译文)
+    fn memory_references(&self, texts: &[String], embeddings: &[Vec<f32>]) -> Vec<(String, String)> {
+        let Some(Ok(memory)) = self.memory.as_ref().map(|m| m.lock()) else {
+            return Vec::new();
+        };
+        let mut references: Vec<(String, String)> = Vec::new();
+        for (text, embedding) in texts.iter().zip(embeddings) {
+            if let Some((entry, _)) = memory.nearest(embedding, self.memory_threshold)
+                && entry.source != *text
+                && !references.iter().any(|(src, _)| *src == entry.source)
+            {
+                references.push((entry.source.clone(), entry.target.clone()));
+            }
+        }
+        references
+    }
+
+    /// 翻译记忆中原文完全相同的译文，仅复用模式使用
+    pub fn recall(&self, source: &str) -> Option<String> {
+        let memory = self.memory.as_ref()?.lock().ok()?;
+        memory.exact(source).map(str::to_string)
+    }
+
+    /// 将模糊记忆写回磁盘
+    pub fn save_memory(&self) {
+        let Some(Ok(memory)) = self.memory.as_ref().map(|m| m.lock()) else {
+            return;
+        };
+        match memory.save() {
+            Ok(()) => log_info!("翻译记忆已保存 ({} 条)", memory.len()),
+            Err(e) => log_warn!("保存翻译记忆失败: {}", e),
+        }
+    }
+
+    fn system_prompt(&self, mod_id: &str) -> String {
+        let prompt = self
             .prompt
-            .replace("{MOD_ID}", &mod_id)
+            .replace("{MOD_ID}", mod_id)
             .replace("{SOURCE_LANG}", &self.source_lang)
             .replace("{TARGET_LANG}", &self.target_lang);
+        if self.mask_placeholders {
+            format!("{}\n{}", prompt, placeholder::PROMPT_NOTE)
+        } else {
+            prompt
+        }
+    }
+
+    /// 单个批次原文的 token 上限，按模型上下文窗口扣除系统提示词估算；模型未知时为 None
+    pub fn batch_token_budget(&self, mod_id: &str) -> Option<usize> {
+        let window = self.context_window?;
+        Some(context_window::input_budget(window, estimate_tokens(&self.system_prompt(mod_id))))
+    }
 
-        let request_body = json!({
+    /// 构造翻译请求体，批量模式下不使用流式输出；流式请求要求服务商在最后一个数据块中返回 usage
+    pub fn build_chat_body<S: Serialize>(&self, texts: &[S], mod_id: &str, stream: bool) -> Result<Value> {
+        let system_prompt = self.system_prompt(mod_id);
+
+        let user_content = serde_json::to_string(texts)?;
+        if let Some(template) = &self.request_template {
+            return Ok(self.render_template(template, &system_prompt, &user_content, stream));
+        }
+        let mut body = json!({
             "model": self.model,
             "messages": [
                 {"role": "system", "content": system_prompt},
-                {"role": "user", "content": serde_json::to_string(&texts)?}
+                {"role": "user", "content": user_content}
             ],
             "temperature": 0.1,
-            "stream": true
+            "stream": stream
+        });
+        if stream {
+            body["stream_options"] = json!({"include_usage": true});
+        }
+        Ok(body)
+    }
+
+    fn render_template(&self, template: &Value, system: &str, user: &str, stream: bool) -> Value {
+        render(
+            template,
+            &[
+                ("{MODEL}", json!(self.model)),
+                ("{SYSTEM}", json!(system)),
+                ("{USER}", json!(user)),
+                ("{STREAM}", json!(stream)),
+            ],
+        )
+    }
+
+    /// 按设置把原文中的格式代码替换为标记，返回值与输入一一对应
+    pub fn mask_texts<S: AsRef<str>>(&self, texts: &[S]) -> Vec<Masked> {
+        texts
+            .iter()
+            .map(|text| match self.mask_placeholders {
+                true => placeholder::mask(text.as_ref()),
+                false => Masked::plain(text.as_ref()),
+            })
+            .collect()
+    }
+
+    /// 还原译文中的标记，有标记丢失的条目记录警告
+    pub fn restore_texts(&self, masked: &[Masked], translated: Vec<String>, batch_label: &str) -> Vec<String> {
+        let mut lost = 0;
+        let restored = translated
+            .into_iter()
+            .enumerate()
+            .map(|(i, text)| match masked.get(i) {
+                Some(m) => {
+                    let (text, missing) = m.restore(&text);
+                    lost += missing as usize;
+                    text
+                }
+                None => text,
+            })
+            .collect();
+        if lost > 0 {
+            log_warn!("[{}] {} 条译文丢失了占位符标记，请检查对应条目的格式代码", batch_label, lost);
+        }
+        restored
+    }
+
+    /// 将模型返回的内容解析为译文数组
+    pub fn parse_translation(&self, content: &str) -> Result<Vec<String>> {
+        if content.is_empty() {
+            return Err(TranslatorError::ModelOutputInvalid("API 返回内容为空".to_string()));
+        }
+
+        let clean_content = self.clean_json_string(content);
+        serde_json::from_str(&clean_content).map_err(|e| TranslatorError::ModelOutputInvalid(e.to_string()))
+    }
+
+    pub fn is_mock(&self) -> bool {
+        self.mock
+    }
+
+    pub fn output_root(&self) -> &str {
+        &self.output_root
+    }
+
+    pub fn batch_endpoint(&self) -> &str {
+        &self.batch_endpoint
+    }
+
+    /// 上传 Batch API 输入文件 (JSONL)，返回文件 ID
+    pub async fn upload_batch_file(&self, jsonl: String, token: &CancellationToken) -> Result<String> {
+        let url = format!("{}/files", self.base_url);
+        let resp = self
+            .send_with_retry(
+                || {
+                    let part = multipart::Part::text(jsonl.clone()).file_name("batch_input.jsonl");
+                    let form = multipart::Form::new().text("purpose", "batch").part("file", part);
+                    self.client
+                        .post(&url)
+                        .header("Authorization", format!("Bearer {}", self.api_key))
+                        .multipart(form)
+                },
+                "",
+                token,
+            )
+            .await?;
+        let json: Value = resp.json().await?;
+        json["id"]
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| TranslatorError::Other(format!("上传批量文件失败: {}", json)))
+    }
+
+    /// 创建 Batch 任务，返回任务 ID
+    pub async fn create_batch(&self, input_file_id: &str, token: &CancellationToken) -> Result<String> {
+        let url = format!("{}/batches", self.base_url);
+        let body = json!({
+            "input_file_id": input_file_id,
+            "endpoint": self.batch_endpoint,
+            "completion_window": "24h"
         });
+        let resp = self
+            .send_with_retry(
+                || {
+                    self.client
+                        .post(&url)
+                        .header("Authorization", format!("Bearer {}", self.api_key))
+                        .json(&body)
+                },
+                "",
+                token,
+            )
+            .await?;
+        let json: Value = resp.json().await?;
+        json["id"]
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| TranslatorError::Other(format!("创建批量任务失败: {}", json)))
+    }
+
+    /// 查询 Batch 任务状态，返回原始 JSON
+    pub async fn get_batch(&self, batch_id: &str, token: &CancellationToken) -> Result<Value> {
+        let url = format!("{}/batches/{}", self.base_url, batch_id);
+        let resp = self
+            .send_with_retry(
+                || {
+                    self.client
+                        .get(&url)
+                        .header("Authorization", format!("Bearer {}", self.api_key))
+                },
+                "",
+                token,
+            )
+            .await?;
+        Ok(resp.json().await?)
+    }
+
+    /// 下载文件内容 (Batch 输出为 JSONL)
+    pub async fn download_file(&self, file_id: &str, token: &CancellationToken) -> Result<String> {
+        let url = format!("{}/files/{}/content", self.base_url, file_id);
+        let resp = self
+            .send_with_retry(
+                || {
+                    self.client
+                        .get(&url)
+                        .header("Authorization", format!("Bearer {}", self.api_key))
+                },
+                "",
+                token,
+            )
+            .await?;
+        Ok(resp.text().await?)
+    }
 
+    async fn stream_completion(
+        &self,
+        request_body: &Value,
+        batch_label: &str,
+        expected: usize,
+        request_id: &mut Option<String>,
+        raw: &mut String,
+        token: &CancellationToken,
+    ) -> Result<String> {
         let mut resp = self
             .send_with_retry(
                 || {
                     self.client
-                        .post(format!("{}/chat/completions", self.base_url))
+                        .post(&self.chat_url)
                         .header("Authorization", format!("Bearer {}", self.api_key))
                         .header("Content-Type", "application/json")
-                        .json(&request_body)
+                        .json(request_body)
                 },
+                batch_label,
                 token,
             )
             .await?;
+        *request_id = provider_request_id(&resp);
 
-        // 流式解析处理
+        // 流式解析处理，raw 保存原始响应供调试；由调用方持有，出错或超时时仍能写入已收到的部分
         let mut full_content = String::new();
         let mut buffer = String::new();
+        let mut counter = ItemCounter::default();
+        let mut reported = 0;
+        let mut usage = Value::Null;
+        let mut truncated = false;
 
-        while let Some(chunk) = resp.chunk().await? {
-            if token.is_cancelled() {
-                return Err(anyhow!("任务取消"));
-            }
+        loop {
+            let chunk = select! {
+                chunk = resp.chunk() => chunk?,
+                _ = token.cancelled() => return Err(TranslatorError::Cancelled),
+            };
+            let Some(chunk) = chunk else {
+                break;
+            };
             let s = String::from_utf8_lossy(&chunk);
+            if self.debug_dump {
+                raw.push_str(&s);
+            }
             buffer.push_str(&s);
 
             while let Some(idx) = buffer.find('\n') {
                 let line = buffer[..idx].trim().to_string();
                 buffer = buffer[idx + 1..].to_string();
 
-                if line.starts_with("data: ") {
-                    let data = line[6..].trim();
+                if let Some(data) = line.strip_prefix("data: ") {
+                    let data = data.trim();
                     if data == "[DONE]" {
                         break;
                     }
-                    if let Ok(v) = serde_json::from_str::<Value>(data) {
-                        if let Some(content) = v["choices"][0]["delta"]["content"].as_str() {
-                            full_content.push_str(content);
-                        }
+                    let Ok(v) = serde_json::from_str::<Value>(data) else {
+                        continue;
+                    };
+                    // 设置 include_usage 后，usage 在 finish_reason 之后单独的数据块 (choices 为空) 中返回，
+                    // 部分服务商则在每个数据块中附带累计值，均以最后一次为准
+                    if v["usage"].is_object() {
+                        usage = v["usage"].clone();
+                    }
+                    if let Some(content) = v["choices"][0]["delta"]["content"].as_str() {
+                        full_content.push_str(content);
+                        counter.feed(content);
+                    }
+                    // 被截断时继续读到结束，以便记录用量
+                    if is_truncated(&v["choices"][0]["finish_reason"]) {
+                        truncated = true;
                     }
                 }
             }
+
+            // 条目数已超出请求数量，继续等待没有意义
+            if counter.count > expected {
+                self.usage.record(&usage, || (estimate_tokens(&request_body.to_string()), estimate_tokens(&full_content)));
+                return Err(TranslatorError::ModelOutputInvalid(format!("返回条目多于请求 ({} > {})，提前终止", counter.count, expected)));
+            }
+            if counter.count != reported {
+                reported = counter.count;
+                send_batch_progress(batch_label, reported, expected);
+            }
         }
 
-        if full_content.is_empty() {
-            return Err(anyhow!("API 返回内容为空"));
+        self.usage.record(&usage, || (estimate_tokens(&request_body.to_string()), estimate_tokens(&full_content)));
+        if truncated {
+            return Err(TranslatorError::Truncated);
         }
+        Ok(full_content)
+    }
+
+    /// 将请求体与原始响应写入 output/debug，便于排查格式错误的批次
+    fn dump_debug(
+        &self,
+        batch_label: &str,
+        request_id: Option<&str>,
+        request_body: &Value,
+        raw: &str,
+        result: &Result<String>,
+    ) {
+        let dir = Path::new(&self.output_root).join("debug");
+        if let Err(e) = fs::create_dir_all(&dir) {
+            log_warn!("无法创建调试目录 {:?}: {}", dir, e);
+            return;
+        }
+
+        // 文件名包含批次标签与请求 ID，可与日志中的前缀对应
+        let label = match request_id {
+            Some(id) => format!("{}_{}", batch_label, id),
+            None => batch_label.to_string(),
+        };
+        let safe_label: String = label
+            .chars()
+            .map(|c| if c.is_alphanumeric() || c == '-' || c == '.' { c } else { '_' })
+            .collect();
+        let stem = format!("{}_{}", safe_label, Local::now().format("%Y%m%d_%H%M%S%3f"));
 
-        let clean_content = self.clean_json_string(&full_content);
-        let parsed: Vec<String> = serde_json::from_str(&clean_content)?;
-        Ok(parsed)
+        let request = serde_json::to_string_pretty(request_body).unwrap_or_default();
+        let response = match result {
+            Ok(_) => raw.to_string(),
+            Err(e) => format!("ERROR: {}\n\n{}", e, raw),
+        };
+        if let Err(e) = fs::write(dir.join(format!("{}.request.json", stem)), request)
+            .and_then(|_| fs::write(dir.join(format!("{}.response.txt", stem)), response))
+        {
+            log_warn!("写入调试文件失败: {}", e);
+        }
     }
 
     fn clean_json_string(&self, s: &str) -> String {
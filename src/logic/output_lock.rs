@@ -0,0 +1,54 @@
+// 输出目录锁：防止两个任务同时写同一个输出目录，破坏增量翻译的状态
+use crate::logic::error::{Result, TranslatorError};
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+const LOCK_FILE_NAME: &str = ".mc_translator.lock";
+
+/// 持有期间独占输出目录，析构时释放。使用系统文件锁，进程异常退出后锁会自动失效
+pub struct OutputLock {
+    file: File,
+    path: PathBuf,
+}
+
+impl OutputLock {
+    pub fn acquire(output_root: &str) -> Result<Self> {
+        fs::create_dir_all(output_root)?;
+        let path = Path::new(output_root).join(LOCK_FILE_NAME);
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)?;
+
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                let mut owner = String::new();
+                let _ = file.read_to_string(&mut owner);
+                let owner = owner.trim();
+                return Err(TranslatorError::Other(format!(
+                    "输出目录 {} 正在被另一个任务使用 (PID {})，请等待其结束或更换输出目录",
+                    output_root,
+                    if owner.is_empty() { "未知" } else { owner }
+                )));
+            }
+            Err(TryLockError::Error(e)) => return Err(e.into()),
+        }
+
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+        write!(file, "{}", std::process::id())?;
+        Ok(Self { file, path })
+    }
+}
+
+impl Drop for OutputLock {
+    fn drop(&mut self) {
+        // 先删除再解锁，避免删掉其他进程刚拿到的锁
+        let _ = fs::remove_file(&self.path);
+        let _ = self.file.unlock();
+    }
+}
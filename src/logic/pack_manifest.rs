@@ -0,0 +1,129 @@
+// 整合包清单：在输出目录记录生成译文时各模组的版本 (pack_manifest.json)，
+// 用户可据此核对汉化对应的模组版本，再次运行时也能发现哪些模组已经更新
+use crate::logic::mc_version::McVersion;
+use crate::logic::mod_metadata::read_versions;
+use crate::{log_info, log_warn};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use zip::ZipArchive;
+
+pub const FILE_NAME: &str = "pack_manifest.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModEntry {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub version: String,
+    pub jar: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha1: Option<String>, // JAR 内容哈希，版本号不变的重新构建也能发现
+}
+
+/// 与上次记录相比发生变化的模组，版本号相同时为 JAR 内容变化
+#[derive(Debug, Clone)]
+pub struct ModUpdate {
+    pub id: String,
+    pub name: Option<String>,
+    pub old_version: String,
+    pub new_version: String,
+    pub jar: PathBuf,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PackManifest {
+    pub generated_at: String,
+    pub target_lang: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub minecraft: Option<String>,
+    #[serde(default)]
+    pub mods: BTreeMap<String, ModEntry>, // ModID -> 版本信息
+}
+
+pub fn load(output_root: &Path) -> Option<PackManifest> {
+    let content = fs::read_to_string(output_root.join(FILE_NAME)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// JAR 文件的 SHA-1，读取失败时为 None
+pub fn jar_sha1(path: &Path) -> Option<String> {
+    let mut file = fs::File::open(path).ok()?;
+    let mut hasher = Sha1::new();
+    std::io::copy(&mut file, &mut hasher).ok()?;
+    Some(format!("{:x}", hasher.finalize()))
+}
+
+/// 对比输入中的 JAR 与上次记录，列出版本或内容变化的模组；输出目录中没有清单时返回 None。
+/// 上次没有翻译过的模组不在清单中，不会列出
+pub fn find_updates(output_root: &Path, jars: &[PathBuf]) -> Option<Vec<ModUpdate>> {
+    let previous = load(output_root)?;
+    let mut updates = Vec::new();
+    for jar in jars {
+        let Some(mut archive) = fs::File::open(jar).ok().and_then(|f| ZipArchive::new(f).ok()) else {
+            continue;
+        };
+        let mut versions: Vec<(String, String)> = read_versions(&mut archive).into_iter().collect();
+        versions.sort();
+        let mut sha1 = None;
+        for (id, version) in versions {
+            let Some(old) = previous.mods.get(&id) else {
+                continue;
+            };
+            let changed = old.version != version
+                || old.sha1.as_ref().is_some_and(|old_sha1| {
+                    sha1.get_or_insert_with(|| jar_sha1(jar)).as_ref().is_some_and(|sha1| sha1 != old_sha1)
+                });
+            if changed {
+                updates.push(ModUpdate {
+                    id,
+                    name: old.name.clone(),
+                    old_version: old.version.clone(),
+                    new_version: version,
+                    jar: jar.clone(),
+                });
+            }
+        }
+    }
+    Some(updates)
+}
+
+/// 与上次记录相比版本发生变化的模组：(ModID, 旧版本, 新版本)
+fn version_changes<'a>(previous: &'a PackManifest, mods: &'a BTreeMap<String, ModEntry>) -> Vec<(&'a str, &'a str, &'a str)> {
+    mods.iter()
+        .filter_map(|(id, entry)| {
+            let old = previous.mods.get(id)?;
+            (old.version != entry.version).then_some((id.as_str(), old.version.as_str(), entry.version.as_str()))
+        })
+        .collect()
+}
+
+/// 写入本次翻译的模组版本；只处理了部分模组时保留上次记录中的其余模组
+pub fn write(output_root: &Path, target_lang: &str, mc_version: Option<McVersion>, mods: BTreeMap<String, ModEntry>) {
+    if mods.is_empty() {
+        return;
+    }
+    let previous = load(output_root).unwrap_or_default();
+    let changes = version_changes(&previous, &mods);
+    if !changes.is_empty() {
+        let listed: Vec<String> = changes.iter().map(|(id, old, new)| format!("{} {} -> {}", id, old, new)).collect();
+        log_info!("与上次生成时相比，{} 个模组的版本有变化: {}", changes.len(), listed.join(", "));
+    }
+    let mut merged = previous.mods;
+    merged.extend(mods);
+    let manifest = PackManifest {
+        generated_at: Local::now().format("%Y-%m-%d %H:%M").to_string(),
+        target_lang: target_lang.to_string(),
+        // 只更新单个 JAR 时通常推断不出版本，沿用上次记录
+        minecraft: mc_version.map(|v| v.to_string()).or(previous.minecraft),
+        mods: merged,
+    };
+    let result = serde_json::to_string_pretty(&manifest)
+        .map_err(std::io::Error::other)
+        .and_then(|content| fs::write(output_root.join(FILE_NAME), content));
+    if let Err(e) = result {
+        log_warn!("无法写入 {}: {}", FILE_NAME, e);
+    }
+}
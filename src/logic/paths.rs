@@ -0,0 +1,68 @@
+// 输出路径的跨平台处理：JAR 条目与模组 ID 中可能含有 Windows 文件名不允许的字符 (如 :)，
+// 大型整合包的深层输出路径也可能超过 Windows 的 MAX_PATH 限制，两者都会导致创建文件失败
+use std::path::{Component, Path, PathBuf};
+
+const INVALID_CHARS: [char; 9] = ['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9", "LPT1", "LPT2",
+    "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// 单个路径段：替换 Windows 不允许的字符，去掉末尾的点与空格，设备名 (CON、NUL 等) 后加下划线。
+/// 各平台输出保持一致，生成的资源包在 Windows 上也能解压使用
+pub fn sanitize_component(name: &str) -> String {
+    let mut out: String = name
+        .chars()
+        .map(|c| if c.is_control() || INVALID_CHARS.contains(&c) { '_' } else { c })
+        .collect();
+    let trimmed = out.trim_end_matches(['.', ' ']).len();
+    if trimmed == 0 {
+        return "_".to_string();
+    }
+    out.truncate(trimmed);
+    let stem_len = out.find('.').unwrap_or(out.len());
+    if RESERVED_NAMES.iter().any(|r| out[..stem_len].eq_ignore_ascii_case(r)) {
+        out.insert(stem_len, '_');
+    }
+    out
+}
+
+/// 逐段清理输出目录之下的部分，`..` 等非普通路径段直接丢弃，不在输出目录内的路径原样返回
+pub fn sanitize_output_path(output_root: &Path, path: PathBuf) -> PathBuf {
+    let Ok(relative) = path.strip_prefix(output_root) else {
+        return path;
+    };
+    let mut out = output_root.to_path_buf();
+    for component in relative.components() {
+        if let Component::Normal(name) = component {
+            out.push(sanitize_component(&name.to_string_lossy()));
+        }
+    }
+    out
+}
+
+/// Windows 下接近 MAX_PATH (260) 的路径改为 \\?\ 形式，文件 API 不再受长度限制；其他平台原样返回
+#[cfg(windows)]
+pub fn long_path(path: PathBuf) -> PathBuf {
+    use std::os::windows::ffi::OsStrExt;
+
+    // 目录路径的上限为 248，留出余量
+    const LIMIT: usize = 240;
+    let Ok(absolute) = std::path::absolute(&path) else {
+        return path;
+    };
+    let text = absolute.to_string_lossy();
+    if absolute.as_os_str().encode_wide().count() < LIMIT || text.starts_with(r"\\?\") {
+        return path;
+    }
+    // \\?\ 形式不会再规范化分隔符，absolute 已统一为反斜杠
+    match text.strip_prefix(r"\\") {
+        Some(unc) => PathBuf::from(format!(r"\\?\UNC\{}", unc)),
+        None => PathBuf::from(format!(r"\\?\{}", text)),
+    }
+}
+
+#[cfg(not(windows))]
+pub fn long_path(path: PathBuf) -> PathBuf {
+    path
+}
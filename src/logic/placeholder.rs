@@ -0,0 +1,115 @@
+// 占位符保护：发送前把格式代码替换为不透明标记，收到译文后还原，不依赖模型遵守提示词
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// 颜色/样式代码与常见格式化占位符，%n 为旧版 .lang 的换行标记
+pub static PLACEHOLDER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"§[0-9a-fk-orA-FK-OR]|%(\d+\$)?[.\d]*[sdfx%]|%n|\{\d*\}").unwrap());
+// 模型偶尔在标记内加空格，还原时一并容忍
+static TOKEN_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"⟦\s*P(\d+)\s*⟧").unwrap());
+
+/// 追加到系统提示词的说明
+pub const PROMPT_NOTE: &str = "文本中形如 ⟦P1⟧ 的标记是占位符，必须原样保留在译文中的对应位置，不要翻译、删除或改写。";
+
+#[derive(Debug, Clone)]
+pub struct Masked {
+    pub text: String,
+    tokens: Vec<String>, // 第 N 个标记对应 tokens[N - 1]
+}
+
+impl Masked {
+    /// 不做替换，用于关闭占位符保护时
+    pub fn plain(text: &str) -> Self {
+        Self { text: text.to_string(), tokens: Vec::new() }
+    }
+
+    /// 还原译文中的标记；序号超出范围的标记原样保留。第二个返回值表示是否有标记丢失
+    pub fn restore(&self, translated: &str) -> (String, bool) {
+        if self.tokens.is_empty() {
+            return (translated.to_string(), false);
+        }
+        let mut seen = vec![false; self.tokens.len()];
+        let restored = TOKEN_RE.replace_all(translated, |caps: &regex::Captures| {
+            let index = caps[1].parse::<usize>().unwrap_or(0);
+            match index.checked_sub(1).and_then(|i| self.tokens.get(i).map(|token| (i, token))) {
+                Some((i, token)) => {
+                    seen[i] = true;
+                    token.clone()
+                }
+                None => caps[0].to_string(),
+            }
+        });
+        (restored.into_owned(), seen.contains(&false))
+    }
+}
+
+pub fn mask(text: &str) -> Masked {
+    let mut tokens = Vec::new();
+    let masked = PLACEHOLDER_RE.replace_all(text, |caps: &regex::Captures| {
+        tokens.push(caps[0].to_string());
+        format!("⟦P{}⟧", tokens.len())
+    });
+    Masked { text: masked.into_owned(), tokens }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks_format_codes() {
+        let masked = mask("§aHello %1$s, %d items {0} 100%% done");
+        assert_eq!(masked.text, "⟦P1⟧Hello ⟦P2⟧, ⟦P3⟧ items ⟦P4⟧ 100⟦P5⟧ done");
+        assert_eq!(masked.tokens, ["§a", "%1$s", "%d", "{0}", "%%"]);
+    }
+
+    #[test]
+    fn masks_legacy_newline_marker() {
+        let masked = mask("Line one%nLine two");
+        assert_eq!(masked.text, "Line one⟦P1⟧Line two");
+    }
+
+    #[test]
+    fn leaves_plain_text_alone() {
+        let masked = mask("No placeholders here");
+        assert_eq!(masked.text, "No placeholders here");
+        assert_eq!(masked.restore("没有占位符"), ("没有占位符".to_string(), false));
+    }
+
+    #[test]
+    fn restores_reordered_tokens() {
+        let masked = mask("%1$s gave %2$s to you");
+        let (restored, missing) = masked.restore("⟦P2⟧ 由 ⟦P1⟧ 给你");
+        assert_eq!(restored, "%2$s 由 %1$s 给你");
+        assert!(!missing);
+    }
+
+    #[test]
+    fn tolerates_whitespace_inside_tokens() {
+        let masked = mask("§cWarning");
+        assert_eq!(masked.restore("⟦ P1 ⟧警告"), ("§c警告".to_string(), false));
+    }
+
+    #[test]
+    fn keeps_out_of_range_tokens() {
+        let masked = mask("%s");
+        let (restored, missing) = masked.restore("⟦P1⟧ ⟦P9⟧ ⟦P0⟧");
+        assert_eq!(restored, "%s ⟦P9⟧ ⟦P0⟧");
+        assert!(!missing);
+    }
+
+    #[test]
+    fn detects_missing_tokens() {
+        let masked = mask("%s and %s");
+        let (restored, missing) = masked.restore("⟦P1⟧ 和");
+        assert_eq!(restored, "%s 和");
+        assert!(missing);
+    }
+
+    #[test]
+    fn plain_restore_passes_through() {
+        let masked = Masked::plain("§a%s");
+        assert_eq!(masked.text, "§a%s");
+        assert_eq!(masked.restore("⟦P1⟧"), ("⟦P1⟧".to_string(), false));
+    }
+}
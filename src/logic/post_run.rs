@@ -0,0 +1,107 @@
+// 任务结束后执行用户配置的命令，例如把资源包复制到游戏目录或提交到 git。
+// 运行结果通过环境变量传给命令
+use crate::logic::mc_version::McVersion;
+use crate::logic::processor::{RunStatus, RunSummary};
+use crate::{log_err, log_info, log_success, log_warn};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::select;
+use tokio_util::sync::CancellationToken;
+
+fn status_name(status: RunStatus) -> &'static str {
+    match status {
+        RunStatus::Completed => "completed",
+        RunStatus::Cancelled => "cancelled",
+        RunStatus::Failed => "failed",
+    }
+}
+
+fn shell(command: &str) -> Command {
+    #[cfg(windows)]
+    {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C").arg(command);
+        // 发布版没有控制台，避免弹出命令行窗口
+        cmd.creation_flags(0x0800_0000); // CREATE_NO_WINDOW
+        cmd
+    }
+    #[cfg(not(windows))]
+    {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(command);
+        cmd
+    }
+}
+
+/// 执行命令并把输出逐行写入日志，命令失败不影响任务状态。
+/// 命令超过 `timeout_secs` 秒 (0 为不限制) 或用户停止任务时终止；任务已取消时不再执行
+pub async fn run(
+    command: &str,
+    output: &str,
+    summary: &RunSummary,
+    mc_version: Option<McVersion>,
+    timeout_secs: u64,
+    token: &CancellationToken,
+) {
+    let command = command.trim();
+    if command.is_empty() {
+        return;
+    }
+    if token.is_cancelled() {
+        log_info!("任务已取消，跳过任务结束命令");
+        return;
+    }
+    log_info!("执行任务结束命令: {:?}", command);
+    let mut cmd = shell(command);
+    cmd.env("MCT_STATUS", status_name(summary.status))
+        .env("MCT_INPUT", &summary.input)
+        .env("MCT_OUTPUT", output)
+        .env("MCT_PROCESSED_FILES", summary.processed_files.to_string())
+        .env("MCT_SKIPPED_FILES", summary.skipped_files.to_string())
+        .env("MCT_FAILED_FILES", summary.failed_files.to_string())
+        .env("MCT_TRANSLATED_ENTRIES", summary.translated_entries.to_string())
+        .env("MCT_FAILED_ENTRIES", summary.failed_entries.to_string())
+        .env("MCT_ELAPSED_SECS", summary.elapsed.as_secs().to_string())
+        .env("MCT_MC_VERSION", mc_version.map(|v| v.to_string()).unwrap_or_default())
+        // 等待输入的命令读到 EOF 立即返回，不会卡住
+        .stdin(Stdio::null())
+        .kill_on_drop(true);
+
+    // 超时或停止时丢弃 output()，kill_on_drop 随之结束子进程
+    let wait = async {
+        match timeout_secs {
+            0 => Some(cmd.output().await),
+            secs => tokio::time::timeout(Duration::from_secs(secs), cmd.output()).await.ok(),
+        }
+    };
+    let result = select! {
+        result = wait => result,
+        _ = token.cancelled() => {
+            log_warn!("任务结束命令已被停止");
+            return;
+        }
+    };
+    let result = match result {
+        Some(Ok(result)) => result,
+        Some(Err(e)) => {
+            log_err!("无法执行任务结束命令: {}", e);
+            return;
+        }
+        None => {
+            log_err!("任务结束命令超过 {} 秒仍未结束，已终止", timeout_secs);
+            return;
+        }
+    };
+    for line in String::from_utf8_lossy(&result.stdout).lines().filter(|l| !l.trim().is_empty()) {
+        log_info!("[命令] {}", line);
+    }
+    for line in String::from_utf8_lossy(&result.stderr).lines().filter(|l| !l.trim().is_empty()) {
+        log_warn!("[命令] {}", line);
+    }
+    match result.status.code() {
+        Some(0) => log_success!("任务结束命令执行完成"),
+        Some(code) => log_err!("任务结束命令退出码为 {}", code),
+        None => log_err!("任务结束命令被终止"),
+    }
+}
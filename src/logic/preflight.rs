@@ -0,0 +1,101 @@
+// 运行前检查输出目录：不可写或剩余空间不足时在调用 API 之前提示，
+// 而不是等译文生成完、写出文件时才逐个失败，白白消耗 token
+use crate::logic::formats::has_extension;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+
+const PROBE_FILE_NAME: &str = ".mc_translator_write_test";
+/// 调试文件、原文快照等额外输出的余量
+const MIN_FREE_BYTES: u64 = 64 * 1024 * 1024;
+
+#[derive(Debug)]
+pub enum OutputIssue {
+    NotWritable(String),
+    LowSpace { available: u64, needed: u64 },
+}
+
+impl fmt::Display for OutputIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotWritable(detail) => write!(f, "输出目录不可写: {}", detail),
+            Self::LowSpace { available, needed } => write!(
+                f,
+                "输出目录剩余空间不足: 剩余 {} MB，预计需要 {} MB",
+                available / (1024 * 1024),
+                needed.div_ceil(1024 * 1024)
+            ),
+        }
+    }
+}
+
+/// 创建并删除一个探测文件，确认输出目录可以写入
+fn check_writable(output_root: &Path) -> io::Result<()> {
+    fs::create_dir_all(output_root)?;
+    let probe = output_root.join(PROBE_FILE_NAME);
+    OpenOptions::new().write(true).create(true).truncate(true).open(&probe)?;
+    fs::remove_file(probe)
+}
+
+#[cfg(unix)]
+fn available_space(path: &Path) -> io::Result<u64> {
+    let stat = rustix::fs::statvfs(path)?;
+    Ok(stat.f_bavail.saturating_mul(stat.f_frsize))
+}
+
+#[cfg(windows)]
+fn available_space(path: &Path) -> io::Result<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+    let mut available = 0u64;
+    // SAFETY: wide 以 0 结尾，其余输出参数允许为空
+    let ok = unsafe { GetDiskFreeSpaceExW(wide.as_ptr(), &mut available, std::ptr::null_mut(), std::ptr::null_mut()) };
+    if ok == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(available)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn available_space(_path: &Path) -> io::Result<u64> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "unsupported platform"))
+}
+
+/// 估算输出所需空间：译文可能比原文长，按原文两倍计；JAR 中的语言文件只占一小部分，
+/// 修补 JAR 时则需要完整复制一份
+pub fn estimate_needed(inputs: &[PathBuf], patch_jars: bool) -> u64 {
+    let input_bytes: u64 = inputs
+        .iter()
+        .map(|path| {
+            let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+            match has_extension(path, "jar") {
+                true if patch_jars => size,
+                true => size / 20,
+                false => size * 2,
+            }
+        })
+        .sum();
+    MIN_FREE_BYTES + input_bytes
+}
+
+/// 输出目录不可写时返回原因
+pub fn check_writable_dir(output_root: &str) -> Option<OutputIssue> {
+    check_writable(Path::new(output_root))
+        .err()
+        .map(|e| OutputIssue::NotWritable(format!("{} ({})", output_root, e)))
+}
+
+/// 剩余空间少于预计所需时返回，无法获取剩余空间时不做判断
+pub fn check_space(output_root: &str, inputs: &[PathBuf], patch_jars: bool) -> Option<OutputIssue> {
+    let available = available_space(Path::new(output_root)).ok()?;
+    let needed = estimate_needed(inputs, patch_jars);
+    (available < needed).then_some(OutputIssue::LowSpace { available, needed })
+}
+
+/// 检查输出目录是否可写、剩余空间是否足够
+pub fn check_output(output_root: &str, inputs: &[PathBuf], patch_jars: bool) -> Option<OutputIssue> {
+    check_writable_dir(output_root).or_else(|| check_space(output_root, inputs, patch_jars))
+}
@@ -1,14 +1,33 @@
-use crate::logic::common::TranslationContext;
+use crate::logic::common::{CircuitBreaker, FailedEntry, RunStats, SkipReason, TranslationContext, check_output_template};
+use crate::logic::concurrency;
+use crate::logic::export;
+use crate::logic::hooks::Hooks;
+use crate::logic::pack_manifest;
+use crate::logic::post_run;
+use crate::logic::preflight;
+use crate::logic::raw_content;
+use crate::logic::run_history::{self, RunRecord};
+use crate::logic::mc_version;
+use crate::logic::error::{Result, TranslatorError, describe};
+use crate::logic::merge::read_community_packs;
 use crate::logic::openai::OpenAIClient;
+use crate::logic::output_lock::OutputLock;
+use crate::logic::validate;
+use crate::message::{Logger, send_file_status, send_run_summary};
 use crate::{log_err, log_info, log_success, log_warn};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tokio_util::sync::CancellationToken;
 use walkdir::{DirEntry, WalkDir};
-use crate::logic::formats::{jar, lang, json, snbt};
+use crate::logic::formats::{
+    FormatHandler, config_comments, descriptions, has_extension, jar, json, lang, lines, origins, script, snbt, structure,
+};
+use std::collections::{BTreeMap, HashMap};
 use tokio::task::JoinSet;
 use tokio::sync::Semaphore;
-use std::sync::Arc;
-use crate::config::AppConfig;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use crate::config::{AppConfig, OriginsMode};
+use serde::{Deserialize, Serialize};
 
 // 1.21+: expect lang dir
 fn detect_ftb_version(root: &Path) -> bool {
@@ -29,6 +48,8 @@ fn detect_ftb_version(root: &Path) -> bool {
         root.join("ftbquests/quests/lang"),
         root.join("quests/lang"),
         root.join("lang"),
+        root.join(".minecraft/config/ftbquests/quests/lang"),
+        root.join("minecraft/config/ftbquests/quests/lang"),
     ];
 
     for path in candidates {
@@ -39,12 +60,26 @@ fn detect_ftb_version(root: &Path) -> bool {
     false
 }
 
-fn is_allowed_dir(entry: &DirEntry, root: &Path, is_ftb_1_21: bool, source_lang: &str) -> bool {
+/// 启动器实例中的游戏目录，位于实例根目录下，扫描时视为透明
+const GAME_DIRS: [&str; 2] = [".minecraft", "minecraft"];
+
+fn is_allowed_dir(
+    entry: &DirEntry,
+    root: &Path,
+    is_ftb_1_21: bool,
+    source_lang: &str,
+    include_config: bool,
+    skip_hidden: bool,
+) -> bool {
     if !entry.file_type().is_dir() { return true; }
     if entry.path() == root { return true; }
 
     let path = entry.path();
     let name = entry.file_name().to_string_lossy();
+    let is_game_dir = GAME_DIRS.iter().any(|d| name.eq_ignore_ascii_case(d));
+    if skip_hidden && name.starts_with('.') && !is_game_dir {
+        return false;
+    }
     let path_str = path.to_string_lossy();
 
     // FTB Quests logic for all version
@@ -66,78 +101,244 @@ fn is_allowed_dir(entry: &DirEntry, root: &Path, is_ftb_1_21: bool, source_lang:
     }
 
     // general logic
-    let allowed_roots = ["resources", "mods", "kubejs", "assets", "lang"];
+    let allowed_roots = ["resources", "mods", "kubejs", "assets", "lang", "datapacks"];
     if let Ok(rel) = path.strip_prefix(root) {
-        if let Some(first) = rel.components().next() {
-            let first_name = first.as_os_str().to_string_lossy();
+        // 指向启动器实例根目录时，从游戏目录开始匹配
+        let mut comps = rel.components().map(|c| c.as_os_str().to_string_lossy()).peekable();
+        if comps.next_if(|c| GAME_DIRS.iter().any(|d| c.eq_ignore_ascii_case(d))).is_some() && comps.peek().is_none() {
+            return true;
+        }
+        if let Some(first_name) = comps.next() {
             if first_name.eq_ignore_ascii_case("config") {
-                return rel.components().count() == 1; // 仅允许 config 根
+                return include_config || comps.next().is_none(); // 默认仅允许 config 根
             }
             if allowed_roots.iter().any(|r| first_name.eq_ignore_ascii_case(r)) {
                 return true;
             }
         }
     }
-    
+
     let root_name = root.file_name().unwrap_or_default().to_string_lossy();
     allowed_roots.iter().any(|r| root_name.eq_ignore_ascii_case(r))
 }
 
-fn should_process_file(path: &Path, config: &AppConfig, is_ftb_1_21: bool) -> bool {
-    let ext = path.extension().unwrap_or_default().to_string_lossy();
-    let source_lang = &config.source_lang;
-
-    match ext.as_ref() {
-        "jar" => true,
-        "lang" => path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .map(|n| n.to_lowercase().contains(source_lang))
-            .unwrap_or(false),
-        "json" => path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .map(|n| n.to_lowercase().contains(source_lang))
-            .unwrap_or(false),
-
-        "snbt" => {
-            // if config.skip_quest { return false; }
-            if !is_ftb_1_21 { return true; }
-
-            let components: Vec<_> = path.components()
-                .map(|c| c.as_os_str().to_string_lossy())
-                .collect();
-            if let Some(idx) = components.iter().rposition(|c| c.eq_ignore_ascii_case("lang")) {
-                if let Some(next_comp) = components.get(idx + 1) {
-                    if next_comp == path.file_name().unwrap().to_str().unwrap() {
-                        return path.file_stem().map_or(false, |s| s.eq_ignore_ascii_case(source_lang));
-                    }
-                    return next_comp.eq_ignore_ascii_case(source_lang);
-                }
-            }
-            false
-        },
+/// 已注册的格式，按顺序匹配，靠前的优先 (如描述数据先于普通 JSON)
+static HANDLERS: &[&dyn FormatHandler] = &[
+    &jar::JarFormat,
+    &descriptions::DescriptionFormat,
+    &origins::OriginsFormat,
+    &json::JsonFormat,
+    &lang::LangFormat,
+    &snbt::SnbtFormat,
+    &script::ScriptFormat,
+    &lines::LinesFormat,
+    &structure::StructureFormat,
+    &config_comments::ConfigCommentsFormat,
+];
 
-        _ => false,
-    }
+fn find_handler(path: &Path, config: &AppConfig, is_ftb_1_21: bool) -> Option<&'static dyn FormatHandler> {
+    matching_handler(path, config, is_ftb_1_21).filter(|h| !config.reuse_only || h.is_lang_format())
+}
+
+/// 能处理该文件的格式，不考虑仅复用模式的过滤
+fn matching_handler(path: &Path, config: &AppConfig, is_ftb_1_21: bool) -> Option<&'static dyn FormatHandler> {
+    HANDLERS.iter().copied().find(|h| h.matches(path, config, is_ftb_1_21))
 }
 
 async fn dispatch_file(
+    handler: &dyn FormatHandler,
     path: &Path,
     output: &str,
     client: &OpenAIClient,
     ctx: Arc<TranslationContext>,
     token: &CancellationToken,
-) -> anyhow::Result<()> {
-    let ext = path.extension().unwrap_or_default().to_string_lossy();
-    match ext.as_ref() {
-        "jar" => jar::process_jar(path, output, client, ctx, token).await,
-        "json" => json::process_json(path, output, client, ctx, token).await,
-        "lang" => lang::process_lang(path, output, client, ctx, token).await,
-        "snbt" => snbt::process_snbt(path, output, client, ctx, token).await, 
-        _ => {
-            log_warn!("跳过不支持的文件: {}", path.display());
-            Ok(())
+) -> Result<()> {
+    // JAR 本身体积不代表文本量，由其中的条目单独判断
+    if !has_extension(path, "jar")
+        && let Ok(meta) = std::fs::metadata(path)
+        && ctx.exceeds_size_limit(&path.to_string_lossy(), meta.len())
+    {
+        send_file_status(path, output, FileState::Skipped);
+        return Ok(());
+    }
+    send_file_status(path, output, FileState::Translating);
+
+    // 单个文件独立计数，用于判断该文件的最终状态，结束后并入整次运行的统计
+    let file_ctx = Arc::new(TranslationContext { stats: Arc::new(RunStats::default()), ..(*ctx).clone() });
+    let mut result = handler.process(path, output, client, file_ctx.clone(), token).await;
+    let file_stats = &file_ctx.stats;
+    // 语法错误的文件跳过并计入报告，不当作空文件继续处理
+    if let Err(e) = &result
+        && e.is_unparsable()
+    {
+        log_warn!("跳过无法解析的文件: {}", describe(e));
+        file_stats.record_unparsable(path.display().to_string());
+        result = Ok(());
+    }
+    ctx.stats.merge(file_stats);
+    let nothing_translated = RunStats::get(&file_stats.translated_entries) == 0;
+    let state = match &result {
+        Err(e) if e.is_cancelled() => FileState::Queued,
+        Err(_) => FileState::Failed,
+        // 中途取消时文件未完成，回到等待状态
+        Ok(_) if token.is_cancelled() => FileState::Queued,
+        Ok(_) if RunStats::get(&file_stats.failed_entries) > 0 => FileState::Failed,
+        Ok(_) if RunStats::get(&file_stats.skipped_files) > 0 && nothing_translated => FileState::Skipped,
+        Ok(_) if !file_stats.unparsable().is_empty() && nothing_translated => FileState::Skipped,
+        Ok(_) => FileState::Done,
+    };
+    match &result {
+        Ok(_) => RunStats::add(&ctx.stats.processed_files, 1),
+        Err(e) if e.is_cancelled() => {}
+        Err(_) => RunStats::add(&ctx.stats.failed_files, 1),
+    }
+    send_file_status(path, output, state);
+    result
+}
+
+/// 按扫描规则收集输入目录下所有待处理的文件
+pub fn scan_input_files(config: &AppConfig) -> Vec<PathBuf> {
+    let input_path = Path::new(&config.input_path);
+    if input_path.is_file() {
+        return vec![input_path.to_path_buf()];
+    }
+    scan_with_handlers(config).0.into_iter().map(|(path, _)| path).collect()
+}
+
+/// FTB Quests 的任务目录：config/ftbquests (输入可以是整合包、启动器实例、config 或任务目录本身)
+/// 以及 KubeJS 数据包中的 kubejs/data/<命名空间>/ftbquests 与 quests
+fn quest_roots(input_path: &Path) -> Vec<PathBuf> {
+    let name = input_path.file_name().unwrap_or_default().to_string_lossy();
+    let mut roots = Vec::new();
+    if name.eq_ignore_ascii_case("ftbquests") {
+        roots.push(input_path.to_path_buf());
+    }
+    let bases = std::iter::once(input_path.to_path_buf()).chain(GAME_DIRS.iter().map(|d| input_path.join(d)));
+    for base in bases {
+        roots.push(base.join("config/ftbquests"));
+        if name.eq_ignore_ascii_case("config") {
+            roots.push(base.join("ftbquests"));
+        }
+        if let Ok(namespaces) = std::fs::read_dir(base.join("kubejs/data")) {
+            for namespace in namespaces.flatten() {
+                roots.push(namespace.path().join("ftbquests"));
+                roots.push(namespace.path().join("quests"));
+            }
+        }
+    }
+    roots.retain(|root| root.is_dir());
+    roots.dedup();
+    roots
+}
+
+/// 扫描输入目录，返回每个待处理文件及负责它的格式，以及没有格式能处理的文件数。
+/// 任务目录单独完整遍历，不受通用扫描的目录规则与深度限制影响，任务文件排在最后
+fn scan_with_handlers(config: &AppConfig) -> (Vec<(PathBuf, &'static dyn FormatHandler)>, usize) {
+    let input_path = Path::new(&config.input_path);
+    if !input_path.is_dir() {
+        return (Vec::new(), 0);
+    }
+
+    let is_ftb_1_21 = detect_ftb_version(input_path);
+    let source_lang = config.source_lang.clone();
+    let include_config = config.translate_config_comments;
+    let skip_hidden = config.skip_hidden_dirs;
+    let quest_roots = quest_roots(input_path);
+    let mut walker = WalkDir::new(input_path).follow_links(config.follow_symlinks);
+    if config.walk_max_depth > 0 {
+        walker = walker.max_depth(config.walk_max_depth);
+    }
+    let general = walker
+        .into_iter()
+        .filter_entry(|e| {
+            !quest_roots.iter().any(|root| root == e.path())
+                && is_allowed_dir(e, input_path, is_ftb_1_21, &source_lang, include_config, skip_hidden)
+        })
+        .flatten();
+    let quests = quest_roots.iter().flat_map(|root| {
+        WalkDir::new(root)
+            .follow_links(config.follow_symlinks)
+            .into_iter()
+            .filter_entry(move |e| e.path() == root || !(skip_hidden && e.file_name().to_string_lossy().starts_with('.')))
+            .flatten()
+    });
+    // 仅复用模式有意排除的非语言文件不算作不支持
+    let mut unsupported = 0;
+    let files = general
+        .chain(quests)
+        .map(|entry| entry.into_path())
+        .filter(|path| path.is_file())
+        .filter_map(|path| match matching_handler(&path, config, is_ftb_1_21) {
+            Some(handler) if !config.reuse_only || handler.is_lang_format() => Some((path, handler)),
+            Some(_) => None,
+            None => {
+                unsupported += 1;
+                None
+            }
+        })
+        .collect();
+    (files, unsupported)
+}
+
+/// 一次运行的最终结果
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Completed,
+    Cancelled,
+    Failed,
+}
+
+/// 单个输入文件在界面文件列表中的状态
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FileState {
+    Queued,
+    Translating,
+    Done,
+    Failed, // 处理出错或有条目翻译失败
+    Skipped, // 输出已存在或超出上限
+}
+
+/// 一次运行的汇总，任务结束时发送给界面
+#[derive(Debug, Clone)]
+pub struct RunSummary {
+    pub input: String,
+    pub status: RunStatus,
+    pub processed_files: usize,
+    pub skipped_files: usize, // 输出已存在、无内容或超出上限等而跳过的文件
+    pub skip_reasons: Vec<(SkipReason, usize)>, // 按原因分别统计的跳过文件数，含语法错误
+    pub failed_files: usize,
+    pub translated_entries: usize,
+    pub failed_entries: usize,
+    pub reuse_only: bool,
+    pub reused_entries: usize,
+    pub untranslated_entries: usize, // 仅复用模式下仍需翻译的条目
+    pub untranslated_chars: usize,
+    pub empty_entries: usize, // 值为空或只含空白的条目
+    pub unparsable_files: Vec<String>,
+    pub failed_keys: Vec<FailedEntry>, // 批次翻译失败的条目
+    pub elapsed: Duration,
+}
+
+impl RunSummary {
+    fn new(input: &str, status: RunStatus, reuse_only: bool, stats: &RunStats, elapsed: Duration) -> Self {
+        Self {
+            input: input.to_string(),
+            status,
+            processed_files: RunStats::get(&stats.processed_files),
+            skipped_files: RunStats::get(&stats.skipped_files),
+            skip_reasons: stats.skip_counts(),
+            failed_files: RunStats::get(&stats.failed_files),
+            translated_entries: RunStats::get(&stats.translated_entries),
+            failed_entries: RunStats::get(&stats.failed_entries),
+            reuse_only,
+            reused_entries: RunStats::get(&stats.reused_entries),
+            untranslated_entries: RunStats::get(&stats.untranslated_entries),
+            untranslated_chars: RunStats::get(&stats.untranslated_chars),
+            empty_entries: RunStats::get(&stats.empty_entries),
+            unparsable_files: stats.unparsable(),
+            failed_keys: stats.failed(),
+            elapsed,
         }
     }
 }
@@ -146,82 +347,358 @@ pub async fn run_processing_task(
     config: AppConfig,
     update_existing: bool,
     token: CancellationToken,
-) {
+) -> RunStatus {
+    let started = Instant::now();
     let client = OpenAIClient::new(config.clone());
     let input = config.input_path.clone();
     let output = config.output_path.clone();
     let input_path = Path::new(&input);
+    // 在调用 API 之前拦下无法写入的输出目录
+    if let Some(issue) = preflight::check_writable_dir(&output) {
+        log_err!("{}", issue);
+        return RunStatus::Failed;
+    }
+    let _output_lock = match OutputLock::acquire(&output) {
+        Ok(lock) => lock,
+        Err(e) => {
+            log_err!("{}", describe(&e));
+            return RunStatus::Failed;
+        }
+    };
+    raw_content::prune(Path::new(&output), config.raw_content_retention_days);
+    // 脚本有误时不继续，避免按错误规则生成整批输出
+    let hooks = match config.script_hooks.trim() {
+        "" => None,
+        path => match Hooks::load(path) {
+            Ok(hooks) => Some(Arc::new(hooks)),
+            Err(e) => {
+                log_err!("{}", e);
+                return RunStatus::Failed;
+            }
+        },
+    };
+    if !config.output_path_template.trim().is_empty()
+        && let Err(e) = check_output_template(config.output_path_template.trim())
+    {
+        log_err!("输出路径模板无效: {}", e);
+        return RunStatus::Failed;
+    }
     let is_ftb_1_21 = detect_ftb_version(input_path);
     if is_ftb_1_21 {
         log_info!("检测到 FTB Quests (MC 1.21+ 结构)，将仅处理 lang 目录下的本地化文件。");
     } else {
         log_info!("未检测到 FTB Quests 新版结构，将按传统模式扫描 quests。");
     }
+    let (mut scanned, unsupported) = if input_path.is_dir() { scan_with_handlers(&config) } else { (Vec::new(), 0) };
+    let quest_files = scanned.iter().filter(|(path, _)| has_extension(path, "snbt")).count();
+    if quest_files > 0 {
+        log_info!("发现 {} 个任务文件，将与其他文件一起按文件并发数同时处理", quest_files);
+    }
+    // 同一模组的多个副本只翻译一次，避免重复计费与相互覆盖的输出
+    let scanned_jars: Vec<PathBuf> = scanned
+        .iter()
+        .map(|(path, _)| path)
+        .filter(|path| has_extension(path, "jar"))
+        .cloned()
+        .collect();
+    let duplicates = jar::find_duplicates(&scanned_jars);
+    for (duplicate, kept) in &duplicates {
+        log_warn!("跳过重复的模组: {} (与 {} 内容相同)", duplicate.display(), kept.display());
+        send_file_status(duplicate, &output, FileState::Skipped);
+    }
+    scanned.retain(|(path, _)| !duplicates.iter().any(|(duplicate, _)| duplicate == path));
+    let inputs: Vec<PathBuf> = scanned
+        .iter()
+        .map(|(path, _)| path.clone())
+        .chain(input_path.is_file().then(|| input_path.to_path_buf()))
+        .collect();
+    // 界面启动前已请用户确认过，这里空间不足仅提醒
+    if let Some(issue) = preflight::check_space(&output, &inputs, config.patch_jars) {
+        log_warn!("{}", issue);
+    }
+    let jars: Vec<PathBuf> = inputs
+        .iter()
+        .filter(|path| has_extension(path, "jar"))
+        .cloned()
+        .collect();
+    let mc_version = match mc_version::detect(&jars) {
+        Some((version, count)) => {
+            log_info!("根据 {} 个模组的元数据推断整合包版本为 Minecraft {}，将据此决定语言文件格式与文件名", count, version);
+            Some(version)
+        }
+        None => {
+            if !jars.is_empty() {
+                log_info!("未能从模组元数据推断 Minecraft 版本，将按源文件扩展名决定语言文件格式");
+            }
+            None
+        }
+    };
+    let shared = jar::find_shared_namespaces(&jars, &config.source_lang, &config.source_lang_fallbacks);
+    let version_merges = if config.merge_mod_versions {
+        jar::merge_versions(&shared, &config.source_lang, &config.source_lang_fallbacks)
+    } else {
+        for (namespace, owners) in &shared {
+            let names: Vec<String> = owners
+                .iter()
+                .map(|p| p.file_name().unwrap_or_default().to_string_lossy().to_string())
+                .collect();
+            log_warn!(
+                "模组 {} 出现在多个 JAR 中 ({})，输出以最后处理的版本为准；开启“合并模组多版本”可合并各版本的条目",
+                namespace,
+                names.join(", ")
+            );
+        }
+        HashMap::new()
+    };
     let ctx = Arc::new(TranslationContext{
         batch_size: config.batch_size,
         skip_existing: config.skip_existing,
-        update_existing: update_existing,
+        reuse_builtin: config.reuse_builtin,
+        retranslate_identical: config.retranslate_identical,
+        // 仅复用模式总是合并到已有输出，只填充缺失的条目
+        update_existing: update_existing || config.reuse_only,
         network_semaphore: Arc::new(Semaphore::new(config.max_network_concurrency)),
         source_lang: config.source_lang.clone(),
         target_lang: config.target_lang.clone(),
+        qa_min_ratio: config.qa_min_ratio,
+        qa_max_ratio: config.qa_max_ratio,
+        breaker: Arc::new(CircuitBreaker::new(config.max_consecutive_failures)),
+        batch_api: config.batch_api_mode,
+        batch_poll_interval: Duration::from_secs(config.batch_poll_interval.max(1)),
+        patch_jars: config.patch_jars,
+        output_layout: config.output_layout,
+        output_order: config.output_order,
+        color_codes: config.color_codes,
+        empty_values: config.empty_values,
+        failed_entries: config.failed_entries,
+        punctuation: config.punctuation_for(&config.target_lang),
+        filename_casing: config.filename_casing,
+        translate_structures: config.translate_structures,
+        origins_mode: config.origins_mode,
+        quest_mode: config.quest_mode,
+        translate_description_data: config.translate_description_data,
+        source_fallbacks: config.source_lang_fallbacks.clone(),
+        max_file_size: config.max_file_size_kb * 1024,
+        max_file_entries: config.max_file_entries,
+        limit_whitelist: config.limit_whitelist.clone(),
+        stats: Arc::new(RunStats::default()),
+        reuse_only: config.reuse_only,
+        community_entries: Arc::new(if config.reuse_only {
+            read_community_packs(&config.community_packs, &config.target_lang)
+        } else {
+            HashMap::new()
+        }),
+        mod_names: Arc::new(Mutex::new(HashMap::new())),
+        mod_versions: Arc::new(Mutex::new(BTreeMap::new())),
+        mc_version,
+        version_merges: Arc::new(version_merges),
+        hooks,
+        output_template: config.output_path_template.clone(),
+        input_root: match input_path.is_file() {
+            true => input_path.parent().map(Path::to_path_buf).unwrap_or_default(),
+            false => input_path.to_path_buf(),
+        },
+        output_root: PathBuf::from(&output),
+        logger: Logger::current(),
     });
+    for _ in &duplicates {
+        ctx.stats.record_skip(SkipReason::Duplicate);
+    }
+    for _ in 0..unsupported {
+        ctx.stats.record_skip(SkipReason::Unsupported);
+    }
+    let _capacity = concurrency::Capacity::register(config.max_network_concurrency);
+    if config.reuse_only {
+        log_info!("仅复用模式：不调用 API，只从内置汉化、社区汉化包和翻译记忆中填充语言文件");
+    }
+    if config.patch_jars {
+        log_warn!("已开启 JAR 修补模式：修补后的模组将输出到 {}/mods_patched，请勿覆盖原模组，且不要在服务器间分发修改过的 JAR", output);
+    }
 
-    let file_semaphore = Arc::new(Semaphore::new(config.file_semaphore));
+    // 批量模式下文件任务大部分时间在等待远端结果，不限制并发文件数
+    let file_limit = if config.batch_api_mode { Semaphore::MAX_PERMITS } else { config.file_semaphore };
+    let file_semaphore = Arc::new(Semaphore::new(file_limit));
+
+    // 整体运行时长上限，到时取消所有任务
+    let run_finished = CancellationToken::new();
+    if config.run_timeout > 0 {
+        let limit = Duration::from_secs(config.run_timeout * 60);
+        let token = token.clone();
+        let run_finished = run_finished.clone();
+        tokio::spawn(ctx.logger.clone().scope(async move {
+            tokio::select! {
+                _ = tokio::time::sleep(limit) => {
+                    log_err!("任务运行超过 {} 分钟上限，正在终止", limit.as_secs() / 60);
+                    token.cancel();
+                }
+                _ = run_finished.cancelled() => {}
+            }
+        }));
+    }
     let mut tasks = JoinSet::new();
 
     let result = if input_path.is_file() {
-        dispatch_file(
-            input_path,
-            &output,
-            &client,
-            ctx.clone(),
-            &token,
-        )
-        .await
+        match find_handler(input_path, &config, is_ftb_1_21) {
+            Some(handler) => dispatch_file(handler, input_path, &output, &client, ctx.clone(), &token).await,
+            None => {
+                log_warn!("跳过不支持的文件: {}", input_path.display());
+                ctx.stats.record_skip(SkipReason::Unsupported);
+                Ok(())
+            }
+        }
     } else if input_path.is_dir() {
-        let source_lang = config.source_lang.clone();
-        let walker = WalkDir::new(input_path)
+        // Origins 定义按命名空间汇总处理，不参与逐文件分发
+        let (origins_files, files): (Vec<_>, Vec<_>) = scanned
             .into_iter()
-            .filter_entry(move |e| is_allowed_dir(e, input_path, is_ftb_1_21, &source_lang));
-
-        for entry in walker.flatten() {
+            .partition(|(p, _)| config.origins_mode != OriginsMode::Off && origins::is_origins_file(&p.to_string_lossy()));
+        let origins_files: Vec<PathBuf> = origins_files.into_iter().map(|(path, _)| path).collect();
+        for path in origins_files.iter().chain(files.iter().map(|(path, _)| path)) {
+            send_file_status(path, &output, FileState::Queued);
+        }
+        for (path, handler) in files {
             if token.is_cancelled() {
                 break;
             }
-            
-            let path = entry.path().to_path_buf(); // 获取路径的所有权
-            
-            if path.is_file() {
-                if should_process_file(&path, &config, is_ftb_1_21) {
-                    let client = client.clone();
-                    let output = output.clone();
-                    let token = token.clone();
-                    let permit = file_semaphore.clone().acquire_owned().await.unwrap();
-                    let ctx = ctx.clone();
-
-                    tasks.spawn(async move {
-                        let _permit = permit; 
-                        if let Err(e) = dispatch_file(
-                            &path, 
-                            &output, 
-                            &client, 
-                            ctx,
-                            &token
-                        ).await {
-                            log_err!("处理失败 [{}]: {}", path.display(), e);
-                        }
-                    });
+
+            let client = client.clone();
+            let output = output.clone();
+            let token = token.clone();
+            // 等待许可时也响应取消，避免停止时仍排队启动新文件
+            let permit = tokio::select! {
+                permit = file_semaphore.clone().acquire_owned() => permit.unwrap(),
+                _ = token.cancelled() => break,
+            };
+            let ctx = ctx.clone();
+
+            let logger = ctx.logger.clone();
+            tasks.spawn(logger.scope(async move {
+                let _permit = permit; 
+                if let Err(e) = dispatch_file(
+                    handler,
+                    &path, 
+                    &output, 
+                    &client, 
+                    ctx,
+                    &token
+                ).await
+                    && !e.is_cancelled()
+                {
+                    log_err!("处理失败 [{}]: {}", path.display(), describe(&e));
                 }
+            }));
+        }
+        while tasks.join_next().await.is_some() {}
+        if origins_files.is_empty() || token.is_cancelled() {
+            Ok(())
+        } else {
+            for path in &origins_files {
+                send_file_status(path, &output, FileState::Translating);
+            }
+            let result = origins::process_origins(&origins_files, &output, &client, &ctx, &token).await;
+            let (counter, state) = if result.is_ok() {
+                (&ctx.stats.processed_files, FileState::Done)
+            } else {
+                (&ctx.stats.failed_files, FileState::Failed)
+            };
+            RunStats::add(counter, origins_files.len());
+            for path in &origins_files {
+                send_file_status(path, &output, state);
             }
+            result
         }
-        while let Some(_) = tasks.join_next().await {}
-        Ok(())
     } else {
-        Err(anyhow::anyhow!("无效的输入路径"))
+        Err(TranslatorError::Other("无效的输入路径".to_string()))
+    };
+
+    run_finished.cancel();
+    client.save_memory();
+
+    let status = if ctx.breaker.is_tripped() {
+        log_err!("任务已被熔断终止，已生成的文件不受影响");
+        RunStatus::Failed
+    } else if token.is_cancelled() {
+        log_warn!("任务已停止，已完成的文件均已保存，未完成的文件未写入");
+        RunStatus::Cancelled
+    } else {
+        match result {
+            Ok(_) => {
+                log_success!("任务已完成！");
+                if let Ok(mods) = ctx.mod_versions.lock() {
+                    pack_manifest::write(Path::new(&output), &config.target_lang, ctx.mc_version, mods.clone());
+                }
+                validate::validate_output(&output);
+                export::export_targets(&output, config.output_layout, &config.output_targets, ctx.mc_version);
+                export::export_server_pack(
+                    &output,
+                    config.output_layout,
+                    &config.server_pack_dir,
+                    &config.server_pack_url,
+                    &config.target_lang,
+                    ctx.mc_version,
+                );
+                RunStatus::Completed
+            }
+            Err(e) => {
+                log_err!("发生严重错误: {}", describe(&e));
+                RunStatus::Failed
+            }
+        }
     };
 
-    match result {
-        Ok(_) => log_success!("任务已完成！"),
-        Err(e) => log_err!("发生严重错误: {}", e),
+    let summary = RunSummary::new(&input, status, config.reuse_only, &ctx.stats, started.elapsed());
+    if !summary.skip_reasons.is_empty() {
+        let reasons: Vec<String> = summary.skip_reasons.iter().map(|(reason, n)| format!("{} {}", reason.label(), n)).collect();
+        log_info!("跳过的文件: {}", reasons.join("，"));
+    }
+    // 仅复用与离线模式不调用 API，没有可比较的数据
+    if config.record_run_history
+        && !config.reuse_only
+        && !config.mock_provider
+        && let Err(e) = run_history::append(RunRecord::new(&config, &summary, client.usage()))
+    {
+        log_warn!("无法保存运行统计: {}", e);
     }
-}
\ No newline at end of file
+    // 先发送汇总，命令执行期间界面已能看到本次结果
+    send_run_summary(summary.clone());
+    post_run::run(&config.post_run_command, &output, &summary, ctx.mc_version, config.post_run_timeout, &token).await;
+    status
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::{AppMsg, Logger, MemorySink};
+    use std::fs;
+
+    #[tokio::test]
+    async fn mock_run_reports_through_injected_logger() {
+        let root = std::env::temp_dir().join(format!("mc_translator_test_{}", std::process::id()));
+        let lang_dir = root.join("input/assets/demo/lang");
+        fs::create_dir_all(&lang_dir).unwrap();
+        fs::write(lang_dir.join("en_us.json"), r#"{"item.demo.apple": "Apple", "item.demo.pear": "Pear"}"#).unwrap();
+        let config = AppConfig {
+            input_path: root.join("input").to_string_lossy().to_string(),
+            output_path: root.join("output").to_string_lossy().to_string(),
+            mock_provider: true,
+            ..AppConfig::default()
+        };
+
+        let sink = MemorySink::default();
+        let status = Logger::new(sink.clone())
+            .scope(run_processing_task(config, false, CancellationToken::new()))
+            .await;
+        let messages = sink.messages();
+        let output = fs::read_to_string(root.join("output/assets/demo/lang/zh_cn.json"));
+        let _ = fs::remove_dir_all(&root);
+
+        assert_eq!(status, RunStatus::Completed);
+        let summary = messages.iter().find_map(|msg| match msg {
+            AppMsg::RunSummary(summary) => Some(summary),
+            _ => None,
+        });
+        assert_eq!(summary.map(|s| (s.processed_files, s.translated_entries)), Some((1, 2)));
+        assert!(messages.iter().any(|msg| matches!(msg, AppMsg::Log(entry) if entry.message.contains("任务已完成"))));
+        assert!(messages.iter().any(|msg| matches!(msg, AppMsg::FileStatus { state: FileState::Done, .. })));
+        assert!(output.unwrap().contains("item.demo.pear"));
+    }
+}
@@ -0,0 +1,60 @@
+// 系统提示词检查：估算 token 数，并提示被误删的关键约束
+use regex::Regex;
+use std::sync::LazyLock;
+
+const KNOWN_PLACEHOLDERS: &[&str] = &["MOD_ID", "SOURCE_LANG", "TARGET_LANG"];
+
+static PLACEHOLDER_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\{([A-Z][A-Z_]*)\}").unwrap());
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PromptWarning {
+    MissingOrder,
+    MissingLength,
+    MissingFormatCodes,
+    MissingJson,
+    UnknownPlaceholder(String),
+}
+
+/// 粗略估算 token 数：中日韩字符约 1 字 1 token，其余约 4 个字符 1 token
+pub fn estimate_tokens(text: &str) -> usize {
+    let (cjk, other) = text.chars().fold((0usize, 0usize), |(cjk, other), c| {
+        if is_cjk(c) { (cjk + 1, other) } else { (cjk, other + 1) }
+    });
+    cjk + other.div_ceil(4)
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF | 0xF900..=0xFAFF | 0xFF00..=0xFFEF)
+}
+
+fn mentions_any(text: &str, words: &[&str]) -> bool {
+    words.iter().any(|w| text.contains(w))
+}
+
+/// 检查提示词是否仍包含保持顺序、数量、格式代码和 JSON 输出的要求
+pub fn lint_prompt(prompt: &str) -> Vec<PromptWarning> {
+    let lower = prompt.to_lowercase();
+    let mut warnings = Vec::new();
+
+    if !mentions_any(&lower, &["顺序", "order"]) {
+        warnings.push(PromptWarning::MissingOrder);
+    }
+    if !mentions_any(&lower, &["长度", "数量", "length", "same number"]) {
+        warnings.push(PromptWarning::MissingLength);
+    }
+    if !mentions_any(&lower, &["格式代码", "占位符", "§", "placeholder", "format code"]) {
+        warnings.push(PromptWarning::MissingFormatCodes);
+    }
+    if !lower.contains("json") {
+        warnings.push(PromptWarning::MissingJson);
+    }
+    for cap in PLACEHOLDER_RE.captures_iter(prompt) {
+        let name = &cap[1];
+        let warning = PromptWarning::UnknownPlaceholder(cap[0].to_string());
+        if !KNOWN_PLACEHOLDERS.contains(&name) && !warnings.contains(&warning) {
+            warnings.push(warning);
+        }
+    }
+    warnings
+}
@@ -0,0 +1,59 @@
+// 伪本地化：不联网生成“看得出是译文”的文本，用于测试输出路径和格式代码处理
+
+fn accent(c: char) -> char {
+    match c {
+        'a' => 'á', 'e' => 'é', 'i' => 'í', 'o' => 'ó', 'u' => 'ú',
+        'A' => 'Á', 'E' => 'É', 'I' => 'Í', 'O' => 'Ó', 'U' => 'Ú',
+        'c' => 'ç', 'C' => 'Ç', 'n' => 'ñ', 'N' => 'Ñ', 'y' => 'ý', 'Y' => 'Ý',
+        _ => c,
+    }
+}
+
+/// 返回从 `start` 开始的格式代码长度（§a、%s、%1$d、{0}、\n 等），不是格式代码则返回 None
+fn format_code_len(chars: &[char], start: usize) -> Option<usize> {
+    match chars[start] {
+        '§' if start + 1 < chars.len() => Some(2),
+        '\\' if start + 1 < chars.len() => Some(2),
+        '%' => {
+            let mut end = start + 1;
+            while end < chars.len() && (chars[end].is_ascii_digit() || chars[end] == '$' || chars[end] == '.') {
+                end += 1;
+            }
+            if end < chars.len() && (chars[end].is_ascii_alphabetic() || chars[end] == '%') {
+                Some(end + 1 - start)
+            } else {
+                None
+            }
+        }
+        '{' => chars[start..].iter().position(|&c| c == '}').map(|p| p + 1),
+        _ => None,
+    }
+}
+
+/// 生成伪译文：字母加重音、约 30% 长度填充并用方括号包裹，格式代码原样保留
+pub fn pseudo_translate(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len() * 2);
+    let mut letters: usize = 0;
+    let mut i = 0;
+
+    out.push('[');
+    while i < chars.len() {
+        if let Some(len) = format_code_len(&chars, i) {
+            out.extend(&chars[i..i + len]);
+            i += len;
+            continue;
+        }
+        let c = chars[i];
+        if c.is_alphabetic() {
+            letters += 1;
+        }
+        out.push(accent(c));
+        i += 1;
+    }
+    for _ in 0..letters.div_ceil(3) {
+        out.push('~');
+    }
+    out.push(']');
+    out
+}
@@ -0,0 +1,148 @@
+// 译文标点规范化：模型时常在中文里混用半角标点 (如 "你好,世界")，或把 ... 原样照抄。
+// 只处理与中日文字相邻的标点，英文原文、格式代码与数字中的标点保持不变
+use crate::config::PunctuationMode;
+use serde_json::{Map, Value};
+use unicode_normalization::UnicodeNormalization;
+
+/// 中日文字与全角标点，韩文通常使用半角标点，不计入
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3000}'..='\u{303f}' // 全角空格与中日标点
+        | '\u{3040}'..='\u{30ff}' // 假名
+        | '\u{3400}'..='\u{4dbf}'
+        | '\u{4e00}'..='\u{9fff}'
+        | '\u{f900}'..='\u{faff}'
+        | '\u{ff00}'..='\u{ffef}' // 全角符号
+        | '…' | '“' | '”' | '‘' | '’'
+    )
+}
+
+fn full_width(c: char) -> Option<char> {
+    Some(match c {
+        ',' => '，',
+        '!' => '！',
+        '?' => '？',
+        ':' => '：',
+        ';' => '；',
+        _ => return None,
+    })
+}
+
+fn half_width(c: char) -> Option<char> {
+    Some(match c {
+        '，' | '、' => ',',
+        '！' => '!',
+        '？' => '?',
+        '：' => ':',
+        '；' => ';',
+        '。' => '.',
+        _ => return None,
+    })
+}
+
+fn to_full_width(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    // 每个未闭合的 ( 是否已转为全角，) 与之保持一致
+    let mut parens: Vec<bool> = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let prev_cjk = out.chars().last().is_some_and(is_cjk);
+        let next = chars.get(i + 1).copied();
+
+        // 连续三个以上的 . 或 … 视为省略号
+        let run = chars[i..].iter().take_while(|&&ch| ch == c).count();
+        if (c == '.' && run >= 3) || c == '…' {
+            if prev_cjk || chars.get(i + run).copied().is_some_and(is_cjk) {
+                out.push_str("……");
+            } else {
+                out.extend(&chars[i..i + run]);
+            }
+            i += run;
+            continue;
+        }
+
+        let converted = match c {
+            '(' => {
+                let convert = prev_cjk || next.is_some_and(is_cjk);
+                parens.push(convert);
+                convert.then_some('（')
+            }
+            ')' => parens.pop().filter(|&convert| convert).map(|_| '）'),
+            // 句号只在句末或后接空白、中日文字时转换，避免改动 1.0、a.b 之类
+            '.' if prev_cjk && next.is_none_or(|n| n.is_whitespace() || is_cjk(n)) => Some('。'),
+            _ if prev_cjk => full_width(c),
+            _ => None,
+        };
+        match converted {
+            Some(full) => {
+                out.push(full);
+                i += 1;
+                // 全角标点自带间距，去掉其后的半角空格
+                if full != '（' {
+                    while chars.get(i) == Some(&' ') && i + 1 < chars.len() {
+                        i += 1;
+                    }
+                }
+            }
+            None => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+fn to_half_width(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == '…' {
+            out.push_str("...");
+            i += chars[i..].iter().take_while(|&&ch| ch == '…').count();
+            continue;
+        }
+        i += 1;
+        match c {
+            '（' => out.push('('),
+            '）' => out.push(')'),
+            _ => match half_width(c) {
+                Some(half) => {
+                    out.push(half);
+                    // 半角标点后补一个空格，句末或后接空白、其他标点时不补
+                    if chars.get(i).is_some_and(|&n| !n.is_whitespace() && !n.is_ascii_punctuation() && half_width(n).is_none() && n != '）') {
+                        out.push(' ');
+                    }
+                }
+                None => out.push(c),
+            },
+        }
+    }
+    out
+}
+
+/// 先做 NFC 规范化 (合并组合字符)，再统一标点
+pub fn normalize(text: &str, mode: PunctuationMode) -> String {
+    let text: String = text.nfc().collect();
+    match mode {
+        PunctuationMode::Keep => text,
+        PunctuationMode::FullWidth => to_full_width(&text),
+        PunctuationMode::HalfWidth => to_half_width(&text),
+    }
+}
+
+/// 对写出的所有字符串值应用规范化
+pub fn normalize_map(map: &mut Map<String, Value>, mode: PunctuationMode) {
+    if mode == PunctuationMode::Keep {
+        return;
+    }
+    for value in map.values_mut() {
+        if let Value::String(text) = value {
+            *text = normalize(text, mode);
+        }
+    }
+}
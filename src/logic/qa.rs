@@ -0,0 +1,65 @@
+use crate::message::send_qa_issue;
+
+/// 译文质量问题，供 UI 的 QA 列表人工复核
+#[derive(Debug, Clone)]
+pub struct QaIssue {
+    pub context_id: String,
+    pub key: String,
+    pub source: String,
+    pub translated: String,
+    pub reason: String,
+}
+
+// 过短的原文长度比例没有参考意义
+const MIN_CHECK_LEN: usize = 12;
+
+fn count_newlines(s: &str) -> usize {
+    s.matches('\n').count() + s.matches("\\n").count()
+}
+
+/// 检查译文长度是否明显异常，返回问题描述
+pub fn check_length(source: &str, translated: &str, min_ratio: f32, max_ratio: f32) -> Option<String> {
+    let src_len = source.chars().count();
+    let dst_len = translated.chars().count();
+
+    if count_newlines(source) > 0 && count_newlines(translated) == 0 {
+        return Some("译文丢失了全部换行符".to_string());
+    }
+
+    if src_len < MIN_CHECK_LEN {
+        return None;
+    }
+
+    let ratio = dst_len as f32 / src_len as f32;
+    if ratio < min_ratio {
+        Some(format!("译文过短 (长度比 {:.2})", ratio))
+    } else if ratio > max_ratio {
+        Some(format!("译文过长 (长度比 {:.2})", ratio))
+    } else {
+        None
+    }
+}
+
+/// 检查并上报到 UI，返回是否发现问题
+pub fn report_length_issue(
+    context_id: &str,
+    key: &str,
+    source: &str,
+    translated: &str,
+    min_ratio: f32,
+    max_ratio: f32,
+) -> bool {
+    match check_length(source, translated, min_ratio, max_ratio) {
+        Some(reason) => {
+            send_qa_issue(QaIssue {
+                context_id: context_id.to_string(),
+                key: key.to_string(),
+                source: source.to_string(),
+                translated: translated.to_string(),
+                reason,
+            });
+            true
+        }
+        None => false,
+    }
+}
@@ -0,0 +1,89 @@
+// 原文快照：在 output_root/raw_content 保存每个语言文件上次翻译时的原文，
+// 增量更新时据此找出原文有改动的条目重新翻译，过期的快照按保留天数清理
+use crate::log_info;
+use crate::logic::paths::sanitize_component;
+use crate::logic::error::Result;
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+pub const DIR_NAME: &str = "raw_content";
+
+/// 快照文件信息，供界面浏览
+#[derive(Debug, Clone)]
+pub struct SnapshotFile {
+    pub path: PathBuf,
+    pub name: String,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+}
+
+pub fn dir(output_root: &Path) -> PathBuf {
+    output_root.join(DIR_NAME)
+}
+
+fn snapshot_path(output_root: &Path, mod_id: &str, original_filename: &str) -> PathBuf {
+    dir(output_root).join(sanitize_component(&format!("{}_{}", mod_id, original_filename)))
+}
+
+pub fn load(output_root: &Path, mod_id: &str, original_filename: &str) -> Option<Map<String, Value>> {
+    let content = fs::read_to_string(snapshot_path(output_root, mod_id, original_filename)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// 保存本次的原文。内容未变时也重写，修改时间即为该模组最近一次处理的时间
+pub fn save(output_root: &Path, mod_id: &str, original_filename: &str, source: &Map<String, Value>) -> Result<()> {
+    fs::create_dir_all(dir(output_root))?;
+    let file = fs::File::create(snapshot_path(output_root, mod_id, original_filename))?;
+    serde_json::to_writer_pretty(file, source)?;
+    Ok(())
+}
+
+/// 快照与当前原文都有、但文本不同的键；上次翻译失败的条目在快照中为 null，同样计入
+pub fn changed_keys(snapshot: &Map<String, Value>, source: &Map<String, Value>) -> Vec<String> {
+    source
+        .iter()
+        .filter(|(k, v)| snapshot.get(*k).is_some_and(|old| old != *v))
+        .map(|(k, _)| k.clone())
+        .collect()
+}
+
+/// 列出所有快照文件，按文件名排序
+pub fn list(output_root: &Path) -> Vec<SnapshotFile> {
+    let Ok(entries) = fs::read_dir(dir(output_root)) else {
+        return Vec::new();
+    };
+    let mut files: Vec<SnapshotFile> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let meta = e.metadata().ok().filter(|m| m.is_file())?;
+            Some(SnapshotFile {
+                name: e.file_name().to_string_lossy().to_string(),
+                path: e.path(),
+                size: meta.len(),
+                modified: meta.modified().ok(),
+            })
+        })
+        .collect();
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+    files
+}
+
+/// 删除超过保留天数未更新的快照 (对应模组多半已移出整合包)，0 表示永久保留，返回删除的数量
+pub fn prune(output_root: &Path, retention_days: u64) -> usize {
+    if retention_days == 0 {
+        return 0;
+    }
+    let max_age = Duration::from_secs(retention_days * 24 * 60 * 60);
+    let now = SystemTime::now();
+    let removed = list(output_root)
+        .into_iter()
+        .filter(|f| f.modified.and_then(|m| now.duration_since(m).ok()).is_some_and(|age| age > max_age))
+        .filter(|f| fs::remove_file(&f.path).is_ok())
+        .count();
+    if removed > 0 {
+        log_info!("已清理 {} 个超过 {} 天未更新的原文快照", removed, retention_days);
+    }
+    removed
+}
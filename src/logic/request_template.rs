@@ -0,0 +1,66 @@
+// 自定义请求体模板，用于请求格式与 OpenAI 略有差异的兼容网关
+use crate::logic::error::{Result, TranslatorError};
+use serde_json::{Map, Value};
+
+/// 与内置请求体等价的模板，供用户在此基础上修改
+pub const DEFAULT_TEMPLATE: &str = r#"{
+  "model": "{MODEL}",
+  "messages": [
+    {"role": "system", "content": "{SYSTEM}"},
+    {"role": "user", "content": "{USER}"}
+  ],
+  "temperature": 0.1,
+  "stream": "{STREAM}"
+}"#;
+
+pub const PLACEHOLDERS: [&str; 4] = ["{MODEL}", "{SYSTEM}", "{USER}", "{STREAM}"];
+
+/// 解析模板，顶层必须是 JSON 对象
+pub fn parse_template(text: &str) -> Result<Value> {
+    let value: Value = serde_json::from_str(text)?;
+    if !value.is_object() {
+        return Err(TranslatorError::Other("请求模板必须是 JSON 对象".to_string()));
+    }
+    Ok(value)
+}
+
+/// 模板中缺少的必要占位符
+pub fn missing_placeholders(text: &str) -> Vec<&'static str> {
+    ["{USER}", "{STREAM}"].into_iter().filter(|p| !text.contains(p)).collect()
+}
+
+/// 替换占位符：字符串整体为占位符时替换为对应类型的值 (如 "{STREAM}" -> true)，
+/// 否则在字符串内按文本替换
+pub fn render(template: &Value, vars: &[(&str, Value)]) -> Value {
+    match template {
+        Value::String(s) => {
+            if let Some((_, value)) = vars.iter().find(|(name, _)| name == s) {
+                return value.clone();
+            }
+            // 单次扫描替换，避免替换进来的内容 (如提示词) 中的占位符被再次替换
+            let mut text = String::with_capacity(s.len());
+            let mut rest = s.as_str();
+            while let Some((pos, name, value)) = vars
+                .iter()
+                .filter_map(|(name, value)| rest.find(name).map(|pos| (pos, *name, value)))
+                .min_by_key(|(pos, _, _)| *pos)
+            {
+                text.push_str(&rest[..pos]);
+                match value {
+                    Value::String(v) => text.push_str(v),
+                    other => text.push_str(&other.to_string()),
+                }
+                rest = &rest[pos + name.len()..];
+            }
+            text.push_str(rest);
+            Value::String(text)
+        }
+        Value::Array(items) => Value::Array(items.iter().map(|item| render(item, vars)).collect()),
+        Value::Object(obj) => Value::Object(
+            obj.iter()
+                .map(|(key, value)| (key.clone(), render(value, vars)))
+                .collect::<Map<String, Value>>(),
+        ),
+        other => other.clone(),
+    }
+}
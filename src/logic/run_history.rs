@@ -0,0 +1,129 @@
+// 本地运行统计：每次运行结束后记录耗时、token 用量与失败率，只保存在本机，不会上传，
+// 便于长期比较不同模型/服务商的性价比
+use crate::config::{AppConfig, data_dir};
+use crate::logic::openai::TokenUsage;
+use crate::logic::processor::{RunStatus, RunSummary};
+use crate::logic::error::Result;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// 最多保留的记录条数，超出后丢弃最早的
+const MAX_RECORDS: usize = 1000;
+const HISTORY_FILE: &str = "run_history.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub finished_at: String,
+    pub model: String,
+    pub base_url: String,
+    pub input: String,
+    pub status: RunStatus,
+    pub elapsed_secs: u64,
+    pub translated_entries: usize,
+    pub failed_entries: usize,
+    pub prompt_tokens: usize,
+    pub completion_tokens: usize,
+    pub tokens_estimated: bool, // 服务商未返回 usage，token 数按字符估算
+}
+
+impl RunRecord {
+    pub fn new(config: &AppConfig, summary: &RunSummary, usage: &TokenUsage) -> Self {
+        let (prompt_tokens, completion_tokens, tokens_estimated) = usage.totals();
+        Self {
+            finished_at: Local::now().format("%Y-%m-%d %H:%M").to_string(),
+            model: config.model.clone(),
+            base_url: config.base_url.clone(),
+            input: summary.input.clone(),
+            status: summary.status,
+            elapsed_secs: summary.elapsed.as_secs(),
+            translated_entries: summary.translated_entries,
+            failed_entries: summary.failed_entries,
+            prompt_tokens,
+            completion_tokens,
+            tokens_estimated,
+        }
+    }
+}
+
+/// 同一模型所有运行的汇总
+#[derive(Debug, Clone, Default)]
+pub struct ModelStats {
+    pub model: String,
+    pub runs: usize,
+    pub translated_entries: usize,
+    pub failed_entries: usize,
+    pub tokens: usize,
+    pub elapsed_secs: u64,
+    pub estimated: bool,
+}
+
+impl ModelStats {
+    pub fn failure_rate(&self) -> f32 {
+        let total = self.translated_entries + self.failed_entries;
+        if total == 0 { 0.0 } else { self.failed_entries as f32 / total as f32 }
+    }
+
+    /// 每条成功译文平均消耗的 token
+    pub fn tokens_per_entry(&self) -> f32 {
+        if self.translated_entries == 0 { 0.0 } else { self.tokens as f32 / self.translated_entries as f32 }
+    }
+
+    pub fn entries_per_minute(&self) -> f32 {
+        if self.elapsed_secs == 0 { 0.0 } else { self.translated_entries as f32 * 60.0 / self.elapsed_secs as f32 }
+    }
+}
+
+fn history_path() -> PathBuf {
+    data_dir().join(HISTORY_FILE)
+}
+
+pub fn load() -> Vec<RunRecord> {
+    fs::read_to_string(history_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save(records: &[RunRecord]) -> Result<()> {
+    let path = history_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(records)?)?;
+    Ok(())
+}
+
+pub fn append(record: RunRecord) -> Result<()> {
+    let mut records = load();
+    records.push(record);
+    let excess = records.len().saturating_sub(MAX_RECORDS);
+    records.drain(..excess);
+    save(&records)
+}
+
+pub fn clear() -> Result<()> {
+    save(&[])
+}
+
+/// 按模型汇总，按每条 token 消耗从低到高排序
+pub fn by_model(records: &[RunRecord]) -> Vec<ModelStats> {
+    let mut stats: BTreeMap<&str, ModelStats> = BTreeMap::new();
+    for record in records {
+        let entry = stats.entry(&record.model).or_insert_with(|| ModelStats {
+            model: record.model.clone(),
+            ..Default::default()
+        });
+        entry.runs += 1;
+        entry.translated_entries += record.translated_entries;
+        entry.failed_entries += record.failed_entries;
+        entry.tokens += record.prompt_tokens + record.completion_tokens;
+        entry.elapsed_secs += record.elapsed_secs;
+        entry.estimated |= record.tokens_estimated;
+    }
+    let mut stats: Vec<ModelStats> = stats.into_values().collect();
+    stats.sort_by(|a, b| a.tokens_per_entry().total_cmp(&b.tokens_per_entry()));
+    stats
+}
@@ -0,0 +1,183 @@
+// 宽松 JSON：很多模组的语言文件是手写的，带注释、尾逗号、单引号或未加引号的键，
+// 先整理为标准 JSON 再交给 serde_json 解析
+use serde_json::{Map, Value};
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// 跳过注释直到行尾 (不消费换行符)
+fn skip_line(chars: &mut Peekable<Chars>) {
+    while let Some(&c) = chars.peek() {
+        if c == '\n' || c == '\r' {
+            break;
+        }
+        chars.next();
+    }
+}
+
+/// 跳过 /* */ 块注释，开头的 /* 已消费。注释以空格代替并保留其中的换行，
+/// 解析报错的行列号与原文件一致
+fn skip_block(chars: &mut Peekable<Chars>, result: &mut String) {
+    result.push_str("  ");
+    let mut prev = '\0';
+    for c in chars.by_ref() {
+        result.push(if c == '\n' { '\n' } else { ' ' });
+        if prev == '*' && c == '/' {
+            break;
+        }
+        prev = c;
+    }
+}
+
+/// 遇到 } 或 ] 时把其前面多余的逗号换成空格
+fn drop_trailing_comma(result: &mut String) {
+    let kept = result.trim_end().len();
+    if result[..kept].ends_with(',') {
+        result.replace_range(kept - 1..kept, " ");
+    }
+}
+
+fn is_key_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '$')
+}
+
+/// 读取一个双引号或单引号字符串 (开头的引号已消费)，以双引号字符串写入 `result`：
+/// 字符串内的换行、制表符转义，其余控制字符丢弃
+fn copy_string(chars: &mut Peekable<Chars>, quote: char, result: &mut String) {
+    result.push('"');
+    let mut escape = false;
+    for c in chars.by_ref() {
+        if escape {
+            // 反斜杠后的回车忽略，等待换行
+            if c == '\r' {
+                continue;
+            }
+            escape = false;
+            match c {
+                // 反斜杠后直接换行，视为 \n
+                '\n' => result.push_str("\\n"),
+                // 单引号字符串中的 \' 在 JSON 中不需要转义
+                '\'' => result.push('\''),
+                _ => {
+                    result.push('\\');
+                    result.push(c);
+                }
+            }
+            continue;
+        }
+        match c {
+            '\\' => escape = true,
+            _ if c == quote => break,
+            '"' => result.push_str("\\\""),
+            '\n' => result.push_str("\\n"),
+            '\r' => {}
+            '\t' => result.push_str("\\t"),
+            _ if c.is_control() => {}
+            _ => result.push(c),
+        }
+    }
+    result.push('"');
+}
+
+/// 整理为标准 JSON：移除 BOM、`//` `#` `/* */` 注释与尾逗号，单引号字符串改为双引号，
+/// 为未加引号的键补上引号，并处理字符串中的换行与控制字符。
+/// 输入本身无法修复时 (如缺少括号) 原样保留问题，由解析阶段报错
+pub fn sanitize(content: &str) -> String {
+    let mut result = String::with_capacity(content.len());
+    let mut chars = content.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '/' if chars.peek() == Some(&'/') => skip_line(&mut chars),
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                skip_block(&mut chars, &mut result);
+            }
+            '#' => skip_line(&mut chars),
+            '"' | '\'' => copy_string(&mut chars, c, &mut result),
+            '}' | ']' => {
+                drop_trailing_comma(&mut result);
+                result.push(c);
+            }
+            _ if c.is_alphabetic() || c == '_' || c == '$' => {
+                let mut word = String::from(c);
+                while let Some(&next) = chars.peek() {
+                    if !is_key_char(next) {
+                        break;
+                    }
+                    word.push(next);
+                    chars.next();
+                }
+                // 后面紧跟冒号的才是键，true/false/null 等值保持原样
+                let mut lookahead = chars.clone();
+                while lookahead.next_if(|c| c.is_whitespace()).is_some() {}
+                if lookahead.peek() == Some(&':') {
+                    result.push('"');
+                    result.push_str(&word);
+                    result.push('"');
+                } else {
+                    result.push_str(&word);
+                }
+            }
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// 宽松解析语言文件，根节点必须是对象
+pub fn parse_object(content: &str) -> Result<Map<String, Value>, String> {
+    match serde_json::from_str::<Value>(&sanitize(content)) {
+        Ok(Value::Object(map)) => Ok(map),
+        Ok(_) => Err("根节点不是对象".to_string()),
+        Err(e) => Err(format!("JSON 解析失败: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drops_trailing_commas() {
+        let map = parse_object("{\"a\": [1, 2,], \"b\": \"x\",}").unwrap();
+        assert_eq!(map["a"], serde_json::json!([1, 2]));
+        assert_eq!(map["b"], "x");
+    }
+
+    #[test]
+    fn converts_single_quoted_strings() {
+        let map = parse_object("{'a': 'it\\'s \"quoted\"'}").unwrap();
+        assert_eq!(map["a"], "it's \"quoted\"");
+    }
+
+    #[test]
+    fn quotes_bare_keys() {
+        let map = parse_object("{item.foo: \"Foo\", flag: true}").unwrap();
+        assert_eq!(map["item.foo"], "Foo");
+        assert_eq!(map["flag"], true);
+    }
+
+    #[test]
+    fn strips_comments() {
+        let content = "{\n  // 行注释\n  \"a\": \"1\", /* 块\n注释 */ \"b\": \"2\"\n}";
+        let map = parse_object(content).unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map["b"], "2");
+    }
+
+    #[test]
+    fn keeps_slashes_inside_strings() {
+        let map = parse_object("{\"url\": \"https://example.com\", \"c\": \"/* x */\"}").unwrap();
+        assert_eq!(map["url"], "https://example.com");
+        assert_eq!(map["c"], "/* x */");
+    }
+
+    #[test]
+    fn keeps_line_and_column_after_comments() {
+        let content = "{\n  /* 多行\n  注释 */ \"a\": 1,\n  \"b\": ?\n}";
+        let sanitized = sanitize(content);
+        assert_eq!(sanitized.lines().count(), content.lines().count());
+        let err = serde_json::from_str::<Value>(&sanitized).unwrap_err();
+        assert_eq!((err.line(), err.column()), (4, 8));
+    }
+}
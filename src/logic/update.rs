@@ -0,0 +1,53 @@
+// 检查 GitHub Releases 上是否有新版本
+use crate::logic::error::{Result, TranslatorError};
+use serde_json::Value;
+use std::time::Duration;
+
+const RELEASES_API: &str = "https://api.github.com/repos/chrysoljq/mc_translator/releases/latest";
+
+#[derive(Debug, Clone)]
+pub struct ReleaseInfo {
+    pub version: String,
+    pub url: String,
+}
+
+/// 将 "v0.2.13" 之类的版本号拆成数字段，无法解析的部分按 0 处理
+fn parse_version(v: &str) -> Vec<u64> {
+    v.trim()
+        .trim_start_matches(['v', 'V'])
+        .split(['.', '-', '+'])
+        .take(3)
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+pub fn is_newer(latest: &str, current: &str) -> bool {
+    parse_version(latest) > parse_version(current)
+}
+
+/// 有比当前版本更新的正式发布时返回其信息
+pub async fn check_latest_release() -> Result<Option<ReleaseInfo>> {
+    let client = reqwest::Client::builder()
+        .user_agent(concat!("mc_translator/", env!("CARGO_PKG_VERSION")))
+        .timeout(Duration::from_secs(15))
+        .build()?;
+
+    let resp = client.get(RELEASES_API).send().await?;
+    if !resp.status().is_success() {
+        return Err(TranslatorError::Other(format!("GitHub API 返回 {}", resp.status())));
+    }
+    let json: Value = resp.json().await?;
+
+    let version = json["tag_name"]
+        .as_str()
+        .ok_or_else(|| TranslatorError::Other("发布信息中没有 tag_name".to_string()))?
+        .to_string();
+    if !is_newer(&version, env!("CARGO_PKG_VERSION")) {
+        return Ok(None);
+    }
+    let url = json["html_url"]
+        .as_str()
+        .unwrap_or("https://github.com/chrysoljq/mc_translator/releases")
+        .to_string();
+    Ok(Some(ReleaseInfo { version, url }))
+}
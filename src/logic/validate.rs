@@ -0,0 +1,151 @@
+// 输出校验：按 Minecraft 的要求重新解析写出的语言文件，提前发现会导致整个文件加载失败的问题
+use crate::logic::tolerant_json;
+use crate::{log_err, log_success, log_warn};
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use walkdir::WalkDir;
+
+// 被多转义一层的 §，游戏中会显示为字面文本
+const ESCAPED_SECTION: &str = "\\u00a7";
+
+fn validate_json(content: &str) -> Result<(), String> {
+    if content.starts_with('\u{feff}') {
+        return Err("文件以 BOM 开头".to_string());
+    }
+    // 不做 sanitize，注释、尾逗号和非法转义都会导致游戏内解析失败
+    let json: Value = serde_json::from_str(content).map_err(|e| format!("JSON 解析失败: {}", e))?;
+    let Some(map) = json.as_object() else {
+        return Err("根节点不是对象".to_string());
+    };
+    if let Some((key, _)) = map.iter().find(|(_, v)| !v.is_string()) {
+        return Err(format!("键 {} 的值不是字符串", key));
+    }
+    if let Some((key, _)) = map.iter().find(|(_, v)| v.as_str().is_some_and(|s| s.contains(ESCAPED_SECTION))) {
+        return Err(format!("键 {} 的值包含字面的 \\u00a7，游戏中不会显示为颜色代码", key));
+    }
+    Ok(())
+}
+
+/// 尝试修复 JSON 语言文件：按宽松规则重新解析后写成标准 JSON，并还原多转义的 §；
+/// 存在非字符串值等无法安全修复的问题时返回 None
+fn repair_json(content: &str) -> Option<String> {
+    let mut map = tolerant_json::parse_object(content).ok()?;
+    for value in map.values_mut() {
+        let Value::String(text) = value else {
+            return None;
+        };
+        if text.contains(ESCAPED_SECTION) {
+            *text = text.replace(ESCAPED_SECTION, "§");
+        }
+    }
+    let fixed = serde_json::to_string_pretty(&map).ok()?;
+    validate_json(&fixed).is_ok().then_some(fixed)
+}
+
+fn validate_lang(content: &str) -> Result<(), String> {
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.split_once('=') {
+            Some((key, _)) if !key.trim().is_empty() => {}
+            _ => return Err(format!("第 {} 行不是 key=value 格式", i + 1)),
+        }
+        if line.chars().any(|c| c.is_control() && c != '\t') {
+            return Err(format!("第 {} 行包含控制字符", i + 1));
+        }
+        // .lang 文件不解码 Unicode 转义
+        if line.contains("\\u00a7") {
+            return Err(format!("第 {} 行使用 \\u00a7 转义，游戏中会显示为字面文本", i + 1));
+        }
+    }
+    Ok(())
+}
+
+/// 修复 .lang 文件中的 BOM、控制字符与 \u00a7 转义，格式错误的行无法修复
+fn repair_lang(content: &str) -> Option<String> {
+    let fixed: String = content
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .map(|line| {
+            let line: String = line.chars().filter(|c| !c.is_control() || *c == '\t').collect();
+            line.replace("\\u00a7", "§") + "\n"
+        })
+        .collect();
+    validate_lang(&fixed).is_ok().then_some(fixed)
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "json")
+}
+
+fn validate_file(path: &Path) -> Result<(), String> {
+    let bytes = fs::read(path).map_err(|e| e.to_string())?;
+    let content = String::from_utf8(bytes).map_err(|_| "不是有效的 UTF-8 编码".to_string())?;
+    if is_json(path) {
+        validate_json(&content)
+    } else {
+        validate_lang(&content)
+    }
+}
+
+/// 尝试就地修复，成功返回 true
+fn repair_file(path: &Path) -> bool {
+    let Ok(content) = fs::read_to_string(path) else {
+        return false;
+    };
+    let fixed = if is_json(path) { repair_json(&content) } else { repair_lang(&content) };
+    match fixed {
+        Some(fixed) => match fs::write(path, fixed) {
+            Ok(()) => true,
+            Err(e) => {
+                log_warn!("写入修复后的文件失败 {:?}: {}", path, e);
+                false
+            }
+        },
+        None => false,
+    }
+}
+
+/// 校验输出目录下所有 lang 目录中的语言文件并写入日志，能安全修复的问题就地修复，
+/// 返回仍有问题的文件数
+pub fn validate_output(output_root: &str) -> usize {
+    let mut checked = 0;
+    let mut invalid = 0;
+    let mut repaired = 0;
+    for entry in WalkDir::new(output_root).into_iter().filter_map(|e| e.ok()) {
+        let path = entry.path();
+        let in_lang_dir = path
+            .parent()
+            .and_then(|p| p.file_name())
+            .is_some_and(|n| n == "lang");
+        let is_lang_file = path
+            .extension()
+            .is_some_and(|ext| ext == "json" || ext == "lang");
+        if !entry.file_type().is_file() || !in_lang_dir || !is_lang_file {
+            continue;
+        }
+
+        checked += 1;
+        if let Err(reason) = validate_file(path) {
+            if repair_file(path) {
+                repaired += 1;
+                log_warn!("已自动修复 {:?}: {}", path, reason);
+            } else {
+                invalid += 1;
+                log_err!("校验失败 {:?}: {}，游戏将无法加载该文件", path, reason);
+            }
+        }
+    }
+
+    if invalid > 0 {
+        log_warn!("输出校验完成：{} 个文件中有 {} 个存在问题 (已自动修复 {} 个)", checked, invalid, repaired);
+    } else if repaired > 0 {
+        log_success!("输出校验通过，共 {} 个语言文件，其中 {} 个已自动修复", checked, repaired);
+    } else {
+        log_success!("输出校验通过，共 {} 个语言文件", checked);
+    }
+    invalid
+}
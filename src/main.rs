@@ -1,34 +1,71 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // 发布时隐藏控制台
 
 mod config;
+mod crash;
 mod logging;
 mod message;
 mod logic;
+mod server;
 mod ui {
     pub mod app;
     pub mod icon;
     pub mod fonts;
+    pub mod notify;
+    pub mod sender;
+    pub use crate::logic::i18n;
 }
 
+use config::AppConfig;
 use ui::app::MyApp;
 
 use crate::ui::icon::load_icon;
 
+/// `--serve 127.0.0.1:8080` 或 `--serve=127.0.0.1:8080`，省略地址时监听 127.0.0.1:7860
+fn serve_addr() -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if let Some(addr) = arg.strip_prefix("--serve=") {
+            return Some(addr.to_string());
+        }
+        if arg == "--serve" {
+            return Some(args.next().filter(|a| !a.starts_with("--")).unwrap_or_else(|| "127.0.0.1:7860".to_string()));
+        }
+    }
+    None
+}
+
 fn main() -> eframe::Result {
     // 初始化日志系统（可选）
     env_logger::init();
+    crash::install_panic_hook();
+
+    // 控制服务模式：不打开界面，通过 HTTP 接口提交任务
+    if let Some(addr) = serve_addr() {
+        if let Err(e) = server::serve(&addr) {
+            eprintln!("控制服务启动失败: {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let config = AppConfig::load(); // 加载保存的配置
+    crash::set_config_snapshot(&config);
+    let mut viewport = eframe::egui::ViewportBuilder::default()
+        .with_inner_size(config.window_size.unwrap_or([810.0, 500.0]))
+        .with_title("MC Mod Translator")
+        .with_icon(load_icon());
+    if let Some(pos) = config.window_pos {
+        viewport = viewport.with_position(pos);
+    }
 
     let options = eframe::NativeOptions {
-        viewport: eframe::egui::ViewportBuilder::default()
-            .with_inner_size([810.0, 500.0])
-            .with_title("MC Mod Translator")
-            .with_icon(load_icon()),
+        viewport,
         ..Default::default()
     };
-    
+
     eframe::run_native(
         "MC Translator",
         options,
-        Box::new(|cc| Ok(Box::new(MyApp::new(cc)))),
+        Box::new(|cc| Ok(Box::new(MyApp::new(cc, config)))),
     )
 }
\ No newline at end of file
@@ -1,19 +1,142 @@
-use crossbeam_channel::Sender;
-use std::sync::OnceLock;
+use std::fmt;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use crate::config::AppConfig;
 use crate::logging::{LogEntry, LogLevel};
+use crate::logic::benchmark::CompareResult;
+use crate::logic::concurrency::ConcurrencySnapshot;
+use crate::logic::diff::DiffReport;
+use crate::logic::frequency::StringStats;
+use crate::logic::glossary::GlossarySuggestion;
+use crate::logic::merge::MergePlan;
+use crate::logic::pack_manifest::ModUpdate;
+use crate::logic::processor::{FileState, RunStatus, RunSummary};
+use crate::logic::qa::QaIssue;
+use crate::logic::update::ReleaseInfo;
 
-pub static GLOBAL_SENDER: OnceLock<Sender<AppMsg>> = OnceLock::new();
+/// 界面启动时设置，未在 Logger::scope 中运行的代码发往这里
+pub static GLOBAL_SENDER: OnceLock<Arc<dyn MessageSink>> = OnceLock::new();
+
+tokio::task_local! {
+    static CURRENT: Logger;
+}
+
+/// 后台消息的接收方：界面通道、控制服务中的单个任务或测试中的内存缓冲
+pub trait MessageSink: Send + Sync {
+    /// 返回 false 表示接收方已关闭
+    fn send(&self, msg: AppMsg) -> bool;
+}
+
+/// 测试中的接收方：消息留在内存中，供测试断言日志与统计
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub struct MemorySink(Arc<std::sync::Mutex<Vec<AppMsg>>>);
+
+#[cfg(test)]
+impl MemorySink {
+    pub fn messages(&self) -> Vec<AppMsg> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+impl MessageSink for MemorySink {
+    fn send(&self, msg: AppMsg) -> bool {
+        self.0.lock().unwrap().push(msg);
+        true
+    }
+}
+
+/// 可注入的消息句柄，由 TranslationContext 携带。
+/// 在 scope 中运行的任务，其中的 log_* 宏与 send_* 函数都发往该句柄；未指定接收方时使用 GLOBAL_SENDER
+#[derive(Clone, Default)]
+pub struct Logger(Option<Arc<dyn MessageSink>>);
+
+impl fmt::Debug for Logger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.0.is_some() { "Logger(custom)" } else { "Logger(global)" })
+    }
+}
+
+impl Logger {
+    pub fn new(sink: impl MessageSink + 'static) -> Self {
+        Self(Some(Arc::new(sink)))
+    }
+
+    /// 当前任务所在 scope 的句柄，不在任何 scope 中时为全局句柄
+    pub fn current() -> Self {
+        CURRENT.try_with(Logger::clone).unwrap_or_default()
+    }
+
+    pub fn send(&self, msg: AppMsg) -> bool {
+        match &self.0 {
+            Some(sink) => sink.send(msg),
+            None => GLOBAL_SENDER.get().is_some_and(|sink| sink.send(msg)),
+        }
+    }
+
+    /// 在该句柄下运行 future。tokio::spawn 出的任务不会继承 scope，需要重新包装
+    pub fn scope<F: Future>(self, future: F) -> impl Future<Output = F::Output> {
+        CURRENT.scope(self, future)
+    }
+}
+
+fn dispatch(msg: AppMsg) {
+    let _ = Logger::current().send(msg);
+}
 
 #[derive(Debug, Clone)]
 pub enum AppMsg {
     Log(LogEntry),
     ModelsFetched(Vec<String>),
+    HealthChecked(Option<u128>), // 测试请求延迟，失败为 None
+    QaIssue(QaIssue),
+    CompareFinished(Option<CompareResult>),
+    BatchProgress { label: String, received: usize, total: usize },
+    BatchDone(String),
+    Concurrency(ConcurrencySnapshot), // 进行中 / 上限 / 排队的请求数
+    TaskFinished(RunStatus), // 后台任务（含所有子任务）已全部退出
+    JobStarted(usize), // 队列任务序号
+    JobFinished(usize, RunStatus),
+    RunSummary(RunSummary), // 单次运行的文件与条目统计
+    FileStatus { path: PathBuf, output: String, state: FileState }, // output 为该文件所属任务的输出目录，重试时沿用
+    UpdateAvailable(ReleaseInfo),
+    MergePlanned(Option<MergePlan>),
+    DiffFinished(Option<DiffReport>),
+    AnalysisFinished(StringStats),
+    // 启动前的输入扫描与输出目录检查：Err 为输出目录不可写，Ok 中为剩余空间不足的提示
+    PreflightChecked { jobs: Vec<(Option<usize>, AppConfig)>, is_update: bool, use_queue: bool, outcome: Result<Vec<String>, String> },
+    ModUpdatesChecked(Option<Vec<ModUpdate>>), // 输出目录中没有 pack_manifest.json 时为 None
+    GlossarySuggested(Vec<GlossarySuggestion>),
 }
 
 pub fn send_log(level: LogLevel, msg: String) {
-    if let Some(sender) = GLOBAL_SENDER.get() {
-        let _ = sender.send(AppMsg::Log(LogEntry::new(level, msg)));
-    }
+    dispatch(AppMsg::Log(LogEntry::new(level, msg)));
+}
+
+pub fn send_batch_progress(label: &str, received: usize, total: usize) {
+    dispatch(AppMsg::BatchProgress { label: label.to_string(), received, total });
+}
+
+pub fn send_batch_done(label: &str) {
+    dispatch(AppMsg::BatchDone(label.to_string()));
+}
+
+pub fn send_concurrency(snapshot: ConcurrencySnapshot) {
+    dispatch(AppMsg::Concurrency(snapshot));
+}
+
+pub fn send_file_status(path: &Path, output: &str, state: FileState) {
+    dispatch(AppMsg::FileStatus { path: path.to_path_buf(), output: output.to_string(), state });
+}
+
+pub fn send_run_summary(summary: RunSummary) {
+    dispatch(AppMsg::RunSummary(summary));
+}
+
+pub fn send_qa_issue(issue: QaIssue) {
+    dispatch(AppMsg::QaIssue(issue));
 }
 
 #[macro_export]
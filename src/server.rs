@@ -0,0 +1,680 @@
+// 本地 HTTP 控制服务：`--serve 127.0.0.1:8080` 启动，不打开界面，
+// 供整合包构建流程或其他工具提交任务、查询进度并取回译文。
+// 只实现 JSON 接口所需的最小 HTTP/1.1 子集，每个连接处理一个请求
+use crate::config::{AppConfig, data_dir};
+use crate::logging::LogEntry;
+use crate::logic::processor::{self, FileState, RunStatus, RunSummary};
+use crate::log_warn;
+use crate::message::{AppMsg, GLOBAL_SENDER, Logger, MessageSink};
+use anyhow::{Result, anyhow};
+use serde_json::{Map, Value, json};
+use sha1::{Digest, Sha1};
+use std::collections::{BTreeMap, VecDeque};
+use std::hash::{BuildHasher, RandomState};
+use std::net::SocketAddr;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::{UnboundedSender, unbounded_channel};
+use tokio_util::sync::CancellationToken;
+use walkdir::WalkDir;
+
+const MAX_HEAD: usize = 64 * 1024;
+const MAX_BODY: usize = 1024 * 1024;
+const MAX_JOB_LOGS: usize = 2000;
+/// 读取一个请求 (请求头与请求体) 的时限，避免空闲或过慢的连接一直占用任务
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+/// 所有请求须携带 `Authorization: Bearer <值>`，未设置时启动时随机生成并写入数据目录下的 [`TOKEN_FILE`]
+const TOKEN_ENV: &str = "MC_TRANSLATOR_SERVE_TOKEN";
+const TOKEN_FILE: &str = "serve_token";
+/// 请求中 `config` 允许覆盖的配置项。密钥、接口地址、输出路径、脚本与命令等只能在本地配置中修改
+const OVERRIDABLE_KEYS: &[&str] = &[
+    "model",
+    "source_lang",
+    "target_lang",
+    "source_lang_fallbacks",
+    "batch_size",
+    "skip_existing",
+    "reuse_builtin",
+    "retranslate_identical",
+    "reuse_only",
+    "max_retries",
+    "retry_delay",
+    "batch_timeout",
+    "file_semaphore",
+    "max_network_concurrency",
+    "prompt",
+    "skip_quest",
+    "quest_mode",
+    "origins_mode",
+    "translate_structures",
+    "translate_config_comments",
+    "translate_description_data",
+    "mask_placeholders",
+    "color_codes",
+    "empty_values",
+    "failed_entries",
+    "punctuation",
+    "mock_provider",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum JobState {
+    Queued,
+    Running,
+    Finished(RunStatus),
+}
+
+impl JobState {
+    fn name(&self) -> &'static str {
+        match self {
+            JobState::Queued => "queued",
+            JobState::Running => "running",
+            JobState::Finished(RunStatus::Completed) => "completed",
+            JobState::Finished(RunStatus::Cancelled) => "cancelled",
+            JobState::Finished(RunStatus::Failed) => "failed",
+        }
+    }
+}
+
+fn file_state_name(state: FileState) -> &'static str {
+    match state {
+        FileState::Queued => "queued",
+        FileState::Translating => "translating",
+        FileState::Done => "done",
+        FileState::Failed => "failed",
+        FileState::Skipped => "skipped",
+    }
+}
+
+struct Job {
+    config: AppConfig,
+    update_existing: bool,
+    state: JobState,
+    token: CancellationToken,
+    logs: VecDeque<(usize, LogEntry)>, // (序号, 日志)，序号用于增量拉取
+    next_log: usize,
+    files: BTreeMap<PathBuf, FileState>,
+    batches: BTreeMap<String, (usize, usize)>, // 批次 -> (已收到, 总数)
+    summary: Option<RunSummary>,
+}
+
+impl Job {
+    fn push_log(&mut self, entry: LogEntry) {
+        self.logs.push_back((self.next_log, entry));
+        self.next_log += 1;
+        while self.logs.len() > MAX_JOB_LOGS {
+            self.logs.pop_front();
+        }
+    }
+
+    fn status_json(&self, id: u64) -> Value {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for state in self.files.values() {
+            *counts.entry(file_state_name(*state)).or_default() += 1;
+        }
+        let batches: Vec<Value> = self
+            .batches
+            .iter()
+            .map(|(label, (received, total))| json!({"label": label, "received": received, "total": total}))
+            .collect();
+        json!({
+            "id": id,
+            "state": self.state.name(),
+            "input_path": self.config.input_path,
+            "output_path": self.config.output_path,
+            "files": counts,
+            "batches": batches,
+            "summary": self.summary.as_ref().map(summary_json),
+        })
+    }
+}
+
+fn summary_json(summary: &RunSummary) -> Value {
+    json!({
+        "status": summary.status,
+        "processed_files": summary.processed_files,
+        "skipped_files": summary.skipped_files,
+        "skip_reasons": summary
+            .skip_reasons
+            .iter()
+            .map(|(reason, count)| (reason.name().to_string(), json!(count)))
+            .collect::<serde_json::Map<String, Value>>(),
+        "failed_files": summary.failed_files,
+        "translated_entries": summary.translated_entries,
+        "failed_entries": summary.failed_entries,
+        "reused_entries": summary.reused_entries,
+        "untranslated_entries": summary.untranslated_entries,
+        "empty_entries": summary.empty_entries,
+        "unparsable_files": summary.unparsable_files,
+        "failed_keys": summary.failed_keys,
+        "elapsed_secs": summary.elapsed.as_secs(),
+    })
+}
+
+#[derive(Default)]
+struct ServerState {
+    jobs: BTreeMap<u64, Job>,
+    next_id: u64,
+    session: String, // 服务启动时间，用于区分多次启动的任务输出目录
+}
+
+type Shared = Arc<Mutex<ServerState>>;
+
+/// 任务一次只执行一个：并发统计、输出锁等都按单次运行设计
+async fn run_worker(state: Shared, mut jobs: tokio::sync::mpsc::UnboundedReceiver<u64>) {
+    while let Some(id) = jobs.recv().await {
+        let Some((config, update_existing, token)) = ({
+            let mut state = state.lock().unwrap();
+            // 排队期间被取消的任务已标记为结束，直接跳过
+            state.jobs.get_mut(&id).filter(|job| job.state == JobState::Queued).map(|job| {
+                job.state = JobState::Running;
+                (job.config.clone(), job.update_existing, job.token.clone())
+            })
+        }) else {
+            continue;
+        };
+        let logger = Logger::new(JobSink { state: state.clone(), id });
+        let status = logger.scope(processor::run_processing_task(config, update_existing, token)).await;
+        let mut state = state.lock().unwrap();
+        if let Some(job) = state.jobs.get_mut(&id) {
+            job.state = JobState::Finished(status);
+            job.batches.clear();
+        }
+    }
+}
+
+fn print_log(entry: &LogEntry) {
+    println!("{} {:?} {}", entry.time, entry.level, entry.message);
+}
+
+/// 不属于任何任务的消息 (如连接错误) 的接收方：只把日志打印到标准输出
+struct ConsoleSink;
+
+impl MessageSink for ConsoleSink {
+    fn send(&self, msg: AppMsg) -> bool {
+        if let AppMsg::Log(entry) = &msg {
+            print_log(entry);
+        }
+        true
+    }
+}
+
+/// 单个任务的消息接收方：记入该任务并把日志打印到标准输出
+struct JobSink {
+    state: Shared,
+    id: u64,
+}
+
+impl MessageSink for JobSink {
+    fn send(&self, msg: AppMsg) -> bool {
+        if let AppMsg::Log(entry) = &msg {
+            print_log(entry);
+        }
+        let mut state = self.state.lock().unwrap();
+        let Some(job) = state.jobs.get_mut(&self.id) else {
+            return false;
+        };
+        match msg {
+            AppMsg::Log(entry) => job.push_log(entry),
+            AppMsg::FileStatus { path, state, .. } => {
+                job.files.insert(path, state);
+            }
+            AppMsg::BatchProgress { label, received, total } => {
+                job.batches.insert(label, (received, total));
+            }
+            AppMsg::BatchDone(label) => {
+                job.batches.remove(&label);
+            }
+            AppMsg::RunSummary(summary) => job.summary = Some(summary),
+            _ => {}
+        }
+        true
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    query: Vec<(String, String)>,
+    authorization: Option<String>,
+    content_type: Option<String>,
+    origin: Option<String>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    fn query(&self, key: &str) -> Option<&str> {
+        self.query.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+}
+
+/// 解码查询参数中的 %XX 与 +
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                    Some(b) => {
+                        out.push(b);
+                        i += 2;
+                    }
+                    None => out.push(b'%'),
+                }
+            }
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+async fn read_request(stream: &mut TcpStream) -> Result<Request> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let head_end = loop {
+        if let Some(pos) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        if buffer.len() > MAX_HEAD {
+            return Err(anyhow!("请求头过长"));
+        }
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Err(anyhow!("连接已关闭"));
+        }
+        buffer.extend_from_slice(&chunk[..n]);
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..head_end]).into_owned();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let target = request_line.next().unwrap_or("/");
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let query = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(k), percent_decode(v))
+        })
+        .collect();
+
+    let mut content_length = 0;
+    let mut authorization = None;
+    let mut content_type = None;
+    let mut origin = None;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else { continue };
+        match name.trim().to_ascii_lowercase().as_str() {
+            "content-length" => content_length = value.trim().parse().unwrap_or(0),
+            "authorization" => authorization = Some(value.trim().to_string()),
+            "content-type" => content_type = Some(value.trim().to_string()),
+            "origin" => origin = Some(value.trim().to_string()),
+            _ => {}
+        }
+    }
+    if content_length > MAX_BODY {
+        return Err(anyhow!("请求体过大"));
+    }
+    let mut body = buffer[head_end + 4..].to_vec();
+    while body.len() < content_length {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+    body.truncate(content_length);
+
+    Ok(Request { method, path: path.to_string(), query, authorization, content_type, origin, body })
+}
+
+struct Response {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+impl Response {
+    fn json(status: u16, body: Value) -> Self {
+        Self { status, content_type: "application/json; charset=utf-8", body: body.to_string().into_bytes() }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Self::json(status, json!({"error": message.into()}))
+    }
+}
+
+async fn write_response(stream: &mut TcpStream, response: Response) -> Result<()> {
+    let reason = match response.status {
+        200 => "OK",
+        201 => "Created",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        409 => "Conflict",
+        415 => "Unsupported Media Type",
+        _ => "Internal Server Error",
+    };
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        reason,
+        response.content_type,
+        response.body.len()
+    );
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(&response.body).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+/// 以当前配置为基础，合并请求中的 `config` 覆盖项，只接受 [`OVERRIDABLE_KEYS`] 中的配置项。
+/// 输出路径不能修改，提交时再改为其下该任务独有的目录
+fn job_config(base: &AppConfig, body: &Value) -> Result<AppConfig> {
+    if body.get("output_path").is_some() {
+        return Err(anyhow!("不能通过请求修改输出路径"));
+    }
+    let mut config = serde_json::to_value(base)?;
+    if let (Some(target), Some(overrides)) = (config.as_object_mut(), body["config"].as_object()) {
+        if let Some(key) = overrides.keys().find(|key| !OVERRIDABLE_KEYS.contains(&key.as_str())) {
+            return Err(anyhow!("不允许覆盖配置项: {}", key));
+        }
+        for (key, value) in overrides {
+            target.insert(key.clone(), value.clone());
+        }
+    }
+    if let Some(value) = body["input_path"].as_str() {
+        config["input_path"] = Value::String(value.to_string());
+    }
+    let mut config: AppConfig = serde_json::from_value(config).map_err(|e| anyhow!("配置无效: {}", e))?;
+    // 远程提交的任务不执行任务结束命令
+    config.post_run_command.clear();
+    if !Path::new(&config.input_path).exists() {
+        return Err(anyhow!("输入路径不存在: {}", config.input_path));
+    }
+    Ok(config)
+}
+
+fn submit_job(state: &Shared, queue: &UnboundedSender<u64>, base: &AppConfig, body: &[u8]) -> Response {
+    let body = if body.is_empty() {
+        Value::Object(Map::new())
+    } else {
+        match serde_json::from_slice(body) {
+            Ok(value @ Value::Object(_)) => value,
+            _ => return Response::error(400, "请求体须为 JSON 对象"),
+        }
+    };
+    let config = match job_config(base, &body) {
+        Ok(config) => config,
+        Err(e) => return Response::error(400, e.to_string()),
+    };
+    let mut state = state.lock().unwrap();
+    state.next_id += 1;
+    let id = state.next_id;
+    // 每个任务写入独立的目录，取回结果时只包含本任务的输出，排队的任务之间也不会互相覆盖
+    let mut config = config;
+    config.output_path = Path::new(&config.output_path)
+        .join("jobs")
+        .join(format!("{}_{}", state.session, id))
+        .to_string_lossy()
+        .into_owned();
+    let job = Job {
+        config,
+        update_existing: body["update_existing"].as_bool().unwrap_or(false),
+        state: JobState::Queued,
+        token: CancellationToken::new(),
+        logs: VecDeque::new(),
+        next_log: 0,
+        files: BTreeMap::new(),
+        batches: BTreeMap::new(),
+        summary: None,
+    };
+    let status = job.status_json(id);
+    state.jobs.insert(id, job);
+    let _ = queue.send(id);
+    Response::json(201, status)
+}
+
+/// 输出目录中的文件，拒绝跳出输出目录的路径 (包括经符号链接跳出)
+fn output_file(output_root: &str, relative: &str) -> Option<PathBuf> {
+    let relative = Path::new(relative);
+    let safe = relative.components().all(|c| matches!(c, Component::Normal(_)));
+    if !safe || relative.components().next().is_none() {
+        return None;
+    }
+    let root = Path::new(output_root).canonicalize().ok()?;
+    let path = root.join(relative).canonicalize().ok()?;
+    path.starts_with(&root).then_some(path)
+}
+
+fn job_result(job: &Job, request: &Request) -> Response {
+    if let Some(file) = request.query("file") {
+        return match output_file(&job.config.output_path, file).map(std::fs::read) {
+            Some(Ok(data)) => Response { status: 200, content_type: "application/octet-stream", body: data },
+            Some(Err(_)) | None => Response::error(404, "文件不存在"),
+        };
+    }
+    let root = Path::new(&job.config.output_path);
+    let files: Vec<String> = WalkDir::new(root)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.path().strip_prefix(root).ok().map(|p| p.to_string_lossy().replace('\\', "/")))
+        .collect();
+    Response::json(200, json!({"output_path": job.config.output_path, "files": files}))
+}
+
+fn handle(state: &Shared, queue: &UnboundedSender<u64>, base: &AppConfig, request: Request) -> Response {
+    let segments: Vec<&str> = request.path.split('/').filter(|s| !s.is_empty()).collect();
+    let method = request.method.as_str();
+    if segments.as_slice() == ["jobs"] {
+        return match method {
+            "POST" => submit_job(state, queue, base, &request.body),
+            "GET" => {
+                let state = state.lock().unwrap();
+                let jobs: Vec<Value> = state.jobs.iter().map(|(id, job)| job.status_json(*id)).collect();
+                Response::json(200, json!({"jobs": jobs}))
+            }
+            _ => Response::error(405, "不支持的方法"),
+        };
+    }
+
+    let ["jobs", id, rest @ ..] = segments.as_slice() else {
+        return Response::error(404, "未知的接口");
+    };
+    let Ok(id) = id.parse::<u64>() else {
+        return Response::error(404, "任务不存在");
+    };
+    let mut state = state.lock().unwrap();
+    let Some(job) = state.jobs.get_mut(&id) else {
+        return Response::error(404, "任务不存在");
+    };
+    match (method, rest) {
+        ("GET", []) => Response::json(200, job.status_json(id)),
+        ("GET", ["logs"]) => {
+            let since: usize = request.query("since").and_then(|s| s.parse().ok()).unwrap_or(0);
+            let logs: Vec<Value> = job
+                .logs
+                .iter()
+                .filter(|(seq, _)| *seq >= since)
+                .map(|(seq, entry)| {
+                    json!({"seq": seq, "time": entry.time, "level": format!("{:?}", entry.level), "message": entry.message})
+                })
+                .collect();
+            Response::json(200, json!({"next": job.next_log, "logs": logs}))
+        }
+        ("POST", ["cancel"]) => {
+            if matches!(job.state, JobState::Finished(_)) {
+                return Response::error(409, "任务已结束");
+            }
+            job.token.cancel();
+            // 尚未开始的任务直接标记为已取消，执行时会被跳过
+            if job.state == JobState::Queued {
+                job.state = JobState::Finished(RunStatus::Cancelled);
+            }
+            Response::json(200, job.status_json(id))
+        }
+        ("GET", ["result"]) => match job.state {
+            JobState::Finished(_) => job_result(job, &request),
+            _ => Response::error(409, "任务尚未结束"),
+        },
+        _ => Response::error(404, "未知的接口"),
+    }
+}
+
+/// 服务自身的来源：浏览器页面只有来自这些地址时才能调用接口
+fn own_origins(addr: SocketAddr) -> Vec<String> {
+    let mut origins = vec![format!("http://{}", addr)];
+    if addr.ip().is_loopback() {
+        origins.push(format!("http://localhost:{}", addr.port()));
+    }
+    origins
+}
+
+/// 请求是否可以处理：来源、令牌与请求体类型，不通过时返回错误响应
+fn check_request(request: &Request, token: &str, origins: &[String]) -> Option<Response> {
+    if let Some(origin) = &request.origin
+        && !origins.iter().any(|o| o.eq_ignore_ascii_case(origin))
+    {
+        return Some(Response::error(403, "不接受来自其他网页的请求"));
+    }
+    if request.authorization.as_deref() != Some(format!("Bearer {}", token).as_str()) {
+        return Some(Response::error(401, "缺少或错误的访问令牌"));
+    }
+    let is_json = request
+        .content_type
+        .as_deref()
+        .and_then(|t| t.split(';').next())
+        .is_some_and(|t| t.trim().eq_ignore_ascii_case("application/json"));
+    if request.method == "POST" && !is_json {
+        return Some(Response::error(415, "请求体须为 application/json"));
+    }
+    None
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    state: Shared,
+    queue: UnboundedSender<u64>,
+    base: Arc<AppConfig>,
+    token: Arc<String>,
+    origins: Arc<Vec<String>>,
+) {
+    let response = match tokio::time::timeout(READ_TIMEOUT, read_request(&mut stream)).await {
+        Ok(Ok(request)) => match check_request(&request, &token, &origins) {
+            Some(rejected) => rejected,
+            None => handle(&state, &queue, &base, request),
+        },
+        Ok(Err(e)) => Response::error(400, e.to_string()),
+        Err(_) => Response::error(408, "读取请求超时"),
+    };
+    if let Err(e) = write_response(&mut stream, response).await {
+        log_warn!("写入响应失败: {}", e);
+    }
+}
+
+/// 随机访问令牌：标准库的 RandomState 每个进程使用系统随机数作为种子
+fn generate_token() -> String {
+    let mut hasher = Sha1::new();
+    for i in 0..4u64 {
+        hasher.update(RandomState::new().hash_one(i).to_le_bytes());
+    }
+    hasher.update(format!("{:?}{}", std::time::SystemTime::now(), std::process::id()));
+    format!("{:x}", hasher.finalize())
+}
+
+/// 生成的令牌写入数据目录，仅当前用户可读 (Windows 下数据目录本身位于用户目录中)
+fn write_token_file(token: &str) -> std::io::Result<PathBuf> {
+    use std::io::Write;
+
+    let dir = data_dir();
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(TOKEN_FILE);
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        // 文件已存在时 mode 不生效，单独收紧权限
+        if path.exists() {
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+        }
+    }
+    options.open(&path)?.write_all(token.as_bytes())?;
+    Ok(path)
+}
+
+/// 发布版在 Windows 上没有控制台：从命令行启动时附加到其控制台，否则新建一个，
+/// 以便看到启动信息与任务日志
+#[cfg(windows)]
+fn attach_console() {
+    use windows_sys::Win32::System::Console::{ATTACH_PARENT_PROCESS, AllocConsole, AttachConsole};
+
+    // SAFETY: 两个函数均无指针参数；已有控制台时调用失败，没有副作用
+    unsafe {
+        if AttachConsole(ATTACH_PARENT_PROCESS) == 0 {
+            AllocConsole();
+        }
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
+
+/// 启动控制服务并一直运行，直到进程退出
+pub fn serve(addr: &str) -> Result<()> {
+    attach_console();
+    let _ = GLOBAL_SENDER.set(Arc::new(ConsoleSink));
+    let addr: SocketAddr = addr.parse().map_err(|e| anyhow!("无效的监听地址 {}: {}", addr, e))?;
+    let token = match std::env::var(TOKEN_ENV).ok().filter(|t| !t.is_empty()) {
+        Some(token) => token,
+        None => {
+            let token = generate_token();
+            let path = write_token_file(&token).map_err(|e| anyhow!("无法写入访问令牌文件: {}", e))?;
+            println!("未设置 {}，本次生成的访问令牌已写入 {}", TOKEN_ENV, path.display());
+            token
+        }
+    };
+    let token = Arc::new(token);
+    let origins = Arc::new(own_origins(addr));
+
+    let state: Shared = Arc::new(Mutex::new(ServerState {
+        session: chrono::Local::now().format("%Y%m%d_%H%M%S").to_string(),
+        ..ServerState::default()
+    }));
+
+    let base = Arc::new(AppConfig::load());
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(async move {
+        let listener = TcpListener::bind(addr).await?;
+        println!("控制服务已启动: http://{}", addr);
+        let (queue, jobs) = unbounded_channel();
+        tokio::spawn(run_worker(state.clone(), jobs));
+        loop {
+            // 连接数耗尽 (EMFILE) 等错误是暂时的，记录后稍等再继续，不能让服务和进行中的任务随之退出
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    log_warn!("接受连接失败: {}", e);
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    continue;
+                }
+            };
+            tokio::spawn(handle_connection(stream, state.clone(), queue.clone(), base.clone(), token.clone(), origins.clone()));
+        }
+    })
+}
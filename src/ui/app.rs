@@ -1,91 +1,606 @@
 use super::fonts::setup_custom_fonts;
-use crate::config::AppConfig;
+use super::i18n::{UiLanguage, set_language, tr, trf};
+use super::notify::notify_run_finished;
+use crate::config::{AppConfig, ColorCodeMode, EmptyValueMode, FailedEntryMode, FilenameCasing, OriginsMode, OutputLayout, OutputOrder, OutputTarget, PunctuationMode, QuestMode, QueuedJob, UiTheme};
+use crate::log_info;
 use crate::logging::{LogEntry, LogLevel};
+use crate::logic::error::describe;
 use crate::logic::openai::OpenAIClient;
-use crate::logic::processor;
+use crate::logic::benchmark::{self, CompareResult};
+use crate::logic::common::SkipReason;
+use crate::logic::formats::has_extension;
+use crate::logic::processor::{self, FileState, RunStatus, RunSummary};
+use crate::logic::prompt_lint::{self, PromptWarning};
+use crate::logic::request_template;
+use crate::logic::context_window;
+use crate::logic::concurrency::ConcurrencySnapshot;
+use crate::logic::diff::{self, DiffEntry, DiffReport};
+use crate::logic::merge::{self, MergePlan, MergePolicy};
+use crate::logic::pack_manifest::{self, ModUpdate};
+use crate::logic::qa::QaIssue;
+use crate::logic::run_history::{self, RunRecord};
+use crate::logic::install;
+use crate::logic::preflight::{self, OutputIssue};
+use crate::logic::instances::{self, Instance};
+use crate::logic::raw_content::{self, SnapshotFile};
+use crate::logic::validate;
+use crate::logic::frequency::{self, StringStats};
+use crate::logic::glossary::{self, GlossarySuggestion};
+use crate::logic::update::{self, ReleaseInfo};
 use crate::message::{AppMsg, GLOBAL_SENDER};
-use crossbeam_channel::{Receiver, Sender};
+use super::sender::UiSender;
+use crossbeam_channel::Receiver;
 use eframe::egui;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::thread;
+use std::time::{Duration, Instant};
+use tokio::runtime::Runtime;
+use tokio::task::JoinSet;
 use tokio_util::sync::CancellationToken;
 
+/// 队列任务在界面上的状态
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum JobState {
+    Pending,
+    Running,
+    Finished(RunStatus),
+}
+
+impl JobState {
+    fn label(&self) -> &'static str {
+        match self {
+            JobState::Pending => tr("⏸ 等待"),
+            JobState::Running => tr("⏳ 运行中"),
+            JobState::Finished(RunStatus::Completed) => tr("✅ 完成"),
+            JobState::Finished(RunStatus::Cancelled) => tr("⏹ 已取消"),
+            JobState::Finished(RunStatus::Failed) => tr("❌ 失败"),
+        }
+    }
+}
+
+/// 进度页文件列表中的一行
+struct FileRow {
+    path: PathBuf,
+    output: String, // 所属任务的输出目录
+    state: FileState,
+}
+
+/// 主界面标签页
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MainTab {
+    Settings,
+    Files,
+    Progress,
+    Log,
+    Review,
+    Stats,
+}
+
+impl MainTab {
+    const ALL: [MainTab; 6] = [
+        MainTab::Settings,
+        MainTab::Files,
+        MainTab::Progress,
+        MainTab::Log,
+        MainTab::Review,
+        MainTab::Stats,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            MainTab::Settings => tr("⚙ 设置"),
+            MainTab::Files => tr("📁 文件"),
+            MainTab::Progress => tr("📊 进度"),
+            MainTab::Log => tr("📜 日志"),
+            MainTab::Review => tr("🔍 审校"),
+            MainTab::Stats => tr("📈 统计"),
+        }
+    }
+}
+
+fn theme_preference(theme: UiTheme) -> egui::ThemePreference {
+    match theme {
+        UiTheme::System => egui::ThemePreference::System,
+        UiTheme::Dark => egui::ThemePreference::Dark,
+        UiTheme::Light => egui::ThemePreference::Light,
+    }
+}
+
+fn theme_name(theme: UiTheme) -> &'static str {
+    match theme {
+        UiTheme::System => tr("跟随系统"),
+        UiTheme::Dark => tr("深色"),
+        UiTheme::Light => tr("浅色"),
+    }
+}
+
+fn format_log_line(entry: &LogEntry) -> String {
+    let prefix = match entry.level {
+        LogLevel::Info => "INFO",
+        LogLevel::Success => "DONE",
+        LogLevel::Warn => "WARN",
+        LogLevel::Error => "ERR ",
+    };
+    format!("{} [{}] {}", entry.time, prefix, entry.message)
+}
+
+fn join_log_lines(logs: &VecDeque<LogEntry>, errors_only: bool) -> String {
+    logs.iter()
+        .filter(|e| !errors_only || matches!(e.level, LogLevel::Error))
+        .map(format_log_line)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// 条目过多时界面只显示前若干条，完整内容请导出报告
+const DIFF_DISPLAY_LIMIT: usize = 500;
+
+fn diff_section(ui: &mut egui::Ui, id: &str, title: String, entries: &[DiffEntry]) {
+    egui::CollapsingHeader::new(title).id_salt(id).show(ui, |ui| {
+        egui::Grid::new(id).num_columns(3).striped(true).show(ui, |ui| {
+            for e in entries.iter().take(DIFF_DISPLAY_LIMIT) {
+                ui.label(format!("{}\n{}", e.mod_id, e.key));
+                ui.add(egui::Label::new(&e.a).wrap());
+                ui.add(egui::Label::new(&e.b).wrap());
+                ui.end_row();
+            }
+        });
+        if entries.len() > DIFF_DISPLAY_LIMIT {
+            ui.label(egui::RichText::new(trf("仅显示前 {} 条，完整内容请导出报告", &[&DIFF_DISPLAY_LIMIT])).weak());
+        }
+    });
+}
+
+const CONFIRM_CANCEL_AFTER: Duration = Duration::from_secs(30);
+const AUTOSAVE_DELAY: Duration = Duration::from_secs(1);
+
+/// 可手动输入的模型选择框，下拉列表支持按名称过滤
+fn model_picker(
+    ui: &mut egui::Ui,
+    id: &str,
+    model: &mut String,
+    models: &[String],
+    filter: &mut String,
+) {
+    ui.add(
+        egui::TextEdit::singleline(model)
+            .desired_width(160.0)
+            .hint_text(tr("模型名称")),
+    )
+    .on_hover_text(tr("可直接输入列表中没有的模型"));
+
+    egui::ComboBox::from_id_salt(id)
+        .selected_text("")
+        .width(20.0)
+        .height(300.0)
+        .show_ui(ui, |ui| {
+            ui.add(egui::TextEdit::singleline(filter).hint_text(tr("过滤模型...")));
+            ui.separator();
+            let needle = filter.to_lowercase();
+            for m in models.iter().filter(|m| m.to_lowercase().contains(&needle)) {
+                ui.selectable_value(model, m.clone(), m);
+            }
+        });
+}
+
+/// 输出目录检查有警告、等待用户确认的任务
+struct PendingRun {
+    jobs: Vec<(Option<usize>, AppConfig)>,
+    is_update: bool,
+    use_queue: bool,
+    warnings: Vec<String>,
+}
+
 pub struct MyApp {
     config: AppConfig,
     is_processing: bool,
+    is_stopping: bool,
+    is_preflighting: bool, // 启动前正在后台扫描输入并检查输出目录
+    run_started: Option<Instant>,
+    show_cancel_confirm: bool,
+    pending_run: Option<PendingRun>,
+    show_queue_window: bool,
+    job_states: Vec<JobState>,
     available_models: Vec<String>,
-    logs: Vec<LogEntry>, // <-- 改用 Vec 存储结构化日志
+    logs: VecDeque<LogEntry>, // 结构化日志，超出上限时丢弃最早的条目
     msg_receiver: Receiver<AppMsg>,
-    msg_sender: Sender<AppMsg>,
+    msg_sender: UiSender,
     cancellation_token: Option<CancellationToken>,
+    runtime: Runtime, // 所有网络与翻译任务共用的多线程运行时
     show_prompt_editor: bool,
+    show_template_editor: bool,
+    qa_issues: Vec<QaIssue>,
+    run_summaries: Vec<RunSummary>, // 本次运行各输入的统计，队列模式下每个任务一条
+    run_history: Vec<RunRecord>, // 本地保存的历次运行统计
+    instances: Option<Vec<Instance>>, // 首次展开实例列表时查找
+    file_rows: Vec<FileRow>,
+    file_index: HashMap<PathBuf, usize>, // 路径 -> file_rows 下标，状态更新频繁，避免线性查找
+    tab: MainTab,
+    show_compare_window: bool,
+    is_comparing: bool,
+    compare_result: Option<CompareResult>,
+    last_latency_ms: Option<u128>,
+    model_filter: String,
+    active_batches: BTreeMap<String, (usize, usize)>, // 批次 -> (已接收, 总数)
+    concurrency: ConcurrencySnapshot,
+    saved_config: AppConfig, // 最近一次写入磁盘的配置
+    pending_config: Option<(AppConfig, Instant)>, // 尚未保存的修改及其最后变动时间
+    window_size: Option<[f32; 2]>,
+    window_pos: Option<[f32; 2]>,
+    available_update: Option<ReleaseInfo>,
+    show_merge_window: bool,
+    merge_sources: Vec<String>,
+    merge_output: String,
+    merge_policy: MergePolicy,
+    merge_plan: Option<MergePlan>, // 等待人工处理冲突的合并结果
+    is_merging: bool,
+    show_diff_window: bool,
+    diff_a: String,
+    diff_b: String,
+    diff_b_is_source: bool, // B 为源模组时按源语言读取，只对比键
+    diff_report: Option<DiffReport>,
+    is_diffing: bool,
+    show_analysis_window: bool,
+    show_raw_content_window: bool,
+    raw_snapshots: Vec<SnapshotFile>, // 输出目录 raw_content/ 中的原文快照
+    is_analyzing: bool,
+    show_mod_updates_window: bool,
+    is_checking_mod_updates: bool,
+    mod_updates: Option<Vec<ModUpdate>>, // 与 pack_manifest.json 相比版本或内容变化的模组
+    string_stats: Option<StringStats>,
+    glossary_drafts: BTreeMap<String, String>, // 高频原文 -> 用户填写的译名
+    glossary_suggestions: Vec<GlossarySuggestion>, // 任务完成后挖掘出的术语，等待确认
+    fallback_langs_input: String, // 备选源语言输入框，逗号分隔
+    limit_whitelist_input: String,
+    community_packs_input: String, // 社区汉化包路径，逗号分隔
 }
 
 impl MyApp {
-    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(cc: &eframe::CreationContext<'_>, config: AppConfig) -> Self {
         setup_custom_fonts(&cc.egui_ctx);
-        cc.egui_ctx.set_zoom_factor(1.1);
+        cc.egui_ctx.set_zoom_factor(config.ui_zoom);
+        cc.egui_ctx.set_theme(theme_preference(config.theme));
+        set_language(config.ui_language);
         let (sender, receiver) = crossbeam_channel::unbounded();
+        let sender = UiSender::new(sender, cc.egui_ctx.clone());
 
-        let _ = GLOBAL_SENDER.set(sender.clone());
+        let _ = GLOBAL_SENDER.set(Arc::new(sender.clone()));
+        let available_models = if config.cached_models.is_empty() {
+            vec!["gpt-3.5-turbo".to_string(), "gpt-4o".to_string()]
+        } else {
+            config.cached_models.clone()
+        };
 
-        Self {
-            config: AppConfig::load(), // 加载保存的配置
-            logs: Vec::new(),
+        let saved_config = config.clone();
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .thread_name("mc-translator-worker")
+            .build()
+            .expect("无法创建 Tokio 运行时");
+        let fallback_langs_input = config.source_lang_fallbacks.join(", ");
+        let limit_whitelist_input = config.limit_whitelist.join(", ");
+        let community_packs_input = config.community_packs.join(", ");
+
+        let app = Self {
+            config,
+            logs: VecDeque::new(),
             is_processing: false,
-            available_models: vec!["gpt-3.5-turbo".to_string(), "gpt-4o".to_string()],
+            is_preflighting: false,
+            is_stopping: false,
+            run_started: None,
+            show_cancel_confirm: false,
+            pending_run: None,
+            show_queue_window: false,
+            job_states: Vec::new(),
+            available_models,
             msg_receiver: receiver,
             msg_sender: sender,
             cancellation_token: None,
+            runtime,
             show_prompt_editor: false,
+            show_template_editor: false,
+            qa_issues: Vec::new(),
+            run_summaries: Vec::new(),
+            run_history: run_history::load(),
+            instances: None,
+            file_rows: Vec::new(),
+            file_index: HashMap::new(),
+            tab: MainTab::Settings,
+            show_compare_window: false,
+            is_comparing: false,
+            compare_result: None,
+            last_latency_ms: None,
+            model_filter: String::new(),
+            active_batches: BTreeMap::new(),
+            concurrency: ConcurrencySnapshot::default(),
+            saved_config,
+            pending_config: None,
+            window_size: None,
+            window_pos: None,
+            available_update: None,
+            show_merge_window: false,
+            merge_sources: Vec::new(),
+            merge_output: String::new(),
+            merge_policy: MergePolicy::PreferExisting,
+            merge_plan: None,
+            is_merging: false,
+            show_diff_window: false,
+            diff_a: String::new(),
+            diff_b: String::new(),
+            diff_b_is_source: false,
+            diff_report: None,
+            is_diffing: false,
+            show_analysis_window: false,
+            show_raw_content_window: false,
+            raw_snapshots: Vec::new(),
+            is_analyzing: false,
+            show_mod_updates_window: false,
+            is_checking_mod_updates: false,
+            mod_updates: None,
+            string_stats: None,
+            glossary_drafts: BTreeMap::new(),
+            glossary_suggestions: Vec::new(),
+            fallback_langs_input,
+            limit_whitelist_input,
+            community_packs_input,
+        };
+        if app.config.check_update {
+            app.check_for_update();
+        }
+        app
+    }
+
+    fn check_for_update(&self) {
+        let sender = self.msg_sender.clone();
+        self.runtime.spawn(async move {
+            match update::check_latest_release().await {
+                Ok(Some(release)) => {
+                    let _ = sender.send(AppMsg::UpdateAvailable(release));
+                }
+                Ok(None) => {}
+                // 检查更新失败不影响使用，只写入 env_logger
+                Err(e) => log::warn!("检查更新失败: {}", e),
+            }
+        });
+    }
+
+    fn save_config(&mut self) {
+        self.config.save();
+        crate::crash::set_config_snapshot(&self.config);
+        self.saved_config = self.config.clone();
+        self.pending_config = None;
+    }
+
+    fn is_config_dirty(&self) -> bool {
+        self.config != self.saved_config
+    }
+
+    // 配置停止变动一段时间后再写盘，避免输入时每个字符都保存一次
+    fn autosave_config(&mut self, ctx: &egui::Context) {
+        if !self.is_config_dirty() {
+            self.pending_config = None;
+            return;
+        }
+        match &self.pending_config {
+            Some((snapshot, since)) if *snapshot == self.config => {
+                let elapsed = since.elapsed();
+                if elapsed >= AUTOSAVE_DELAY {
+                    self.save_config();
+                } else {
+                    ctx.request_repaint_after(AUTOSAVE_DELAY - elapsed);
+                }
+            }
+            _ => {
+                self.pending_config = Some((self.config.clone(), Instant::now()));
+                ctx.request_repaint_after(AUTOSAVE_DELAY);
+            }
         }
     }
 
+    // 伪翻译模式不需要 API Key
+    fn missing_api_key(&self) -> bool {
+        self.config.api_key.is_empty() && !self.config.mock_provider
+    }
+
+    /// 仅复用模式不调用 API，开始任务时不要求 API Key
+    fn run_missing_api_key(&self) -> bool {
+        self.missing_api_key() && !self.config.reuse_only
+    }
+
     fn check_connection_and_fetch_models(&self) {
         let config = self.config.clone();
+        let model = config.model.clone();
         let sender = self.msg_sender.clone();
 
         let _ = sender.send(AppMsg::Log(LogEntry::new(
             LogLevel::Info,
-            "正在连接 API 获取模型列表...",
+            tr("正在连接 API 获取模型列表..."),
         )));
 
-        thread::spawn(move || {
-            let rt = tokio::runtime::Builder::new_current_thread()
-                .enable_all()
-                .build()
-                .unwrap();
-            rt.block_on(async {
-                let client = OpenAIClient::new(config);
-                let token = CancellationToken::new();
-                match client.fetch_models(&token).await {
-                    Ok(models) => {
-                        let _ = sender.send(AppMsg::Log(LogEntry::new(
-                            LogLevel::Success,
-                            format!("✅ 连接成功！获取到 {} 个模型", models.len()),
-                        )));
-                        let _ = sender.send(AppMsg::ModelsFetched(models));
-                    }
-                    Err(e) => {
+        self.runtime.spawn(async move {
+            let client = OpenAIClient::new(config);
+            let token = CancellationToken::new();
+            match client.fetch_models(&token).await {
+                Ok(models) => {
+                    let _ = sender.send(AppMsg::Log(LogEntry::new(
+                        LogLevel::Success,
+                        trf("✅ 连接成功！获取到 {} 个模型", &[&models.len()]),
+                    )));
+                    let _ = sender.send(AppMsg::ModelsFetched(models));
+                }
+                Err(e) => {
+                    let _ = sender.send(AppMsg::Log(LogEntry::new(
+                        LogLevel::Error,
+                        trf("❌ 连接失败: {}", &[&describe(&e)]),
+                    )));
+                }
+            }
+
+            // 部分网关不实现 /models，无论上一步是否成功都实际测试一次当前模型
+            match client.health_check(&token).await {
+                Ok(report) => {
+                    let latency_ms = report.latency.as_millis();
+                    let _ = sender.send(AppMsg::Log(LogEntry::new(
+                        LogLevel::Success,
+                        trf("✅ 模型 {} 测试请求成功，往返延迟 {} ms", &[&model, &latency_ms]),
+                    )));
+                    if !report.rate_limits.is_empty() {
+                        let limits: Vec<String> = report
+                            .rate_limits
+                            .iter()
+                            .map(|(k, v)| format!("{}={}", k, v))
+                            .collect();
                         let _ = sender.send(AppMsg::Log(LogEntry::new(
-                            LogLevel::Error,
-                            format!("❌ 连接失败: {}", e),
+                            LogLevel::Info,
+                            trf("限流信息: {}", &[&limits.join(", ")]),
                         )));
                     }
+                    let _ = sender.send(AppMsg::HealthChecked(Some(latency_ms)));
                 }
-            });
+                Err(e) => {
+                    let _ = sender.send(AppMsg::Log(LogEntry::new(
+                        LogLevel::Error,
+                        trf("❌ 模型 {} 测试请求失败: {}", &[&model, &describe(&e)]),
+                    )));
+                    let _ = sender.send(AppMsg::HealthChecked(None));
+                }
+            }
         });
     }
 
-    fn start_processing(&mut self, is_update: bool) {
-        if self.is_processing {
+    fn start_comparison(&mut self) {
+        if self.is_comparing {
             return;
         }
+        self.is_comparing = true;
+        self.compare_result = None;
 
-        self.is_processing = true;
+        let config = self.config.clone();
+        let sender = self.msg_sender.clone();
+        let sample_size = self.config.compare_sample_size;
+        let model_a = self.config.model.clone();
+        let model_b = self.config.compare_model.clone();
+
+        let _ = sender.send(AppMsg::Log(LogEntry::new(
+            LogLevel::Info,
+            trf("正在抽取 {} 条样本对比 {} 与 {}...", &[&sample_size, &model_a, &model_b]),
+        )));
+
+        self.runtime.spawn(async move {
+            let token = CancellationToken::new();
+            match benchmark::run_model_comparison(config, sample_size, model_a, model_b, token).await {
+                Ok(result) => {
+                    let _ = sender.send(AppMsg::Log(LogEntry::new(
+                        LogLevel::Success,
+                        trf("✅ 模型对比完成，共 {} 条样本", &[&result.rows.len()]),
+                    )));
+                    let _ = sender.send(AppMsg::CompareFinished(Some(result)));
+                }
+                Err(e) => {
+                    let _ = sender.send(AppMsg::Log(LogEntry::new(
+                        LogLevel::Error,
+                        trf("❌ 模型对比失败: {}", &[&e]),
+                    )));
+                    let _ = sender.send(AppMsg::CompareFinished(None));
+                }
+            }
+        });
+    }
+
+    fn start_processing(&mut self, is_update: bool, use_queue: bool) {
+        // 上一次任务的所有异步任务结束前不允许再次启动，否则两次运行的日志会交错
+        if self.is_processing || self.is_stopping || self.is_preflighting {
+            self.logs.push_back(LogEntry::new(LogLevel::Warn, tr("上一个任务仍在运行或停止中，请稍候")));
+            return;
+        }
+
+        // 队列任务复用当前设置，仅替换输入和输出路径
+        let jobs: Vec<(Option<usize>, AppConfig)> = if use_queue {
+            if self.config.job_queue.is_empty() {
+                self.logs.push_back(LogEntry::new(LogLevel::Error, tr("任务队列为空")));
+                return;
+            }
+            self.config
+                .job_queue
+                .iter()
+                .enumerate()
+                .map(|(i, job)| {
+                    let mut config = self.config.clone();
+                    config.input_path = job.input_path.clone();
+                    config.output_path = job.output_path.clone();
+                    (Some(i), config)
+                })
+                .collect()
+        } else {
+            vec![(None, self.config.clone())]
+        };
+
+        // 调用 API 前检查输出目录，不可写时直接拒绝，空间可能不足时请用户确认。
+        // 扫描大型整合包可能较慢，放到后台执行，结果通过 PreflightChecked 返回
+        self.is_preflighting = true;
+        let sender = self.msg_sender.clone();
+        self.runtime.spawn_blocking(move || {
+            let mut outcome = Ok(Vec::new());
+            for (_, config) in &jobs {
+                let inputs = processor::scan_input_files(config);
+                match preflight::check_output(&config.output_path, &inputs, config.patch_jars) {
+                    Some(OutputIssue::NotWritable(detail)) => {
+                        outcome = Err(detail);
+                        break;
+                    }
+                    Some(OutputIssue::LowSpace { available, needed }) => {
+                        if let Ok(warnings) = &mut outcome {
+                            warnings.push(trf(
+                                "{}: 剩余 {} MB，预计需要 {} MB",
+                                &[&config.output_path, &(available / (1024 * 1024)), &needed.div_ceil(1024 * 1024)],
+                            ));
+                        }
+                    }
+                    None => {}
+                }
+            }
+            let _ = sender.send(AppMsg::PreflightChecked { jobs, is_update, use_queue, outcome });
+        });
+    }
+
+    fn begin_run(&mut self, jobs: Vec<(Option<usize>, AppConfig)>, is_update: bool, use_queue: bool) {
+        self.job_states = if use_queue {
+            vec![JobState::Pending; jobs.len()]
+        } else {
+            Vec::new()
+        };
+        self.qa_issues.clear();
+        self.run_summaries.clear();
+        self.file_rows.clear();
+        self.file_index.clear();
         // 保存当前配置
-        self.config.save();
+        self.save_config();
+        self.launch_jobs(jobs, is_update, use_queue && self.config.queue_parallel);
+    }
 
-        let config = self.config.clone();
+    /// 重新处理失败的文件：以更新模式补翻缺失条目，并关闭跳过已存在，保留其余文件的状态
+    fn retry_file(&mut self, path: &Path, output: &str) {
+        if self.is_processing || self.is_stopping {
+            return;
+        }
+        let mut config = self.config.clone();
+        config.input_path = path.to_string_lossy().to_string();
+        config.output_path = output.to_string();
+        config.skip_existing = false;
+        log_info!("重试文件: {}", path.display());
+        self.launch_jobs(vec![(None, config)], true, false);
+    }
+
+    fn launch_jobs(&mut self, jobs: Vec<(Option<usize>, AppConfig)>, is_update: bool, parallel: bool) {
+        let notify = self.config.notify_on_finish;
+        let notify_sound = self.config.notify_sound;
+
+        self.is_processing = true;
+        self.run_started = Some(Instant::now());
+        self.active_batches.clear();
+        self.tab = MainTab::Progress;
 
         // 创建新的 CancellationToken
         let token = CancellationToken::new();
@@ -93,39 +608,242 @@ impl MyApp {
 
         let sender = self.msg_sender.clone();
         let completion_msg = if is_update {
-            "所有更新任务已完成"
+            tr("所有更新任务已完成")
         } else {
-            "所有翻译任务已完成"
+            tr("所有翻译任务已完成")
         };
 
-        thread::spawn(move || {
-            let rt = tokio::runtime::Builder::new_current_thread()
-                .enable_all()
-                .build()
-                .unwrap();
-
-            rt.block_on(async {
-                processor::run_processing_task(config, is_update, token).await;
-                let _ = sender.send(AppMsg::Log(LogEntry::new(LogLevel::Info, completion_msg)));
-            });
+        self.runtime.spawn(async move {
+            let overall = {
+                let mut tasks = JoinSet::new();
+                let mut statuses = Vec::new();
+                for (index, config) in jobs {
+                    if token.is_cancelled() {
+                        break;
+                    }
+                    let token = token.clone();
+                    let sender = sender.clone();
+                    let job = async move {
+                        if let Some(i) = index {
+                            log_info!("开始队列任务 #{}: {}", i + 1, config.input_path);
+                            let _ = sender.send(AppMsg::JobStarted(i));
+                        }
+                        let status = processor::run_processing_task(config, is_update, token).await;
+                        if let Some(i) = index {
+                            let _ = sender.send(AppMsg::JobFinished(i, status));
+                        }
+                        status
+                    };
+                    if parallel {
+                        tasks.spawn(job);
+                    } else {
+                        statuses.push(job.await);
+                    }
+                }
+                while let Some(res) = tasks.join_next().await {
+                    statuses.push(res.unwrap_or(RunStatus::Failed));
+                }
+
+                if !token.is_cancelled() {
+                    let _ = sender.send(AppMsg::Log(LogEntry::new(LogLevel::Info, completion_msg)));
+                }
+
+                // 任一任务失败即视为失败，其次为取消
+                if statuses.contains(&RunStatus::Failed) {
+                    RunStatus::Failed
+                } else if token.is_cancelled() || statuses.contains(&RunStatus::Cancelled) {
+                    RunStatus::Cancelled
+                } else {
+                    RunStatus::Completed
+                }
+            };
+            // 此时所有任务均已结束；系统通知可能阻塞，放到阻塞线程池执行
+            if notify {
+                let _ = tokio::task::spawn_blocking(move || notify_run_finished(overall, notify_sound)).await;
+            }
+            let _ = sender.send(AppMsg::TaskFinished(overall));
         });
     }
 
+    fn render_queue_window(&mut self, ctx: &egui::Context) {
+        let mut is_open = self.show_queue_window;
+
+        egui::Window::new(tr("📋 任务队列"))
+            .open(&mut is_open)
+            .collapsible(false)
+            .default_width(650.0)
+            .show(ctx, |ui| {
+                ui.label(tr("每个任务使用独立的输入和输出路径，其余设置与主界面相同。"));
+                ui.horizontal(|ui| {
+                    let can_edit = !self.is_processing;
+                    if ui
+                        .add_enabled(can_edit, egui::Button::new(tr("➕ 添加当前输入/输出路径")))
+                        .clicked()
+                    {
+                        let job = QueuedJob {
+                            input_path: self.config.input_path.clone(),
+                            output_path: self.config.output_path.clone(),
+                        };
+                        if job.input_path.trim().is_empty() {
+                            self.logs.push_back(LogEntry::new(LogLevel::Error, tr("请先填写输入路径")));
+                        } else if !self.config.job_queue.contains(&job) {
+                            self.config.job_queue.push(job);
+                            self.job_states.clear();
+                        }
+                    }
+                    ui.add_enabled(can_edit, egui::Checkbox::new(&mut self.config.queue_parallel, tr("并行执行")));
+                    if ui.add_enabled(can_edit, egui::Button::new(tr("清空"))).clicked() {
+                        self.config.job_queue.clear();
+                        self.job_states.clear();
+                    }
+                });
+                ui.separator();
+
+                let mut remove = None;
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    egui::Grid::new("job_queue_grid")
+                        .num_columns(5)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("#");
+                            ui.strong(tr("输入路径"));
+                            ui.strong(tr("输出目录"));
+                            ui.strong(tr("状态"));
+                            ui.end_row();
+
+                            for (i, job) in self.config.job_queue.iter_mut().enumerate() {
+                                ui.label(format!("{}", i + 1));
+                                ui.add_enabled(
+                                    !self.is_processing,
+                                    egui::TextEdit::singleline(&mut job.input_path).desired_width(220.0),
+                                );
+                                ui.add_enabled(
+                                    !self.is_processing,
+                                    egui::TextEdit::singleline(&mut job.output_path).desired_width(180.0),
+                                );
+                                let state = self.job_states.get(i).copied().unwrap_or(JobState::Pending);
+                                ui.label(state.label());
+                                if ui.add_enabled(!self.is_processing, egui::Button::new("🗑")).clicked() {
+                                    remove = Some(i);
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+                if let Some(i) = remove {
+                    self.config.job_queue.remove(i);
+                    self.job_states.clear();
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let can_start = !self.is_processing && !self.is_preflighting && !self.config.job_queue.is_empty();
+                    if ui.add_enabled(can_start, egui::Button::new(tr("🚀 运行队列"))).clicked() {
+                        if self.run_missing_api_key() {
+                            self.logs.push_back(LogEntry::new(LogLevel::Error, tr("请先填写 API Key")));
+                        } else {
+                            self.start_processing(false, true);
+                        }
+                    }
+                    if ui.add_enabled(can_start, egui::Button::new(tr("🔄 更新队列翻译"))).clicked() {
+                        if self.run_missing_api_key() {
+                            self.logs.push_back(LogEntry::new(LogLevel::Error, tr("请先填写 API Key")));
+                        } else {
+                            self.start_processing(true, true);
+                        }
+                    }
+                });
+            });
+
+        self.show_queue_window = is_open;
+    }
+
+    // 只发出取消信号，等待 TaskFinished 后才恢复界面状态
     fn cancel_processing(&mut self) {
+        if self.is_stopping {
+            return;
+        }
         if let Some(token) = &self.cancellation_token {
             token.cancel();
+            self.is_stopping = true;
             self.logs
-                .push(LogEntry::new(LogLevel::Warn, "任务已被用户取消"));
+                .push_back(LogEntry::new(LogLevel::Warn, tr("正在停止，等待进行中的批次结束...")));
+        }
+    }
+
+    // 运行超过一段时间后取消需要二次确认，避免误触丢失进度
+    fn request_cancel(&mut self) {
+        let long_run = self
+            .run_started
+            .is_some_and(|t| t.elapsed() >= CONFIRM_CANCEL_AFTER);
+        if long_run {
+            self.show_cancel_confirm = true;
+        } else {
+            self.cancel_processing();
+        }
+    }
+
+    fn render_cancel_confirm(&mut self, ctx: &egui::Context) {
+        if !self.show_cancel_confirm {
+            return;
+        }
+        if !self.is_processing || self.is_stopping {
+            self.show_cancel_confirm = false;
+            return;
+        }
+
+        let elapsed = self.run_started.map(|t| t.elapsed().as_secs()).unwrap_or(0);
+        egui::Modal::new(egui::Id::new("cancel_confirm")).show(ctx, |ui| {
+            ui.heading(tr("确定要取消任务吗？"));
+            ui.label(trf("任务已运行 {} 分 {} 秒，进行中的文件不会被保存。", &[&(elapsed / 60), &(elapsed % 60)]));
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                if ui.button(tr("确定取消")).clicked() {
+                    self.show_cancel_confirm = false;
+                    self.cancel_processing();
+                }
+                if ui.button(tr("继续运行")).clicked() {
+                    self.show_cancel_confirm = false;
+                }
+            });
+        });
+    }
+
+    fn render_preflight_confirm(&mut self, ctx: &egui::Context) {
+        let Some(pending) = &self.pending_run else {
+            return;
+        };
+
+        let mut start = false;
+        let mut dismiss = false;
+        egui::Modal::new(egui::Id::new("preflight_confirm")).show(ctx, |ui| {
+            ui.heading(tr("输出目录空间可能不足"));
+            for warning in &pending.warnings {
+                ui.label(warning);
+            }
+            ui.label(tr("写出时空间不足会导致已翻译的内容丢失，建议先清理磁盘或更换输出目录。"));
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                if ui.button(tr("仍然开始")).clicked() {
+                    start = true;
+                }
+                if ui.button(tr("取消")).clicked() {
+                    dismiss = true;
+                }
+            });
+        });
+        if dismiss {
+            self.pending_run = None;
+        } else if start && let Some(pending) = self.pending_run.take() {
+            self.begin_run(pending.jobs, pending.is_update, pending.use_queue);
         }
-        self.is_processing = false;
-        self.cancellation_token = None;
     }
 
     fn render_prompt_editor(&mut self, ctx: &egui::Context) {
         let mut is_open = self.show_prompt_editor;
         let mut should_close = false;
 
-        egui::Window::new("📝 自定义系统提示词 (System Prompt)")
+        egui::Window::new(tr("📝 自定义系统提示词 (System Prompt)"))
             .open(&mut is_open) // 这里借用的是局部的 is_open
             .collapsible(false)
             .resizable(false)
@@ -134,7 +852,7 @@ impl MyApp {
             .auto_sized()
             .default_width(400.0)
             .show(ctx, |ui| {
-                ui.label("在此设置发送给 AI 的系统级指令，可用于控制翻译风格、保留特定术语等。");
+                ui.label(tr("在此设置发送给 AI 的系统级指令，可用于控制翻译风格、保留特定术语等。"));
                 ui.separator();
 
                 egui::ScrollArea::vertical()
@@ -142,22 +860,40 @@ impl MyApp {
                     .show(ui, |ui| {
                         ui.add(
                             egui::TextEdit::multiline(&mut self.config.prompt)
-                                .hint_text("请输入 System Prompt...")
+                                .hint_text(tr("请输入 System Prompt..."))
                                 .desired_width(f32::INFINITY)
                                 .desired_rows(8)
                                 .font(egui::TextStyle::Monospace),
                         );
                     });
 
+                ui.label(
+                    egui::RichText::new(trf(
+                        "约 {} tokens（每个批次都会发送）",
+                        &[&prompt_lint::estimate_tokens(&self.config.prompt)],
+                    ))
+                    .weak(),
+                );
+                for warning in prompt_lint::lint_prompt(&self.config.prompt) {
+                    let text = match warning {
+                        PromptWarning::MissingOrder => tr("⚠ 缺少保持顺序的要求，译文可能与原文错位").to_string(),
+                        PromptWarning::MissingLength => tr("⚠ 缺少保持数组长度的要求，批次可能因数量不符而失败").to_string(),
+                        PromptWarning::MissingFormatCodes => tr("⚠ 缺少保留格式代码的要求，§a、%s 等可能被翻译或删除").to_string(),
+                        PromptWarning::MissingJson => tr("⚠ 没有要求返回 JSON 数组，结果可能无法解析").to_string(),
+                        PromptWarning::UnknownPlaceholder(name) => trf("⚠ 未知占位符 {}，不会被替换", &[&name]),
+                    };
+                    ui.colored_label(ui.visuals().warn_fg_color, text);
+                }
+
                 ui.add_space(10.0);
                 ui.horizontal(|ui| {
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        if ui.button("保存并关闭").clicked() {
-                            self.config.save();
+                        if ui.button(tr("保存并关闭")).clicked() {
+                            self.save_config();
                             should_close = true;
                         }
                         ui.add_space(5.0);
-                        if ui.button("恢复默认").clicked() {
+                        if ui.button(tr("恢复默认")).clicked() {
                             self.config.prompt = AppConfig::default().prompt;
                         }
                     });
@@ -170,289 +906,2189 @@ impl MyApp {
 
         self.show_prompt_editor = is_open;
     }
-}
-
-impl eframe::App for MyApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        self.render_prompt_editor(ctx);
-        // 处理日志
-        while let Ok(msg) = self.msg_receiver.try_recv() {
-            match msg {
-                AppMsg::Log(entry) => {
-                    if self.logs.len() > 1000 {
-                        self.logs.remove(0);
-                    }
-                    if entry.message.contains("已完成") || entry.message.contains("任务终止")
-                    {
-                        self.is_processing = false;
-                        self.cancellation_token = None;
-                    }
-                    self.logs.push(entry);
-                }
-                AppMsg::ModelsFetched(models) => {
-                    self.available_models = models;
-                    // 如果当前配置的模型不在列表里，默认选中第一个
-                    if !self.available_models.contains(&self.config.model)
-                        && !self.available_models.is_empty()
-                    {
-                        self.config.model = self.available_models[0].clone();
-                    }
-                }
-            }
-        }
-
-        // 底部个人信息
-        egui::TopBottomPanel::bottom("footer_panel").show(ctx, |ui| {
-            ui.add_space(2.0);
-            ui.horizontal(|ui| {
-                ui.label(egui::RichText::new("v0.2.13").weak().size(10.0));
-                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    ui.hyperlink_to(
-                        egui::RichText::new("GitHub 主页").size(11.0),
-                        "https://github.com/chrysoljq/mc_translator",
-                    );
 
-                    ui.label(egui::RichText::new("|").weak().size(11.0));
+    fn render_template_editor(&mut self, ctx: &egui::Context) {
+        let mut is_open = self.show_template_editor;
+        let mut should_close = false;
 
-                    ui.hyperlink_to(
-                        egui::RichText::new("关于作者").size(11.0),
-                        "https://github.com/chrysoljq",
-                    );
+        egui::Window::new(tr("🧩 请求模板"))
+            .open(&mut is_open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .vscroll(true)
+            .auto_sized()
+            .default_width(400.0)
+            .show(ctx, |ui| {
+                ui.label(tr("用于请求格式与 OpenAI 略有不同的网关，如需额外字段 \"enable_thinking\": false。响应仍需为 OpenAI 兼容的流式格式。"));
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label(tr("接口路径:"));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.config.chat_path)
+                            .hint_text("/chat/completions")
+                            .desired_width(240.0),
+                    )
+                    .on_hover_text(tr("拼接在 BASE URL 之后"));
                 });
-            });
-            ui.add_space(2.0); // 底部留白
-        });
 
-        egui::CentralPanel::default().show(ctx, |ui| {
-            ui.heading("Minecraft Mod 汉化助手（支持任务、模组、资源包）");
-            ui.separator();
+                egui::ScrollArea::vertical()
+                    .max_height(220.0)
+                    .show(ui, |ui| {
+                        ui.add(
+                            egui::TextEdit::multiline(&mut self.config.request_template)
+                                .hint_text(tr("留空使用内置请求体"))
+                                .desired_width(f32::INFINITY)
+                                .desired_rows(10)
+                                .font(egui::TextStyle::Monospace),
+                        );
+                    });
 
-            egui::Grid::new("settings_grid")
-                .num_columns(3)
-                .spacing([10.0, 8.0])
-                .striped(true)
-                .show(ui, |ui| {
-                    ui.label("BASE URL:");
-                    
+                ui.label(
+                    egui::RichText::new(trf(
+                        "占位符: {}。整个字符串为占位符时替换为对应类型，如 \"{STREAM}\" 替换为 true/false",
+                        &[&request_template::PLACEHOLDERS.join(" ")],
+                    ))
+                    .weak(),
+                );
+                let template = self.config.request_template.trim();
+                if !template.is_empty() {
+                    match request_template::parse_template(template) {
+                        Ok(_) => {
+                            for name in request_template::missing_placeholders(template) {
+                                ui.colored_label(ui.visuals().warn_fg_color, trf("⚠ 模板中没有 {}", &[&name]));
+                            }
+                        }
+                        Err(e) => {
+                            ui.colored_label(ui.visuals().error_fg_color, trf("❌ 模板无效: {}", &[&e]));
+                        }
+                    }
+                }
+
+                ui.add_space(10.0);
+                ui.horizontal(|ui| {
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button(tr("保存并关闭")).clicked() {
+                            self.save_config();
+                            should_close = true;
+                        }
+                        ui.add_space(5.0);
+                        if ui.button(tr("恢复默认")).clicked() {
+                            self.config.chat_path = AppConfig::default().chat_path;
+                            self.config.request_template.clear();
+                        }
+                        if ui.button(tr("填入内置模板")).clicked() {
+                            self.config.request_template = request_template::DEFAULT_TEMPLATE.to_string();
+                        }
+                    });
+                });
+            });
 
+        if should_close {
+            is_open = false;
+        }
+
+        self.show_template_editor = is_open;
+    }
+
+    fn start_merge(&mut self) {
+        let sources = self.merge_sources.clone();
+        let target_lang = self.config.target_lang.clone();
+        let sender = self.msg_sender.clone();
+        self.is_merging = true;
+        self.merge_plan = None;
+
+        thread::spawn(move || match merge::plan_merge(&sources, &target_lang) {
+            Ok(plan) => {
+                let _ = sender.send(AppMsg::MergePlanned(Some(plan)));
+            }
+            Err(e) => {
+                let _ = sender.send(AppMsg::Log(LogEntry::new(LogLevel::Error, trf("❌ 读取合并来源失败: {}", &[&e]))));
+                let _ = sender.send(AppMsg::MergePlanned(None));
+            }
+        });
+    }
+
+    fn write_merge(&mut self, plan: &MergePlan) {
+        match plan.write(&self.merge_output, self.config.output_layout) {
+            Ok(count) => self.logs.push_back(LogEntry::new(
+                LogLevel::Success,
+                trf("✅ 合并完成，写入 {} 个文件，{} 处冲突，输出到 {}", &[&count, &plan.conflicts.len(), &self.merge_output]),
+            )),
+            Err(e) => self.logs.push_back(LogEntry::new(LogLevel::Error, trf("❌ 写入合并结果失败: {}", &[&e]))),
+        }
+    }
+
+    /// 以实例的游戏目录作为输入，扫描时会进入其中的 mods/、kubejs/ 与 config/
+    fn select_instance(&mut self, instance: &Instance) {
+        let game_dir = instance.game_dir.display().to_string();
+        self.config.input_path = game_dir.clone();
+        if self.config.game_dir.trim().is_empty() {
+            self.config.game_dir = game_dir;
+        }
+        let dirs = instance.scan_dirs();
+        let message = if dirs.is_empty() {
+            trf("已选择实例 {}，未找到 mods、kubejs 或 config 目录", &[&instance.name])
+        } else {
+            trf("已选择实例 {}，将扫描: {}", &[&instance.name, &dirs.join(", ")])
+        };
+        self.logs.push_back(LogEntry::new(LogLevel::Info, message));
+    }
+
+    fn install_to_game(&mut self) {
+        let result = install::install(
+            &self.config.output_path,
+            self.config.output_layout,
+            &self.config.target_lang,
+            Path::new(self.config.game_dir.trim()),
+            self.config.enable_pack_in_options,
+        );
+        match result {
+            Ok(report) => {
+                let pack = report.pack_path.display().to_string();
+                self.logs.push_back(LogEntry::new(
+                    LogLevel::Success,
+                    trf("✅ 已安装 {} 个文件到 {}", &[&report.files, &pack]),
+                ));
+                if report.enabled {
+                    self.logs.push_back(LogEntry::new(LogLevel::Info, tr("已在 options.txt 中启用资源包")));
+                }
+            }
+            Err(e) => self.logs.push_back(LogEntry::new(LogLevel::Error, trf("❌ 安装到游戏失败: {}", &[&e]))),
+        }
+    }
+
+    fn render_merge_window(&mut self, ctx: &egui::Context) {
+        let mut is_open = self.show_merge_window;
+
+        egui::Window::new(tr("🔀 合并译文"))
+            .open(&mut is_open)
+            .collapsible(false)
+            .default_width(650.0)
+            .show(ctx, |ui| {
+                ui.label(tr("按添加顺序合并多个译文目录或资源包 zip，先添加的视为现有译文。"));
+                ui.horizontal(|ui| {
+                    if ui.button(tr("📂 添加文件夹")).clicked()
+                        && let Some(path) = rfd::FileDialog::new().pick_folder()
+                    {
+                        self.merge_sources.push(path.display().to_string());
+                    }
+                    if ui.button(tr("📄 添加资源包")).clicked()
+                        && let Some(path) = rfd::FileDialog::new().add_filter("Resource Pack", &["zip"]).pick_file()
+                    {
+                        self.merge_sources.push(path.display().to_string());
+                    }
+                    if ui.button(tr("清空")).clicked() {
+                        self.merge_sources.clear();
+                        self.merge_plan = None;
+                    }
+                });
+
+                let mut remove = None;
+                for (i, source) in self.merge_sources.iter().enumerate() {
                     ui.horizontal(|ui| {
-                        ui.text_edit_singleline(&mut self.config.base_url);
-                        const LANGUAGES: &[(&str, &str)] = &[
-                            ("en_us", "English"),
-                            ("zh_cn", "Simplified Chinese"),
-                            ("zh_tw", "Traditional Chinese"),
-                            ("ja_jp", "Japanese"),
-                            ("ko_kr", "Korean"),
-                            ("ru_ru", "Russian"),
-                            ("fr_fr", "French"),
-                            ("es_es", "Spanish"),
-                            ("de_de", "German"),
-                            ("it_it", "Italian"),
-                            ("pt_br", "Brazil"),
-                        ];
-
-                        egui::ComboBox::from_id_salt("source_lang")
-                            .selected_text(&self.config.source_lang)
-                            .width(80.0)
-                            .show_ui(ui, |ui| {
-                                for (code, name) in LANGUAGES {
-                                    ui.selectable_value(
-                                        &mut self.config.source_lang,
-                                        code.to_string(),
-                                        format!("{} - {}", code, name),
-                                    );
+                        ui.label(format!("{}. {}", i + 1, source));
+                        if ui.small_button("🗑").clicked() {
+                            remove = Some(i);
+                        }
+                    });
+                }
+                if let Some(i) = remove {
+                    self.merge_sources.remove(i);
+                }
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    ui.label(tr("输出目录:"));
+                    ui.text_edit_singleline(&mut self.merge_output);
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr("冲突处理:"));
+                    ui.radio_value(&mut self.merge_policy, MergePolicy::PreferExisting, tr("保留现有"));
+                    ui.radio_value(&mut self.merge_policy, MergePolicy::PreferNew, tr("使用新的"));
+                    ui.radio_value(&mut self.merge_policy, MergePolicy::Ask, tr("逐条选择"));
+                });
+
+                let can_start = !self.is_merging && self.merge_sources.len() >= 2 && !self.merge_output.trim().is_empty();
+                if self.is_merging {
+                    ui.add_enabled(false, egui::Button::new(tr("⏳ 读取中...")));
+                } else if ui.add_enabled(can_start, egui::Button::new(tr("开始合并"))).clicked() {
+                    self.start_merge();
+                }
+
+                let mut write_plan = false;
+                if let Some(plan) = &mut self.merge_plan {
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        ui.label(trf("共 {} 处冲突，勾选的条目使用新的译文", &[&plan.conflicts.len()]));
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui.button(tr("写入")).clicked() {
+                                write_plan = true;
+                            }
+                            if ui.button(tr("全部使用新的")).clicked() {
+                                plan.apply_policy(MergePolicy::PreferNew);
+                            }
+                            if ui.button(tr("全部保留现有")).clicked() {
+                                plan.apply_policy(MergePolicy::PreferExisting);
+                            }
+                        });
+                    });
+                    egui::ScrollArea::both().max_height(300.0).show(ui, |ui| {
+                        egui::Grid::new("merge_conflicts_grid")
+                            .num_columns(4)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong(tr("使用新的"));
+                                ui.strong(tr("位置"));
+                                ui.strong(tr("现有译文"));
+                                ui.strong(tr("新的译文"));
+                                ui.end_row();
+
+                                for conflict in &mut plan.conflicts {
+                                    ui.checkbox(&mut conflict.use_incoming, "");
+                                    ui.label(format!("{}\n{}", conflict.file, conflict.key));
+                                    ui.add(egui::Label::new(conflict.existing.as_str().unwrap_or_default()).wrap());
+                                    ui.add(egui::Label::new(conflict.incoming.as_str().unwrap_or_default()).wrap());
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                }
+                if write_plan && let Some(plan) = self.merge_plan.take() {
+                    self.write_merge(&plan);
+                }
+            });
+
+        self.show_merge_window = is_open;
+    }
+
+    fn start_diff(&mut self) {
+        let (a, b) = (self.diff_a.clone(), self.diff_b.clone());
+        let lang_a = self.config.target_lang.clone();
+        let lang_b = if self.diff_b_is_source { self.config.source_lang.clone() } else { lang_a.clone() };
+        let sender = self.msg_sender.clone();
+        self.is_diffing = true;
+        self.diff_report = None;
+
+        thread::spawn(move || match diff::diff_sources(&a, &lang_a, &b, &lang_b) {
+            Ok(report) => {
+                let _ = sender.send(AppMsg::DiffFinished(Some(report)));
+            }
+            Err(e) => {
+                let _ = sender.send(AppMsg::Log(LogEntry::new(LogLevel::Error, trf("❌ 译文对比失败: {}", &[&e]))));
+                let _ = sender.send(AppMsg::DiffFinished(None));
+            }
+        });
+    }
+
+    fn render_diff_window(&mut self, ctx: &egui::Context) {
+        let mut is_open = self.show_diff_window;
+
+        egui::Window::new(tr("🆚 译文对比"))
+            .open(&mut is_open)
+            .collapsible(false)
+            .default_width(700.0)
+            .show(ctx, |ui| {
+                egui::Grid::new("diff_paths_grid").num_columns(2).show(ui, |ui| {
+                    for (label, path) in [("A:", &mut self.diff_a), ("B:", &mut self.diff_b)] {
+                        ui.label(label);
+                        ui.horizontal(|ui| {
+                            ui.text_edit_singleline(path);
+                            if ui.button("📂").on_hover_text(tr("📂 选择文件夹")).clicked()
+                                && let Some(p) = rfd::FileDialog::new().pick_folder()
+                            {
+                                *path = p.display().to_string();
+                            }
+                            if ui.button("📄").on_hover_text(tr("📄 打开文件")).clicked()
+                                && let Some(p) = rfd::FileDialog::new().add_filter("Pack", &["zip", "jar"]).pick_file()
+                            {
+                                *path = p.display().to_string();
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+                ui.checkbox(&mut self.diff_b_is_source, tr("B 为源模组（按源语言读取，只对比键）"));
+
+                let can_start = !self.is_diffing && !self.diff_a.trim().is_empty() && !self.diff_b.trim().is_empty();
+                ui.horizontal(|ui| {
+                    if self.is_diffing {
+                        ui.add_enabled(false, egui::Button::new(tr("⏳ 读取中...")));
+                    } else if ui.add_enabled(can_start, egui::Button::new(tr("开始对比"))).clicked() {
+                        self.start_diff();
+                    }
+                    if let Some(report) = &self.diff_report
+                        && ui.button(tr("💾 导出报告")).clicked()
+                        && let Some(path) = rfd::FileDialog::new()
+                            .add_filter("CSV", &["csv"])
+                            .set_file_name("diff_report.csv")
+                            .save_file()
+                    {
+                        match report.export_csv(&path) {
+                            Ok(()) => self.logs.push_back(LogEntry::new(
+                                LogLevel::Success,
+                                trf("✅ 对比报告已导出到 {}", &[&path.display()]),
+                            )),
+                            Err(e) => self.logs.push_back(LogEntry::new(LogLevel::Error, trf("❌ 导出失败: {}", &[&e]))),
+                        }
+                    }
+                });
+
+                let Some(report) = &self.diff_report else {
+                    return;
+                };
+                ui.separator();
+                egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    diff_section(ui, "diff_only_a", trf("仅在 A 中 ({})", &[&report.only_a.len()]), &report.only_a);
+                    diff_section(ui, "diff_only_b", trf("仅在 B 中 ({})", &[&report.only_b.len()]), &report.only_b);
+                    diff_section(ui, "diff_different", trf("译文不同 ({})", &[&report.different.len()]), &report.different);
+                });
+            });
+
+        self.show_diff_window = is_open;
+    }
+
+    fn start_analysis(&mut self) {
+        let config = self.config.clone();
+        let sender = self.msg_sender.clone();
+        self.is_analyzing = true;
+        thread::spawn(move || {
+            let stats = frequency::analyze_strings(&config);
+            let _ = sender.send(AppMsg::AnalysisFinished(stats));
+        });
+    }
+
+    fn start_mod_update_check(&mut self) {
+        let config = self.config.clone();
+        let sender = self.msg_sender.clone();
+        self.is_checking_mod_updates = true;
+        self.mod_updates = None;
+        thread::spawn(move || {
+            let jars: Vec<PathBuf> = processor::scan_input_files(&config)
+                .into_iter()
+                .filter(|path| has_extension(path, "jar"))
+                .collect();
+            let updates = pack_manifest::find_updates(Path::new(&config.output_path), &jars);
+            let _ = sender.send(AppMsg::ModUpdatesChecked(updates));
+        });
+    }
+
+    /// 只对变化的模组以更新模式重新处理，每个 JAR 一个任务，输出仍写入当前输出目录
+    fn update_changed_mods(&mut self) {
+        if self.is_processing || self.is_stopping {
+            return;
+        }
+        let Some(updates) = &self.mod_updates else {
+            return;
+        };
+        let mut jars: Vec<PathBuf> = updates.iter().map(|u| u.jar.clone()).collect();
+        jars.sort();
+        jars.dedup();
+        let jobs = jars
+            .iter()
+            .map(|jar| {
+                let mut config = self.config.clone();
+                config.input_path = jar.to_string_lossy().to_string();
+                config.skip_existing = false;
+                (None, config)
+            })
+            .collect();
+        log_info!("开始更新 {} 个模组 JAR 的翻译", jars.len());
+        self.mod_updates = None;
+        self.begin_run(jobs, true, false);
+    }
+
+    /// 将术语以 "原文":"译名" 的形式追加到提示词的术语表中
+    fn append_glossary(&mut self, terms: &[(String, String)]) {
+        if terms.is_empty() {
+            return;
+        }
+        if !self.config.prompt.contains("术语表：") {
+            self.config.prompt.push_str("\n术语表：");
+        }
+        for (src, dst) in terms {
+            self.config.prompt.push('\n');
+            self.config.prompt.push_str(&format!("{}:{}", serde_json::json!(src), serde_json::json!(dst)));
+        }
+        self.logs.push_back(LogEntry::new(LogLevel::Success, trf("✅ 已将 {} 条术语加入提示词", &[&terms.len()])));
+    }
+
+    fn apply_glossary_drafts(&mut self) {
+        let terms: Vec<(String, String)> = self
+            .glossary_drafts
+            .iter()
+            .filter(|(_, dst)| !dst.trim().is_empty())
+            .map(|(src, dst)| (src.clone(), dst.trim().to_string()))
+            .collect();
+        self.append_glossary(&terms);
+        self.glossary_drafts.retain(|_, dst| dst.trim().is_empty());
+    }
+
+    fn start_glossary_mining(&self) {
+        let config = self.config.clone();
+        let sender = self.msg_sender.clone();
+        thread::spawn(move || {
+            let suggestions = glossary::suggest_terms(&config);
+            let _ = sender.send(AppMsg::GlossarySuggested(suggestions));
+        });
+    }
+
+    fn render_glossary_window(&mut self, ctx: &egui::Context) {
+        if self.glossary_suggestions.is_empty() {
+            return;
+        }
+        let mut is_open = true;
+        let mut accept = false;
+
+        egui::Window::new(tr("📖 术语建议"))
+            .open(&mut is_open)
+            .collapsible(false)
+            .default_width(500.0)
+            .show(ctx, |ui| {
+                ui.label(tr("以下术语在本次译文中反复出现且译法一致，加入提示词后可提高后续翻译的一致性。"));
+                egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                    egui::Grid::new("glossary_suggestions_grid").num_columns(4).striped(true).show(ui, |ui| {
+                        ui.strong("");
+                        ui.strong(tr("原文"));
+                        ui.strong(tr("译名"));
+                        ui.strong(tr("次数"));
+                        ui.end_row();
+                        for suggestion in &mut self.glossary_suggestions {
+                            ui.checkbox(&mut suggestion.accepted, "");
+                            ui.label(&suggestion.source);
+                            ui.add(egui::TextEdit::singleline(&mut suggestion.target).desired_width(160.0));
+                            ui.label(suggestion.support.to_string());
+                            ui.end_row();
+                        }
+                    });
+                });
+                ui.horizontal(|ui| {
+                    let selected = self.glossary_suggestions.iter().filter(|s| s.accepted).count();
+                    if ui
+                        .add_enabled(selected > 0, egui::Button::new(trf("加入提示词术语表 ({})", &[&selected])))
+                        .clicked()
+                    {
+                        accept = true;
+                    }
+                    if ui.button(tr("忽略")).clicked() {
+                        self.glossary_suggestions.clear();
+                    }
+                });
+            });
+
+        if accept {
+            let terms: Vec<(String, String)> = self
+                .glossary_suggestions
+                .drain(..)
+                .filter(|s| s.accepted && !s.target.trim().is_empty())
+                .map(|s| (s.source, s.target.trim().to_string()))
+                .collect();
+            self.append_glossary(&terms);
+        }
+        if !is_open {
+            self.glossary_suggestions.clear();
+        }
+    }
+
+    fn render_raw_content_window(&mut self, ctx: &egui::Context) {
+        let mut is_open = self.show_raw_content_window;
+        let output_root = PathBuf::from(&self.config.output_path);
+        let mut to_delete: Vec<PathBuf> = Vec::new();
+        let mut refresh = false;
+
+        egui::Window::new(tr("🗄 原文快照"))
+            .open(&mut is_open)
+            .collapsible(false)
+            .default_width(600.0)
+            .show(ctx, |ui| {
+                ui.label(tr("增量更新时与快照比较，原文有改动的条目会重新翻译；删除快照后对应文件只补翻新增条目。"));
+                let total: u64 = self.raw_snapshots.iter().map(|f| f.size).sum();
+                ui.label(trf("共 {} 个快照，{} KB，位于 {}", &[
+                    &self.raw_snapshots.len(),
+                    &total.div_ceil(1024),
+                    &raw_content::dir(&output_root).display(),
+                ]));
+                ui.horizontal(|ui| {
+                    ui.label(tr("保留天数:"));
+                    ui.add(egui::DragValue::new(&mut self.config.raw_content_retention_days).range(0..=3650))
+                        .on_hover_text(tr("超过该天数未更新的快照在任务开始时清理，0 为永久保留"));
+                    if ui.button(tr("🔄 刷新")).clicked() {
+                        refresh = true;
+                    }
+                    if ui.add_enabled(!self.is_processing, egui::Button::new(tr("🧹 清理过期"))).clicked() {
+                        raw_content::prune(&output_root, self.config.raw_content_retention_days);
+                        refresh = true;
+                    }
+                    if ui
+                        .add_enabled(!self.is_processing && !self.raw_snapshots.is_empty(), egui::Button::new(tr("🗑 全部删除")))
+                        .clicked()
+                    {
+                        to_delete = self.raw_snapshots.iter().map(|f| f.path.clone()).collect();
+                    }
+                });
+                ui.separator();
+                egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    egui::Grid::new("raw_content_grid").num_columns(4).striped(true).show(ui, |ui| {
+                        ui.strong(tr("文件"));
+                        ui.strong(tr("大小"));
+                        ui.strong(tr("更新时间"));
+                        ui.label("");
+                        ui.end_row();
+                        for file in &self.raw_snapshots {
+                            ui.label(&file.name);
+                            ui.label(format!("{} KB", file.size.div_ceil(1024)));
+                            let modified = file
+                                .modified
+                                .map(|m| chrono::DateTime::<chrono::Local>::from(m).format("%Y-%m-%d %H:%M").to_string())
+                                .unwrap_or_default();
+                            ui.label(modified);
+                            if ui.add_enabled(!self.is_processing, egui::Button::new("🗑")).clicked() {
+                                to_delete.push(file.path.clone());
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+
+        if !to_delete.is_empty() {
+            let removed = to_delete.iter().filter(|p| std::fs::remove_file(p).is_ok()).count();
+            self.logs.push_back(LogEntry::new(LogLevel::Info, trf("已删除 {} 个原文快照", &[&removed])));
+            refresh = true;
+        }
+        if refresh {
+            self.raw_snapshots = raw_content::list(&output_root);
+        }
+        self.show_raw_content_window = is_open;
+    }
+
+    fn render_mod_updates_window(&mut self, ctx: &egui::Context) {
+        let mut is_open = self.show_mod_updates_window;
+        let mut recheck = false;
+        let mut start_update = false;
+
+        egui::Window::new(tr("🔄 检查模组更新"))
+            .open(&mut is_open)
+            .collapsible(false)
+            .default_width(600.0)
+            .show(ctx, |ui| {
+                ui.label(tr("与输出目录中 pack_manifest.json 记录的版本比较，上次未翻译过的模组不会列出。"));
+                ui.horizontal(|ui| {
+                    if self.is_checking_mod_updates {
+                        ui.add_enabled(false, egui::Button::new(tr("⏳ 扫描中...")));
+                    } else if ui.button(tr("🔄 刷新")).clicked() {
+                        recheck = true;
+                    }
+                    let count = self.mod_updates.as_ref().map_or(0, Vec::len);
+                    if ui
+                        .add_enabled(!self.is_processing && count > 0, egui::Button::new(trf("更新这些模组的翻译 ({})", &[&count])))
+                        .on_hover_text(tr("以更新模式只处理这些模组，补翻新增条目并重译原文有改动的条目"))
+                        .clicked()
+                    {
+                        start_update = true;
+                    }
+                });
+                let Some(updates) = &self.mod_updates else {
+                    return;
+                };
+                ui.separator();
+                if updates.is_empty() {
+                    ui.label(tr("所有模组均与上次翻译时一致"));
+                    return;
+                }
+                egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    egui::Grid::new("mod_updates_grid").num_columns(4).striped(true).show(ui, |ui| {
+                        ui.strong(tr("模组"));
+                        ui.strong(tr("上次版本"));
+                        ui.strong(tr("当前版本"));
+                        ui.strong(tr("文件"));
+                        ui.end_row();
+                        for update in updates {
+                            match &update.name {
+                                Some(name) => ui.label(format!("{} ({})", name, update.id)),
+                                None => ui.label(&update.id),
+                            };
+                            ui.label(&update.old_version);
+                            if update.new_version == update.old_version {
+                                ui.label(tr("版本相同，JAR 内容有变化"));
+                            } else {
+                                ui.label(&update.new_version);
+                            }
+                            ui.label(update.jar.file_name().unwrap_or_default().to_string_lossy());
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+
+        if recheck {
+            self.start_mod_update_check();
+        }
+        if start_update {
+            self.update_changed_mods();
+        }
+        self.show_mod_updates_window = is_open;
+    }
+
+    fn render_analysis_window(&mut self, ctx: &egui::Context) {
+        let mut is_open = self.show_analysis_window;
+        let mut apply_glossary = false;
+
+        egui::Window::new(tr("📊 字符串分析"))
+            .open(&mut is_open)
+            .collapsible(false)
+            .default_width(700.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if self.is_analyzing {
+                        ui.add_enabled(false, egui::Button::new(tr("⏳ 扫描中...")));
+                    } else if ui.button(tr("开始分析")).clicked() {
+                        self.start_analysis();
+                    }
+                    let filled = self.glossary_drafts.values().filter(|v| !v.trim().is_empty()).count();
+                    if ui
+                        .add_enabled(filled > 0, egui::Button::new(trf("加入提示词术语表 ({})", &[&filled])))
+                        .clicked()
+                    {
+                        apply_glossary = true;
+                    }
+                });
+
+                let Some(stats) = &self.string_stats else {
+                    return;
+                };
+                ui.label(trf("共 {} 条原文，{} 条不重复", &[&stats.total, &stats.unique]));
+                ui.separator();
+                egui::ScrollArea::vertical().max_height(420.0).show(ui, |ui| {
+                    egui::CollapsingHeader::new(trf("高频原文 ({})", &[&stats.frequent.len()]))
+                        .id_salt("analysis_frequent")
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            egui::Grid::new("analysis_frequent_grid").num_columns(3).striped(true).show(ui, |ui| {
+                                ui.strong(tr("次数"));
+                                ui.strong(tr("原文"));
+                                ui.strong(tr("译名"));
+                                ui.end_row();
+                                for (text, count) in &stats.frequent {
+                                    ui.label(count.to_string());
+                                    ui.add(egui::Label::new(text.as_str()).wrap());
+                                    let draft = self.glossary_drafts.entry(text.clone()).or_default();
+                                    ui.add(egui::TextEdit::singleline(draft).desired_width(160.0));
+                                    ui.end_row();
+                                }
+                            });
+                        });
+                    egui::CollapsingHeader::new(trf("最长原文 ({})", &[&stats.longest.len()]))
+                        .id_salt("analysis_longest")
+                        .show(ui, |ui| {
+                            egui::Grid::new("analysis_longest_grid").num_columns(3).striped(true).show(ui, |ui| {
+                                ui.strong(tr("字数"));
+                                ui.strong(tr("位置"));
+                                ui.strong(tr("原文"));
+                                ui.end_row();
+                                for item in &stats.longest {
+                                    ui.label(item.text.chars().count().to_string());
+                                    ui.label(format!("{}\n{}", item.mod_id, item.key));
+                                    ui.add(egui::Label::new(item.text.as_str()).wrap());
+                                    ui.end_row();
+                                }
+                            });
+                        });
+                });
+            });
+
+        if apply_glossary {
+            self.apply_glossary_drafts();
+        }
+        self.show_analysis_window = is_open;
+    }
+
+    fn render_compare_window(&mut self, ctx: &egui::Context) {
+        let mut is_open = self.show_compare_window;
+        let mut should_start = false;
+
+        egui::Window::new(tr("⚖ 模型对比"))
+            .open(&mut is_open)
+            .collapsible(false)
+            .default_width(700.0)
+            .default_height(400.0)
+            .show(ctx, |ui| {
+                ui.label(tr("从输入路径中抽取样本，分别用两个模型翻译，便于在全量翻译前挑选模型。"));
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    ui.label(tr("模型 A:"));
+                    ui.label(egui::RichText::new(&self.config.model).strong());
+                    ui.separator();
+                    ui.label(tr("模型 B:"));
+                    model_picker(
+                        ui,
+                        "compare_model_select",
+                        &mut self.config.compare_model,
+                        &self.available_models,
+                        &mut self.model_filter,
+                    );
+                    ui.separator();
+                    ui.label(tr("样本数:"));
+                    ui.add(egui::DragValue::new(&mut self.config.compare_sample_size).range(1..=200));
+
+                    if self.is_comparing {
+                        ui.add_enabled(false, egui::Button::new(tr("⏳ 对比中...")));
+                    } else if ui.button(tr("开始对比")).clicked() {
+                        should_start = true;
+                    }
+                });
+                ui.separator();
+
+                if let Some(result) = &self.compare_result {
+                    ui.label(trf(
+                        "{} 用时 {}s | {} 用时 {}s",
+                        &[
+                            &result.model_a,
+                            &format!("{:.1}", result.elapsed_a.as_secs_f32()),
+                            &result.model_b,
+                            &format!("{:.1}", result.elapsed_b.as_secs_f32()),
+                        ],
+                    ));
+                    egui::ScrollArea::both().show(ui, |ui| {
+                        egui::Grid::new("compare_grid")
+                            .num_columns(4)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong(tr("键名"));
+                                ui.strong(tr("原文"));
+                                ui.strong(&result.model_a);
+                                ui.strong(&result.model_b);
+                                ui.end_row();
+
+                                for row in &result.rows {
+                                    ui.label(&row.key);
+                                    ui.add(egui::Label::new(&row.source).wrap());
+                                    ui.add(egui::Label::new(&row.model_a).wrap());
+                                    ui.add(egui::Label::new(&row.model_b).wrap());
+                                    ui.end_row();
                                 }
                             });
+                    });
+                }
+            });
+
+        if should_start {
+            if self.missing_api_key() {
+                self.logs.push_back(LogEntry::new(LogLevel::Error, tr("请先填写 API Key")));
+            } else {
+                self.start_comparison();
+            }
+        }
+        self.show_compare_window = is_open;
+    }
+
+    /// 本次运行的文件列表，失败的文件可单独重试
+    fn render_file_table(&mut self, ui: &mut egui::Ui) {
+        if self.file_rows.is_empty() {
+            return;
+        }
+        let count = |state: FileState| self.file_rows.iter().filter(|r| r.state == state).count();
+        ui.label(trf(
+            "文件 ({})：等待 {}，翻译中 {}，完成 {}，失败 {}，跳过 {}",
+            &[
+                &self.file_rows.len(),
+                &count(FileState::Queued),
+                &count(FileState::Translating),
+                &count(FileState::Done),
+                &count(FileState::Failed),
+                &count(FileState::Skipped),
+            ],
+        ));
+        // 任务章节数量多、单个较小，单独汇总便于查看任务汉化的进度
+        let quests: Vec<FileState> =
+            self.file_rows.iter().filter(|r| has_extension(&r.path, "snbt")).map(|r| r.state).collect();
+        if !quests.is_empty() {
+            let count = |state: FileState| quests.iter().filter(|&&s| s == state).count();
+            ui.label(trf(
+                "任务文件 ({})：完成 {}，失败 {}，剩余 {}",
+                &[
+                    &quests.len(),
+                    &count(FileState::Done),
+                    &count(FileState::Failed),
+                    &(count(FileState::Queued) + count(FileState::Translating)),
+                ],
+            ));
+        }
+
+        let mut retry = None;
+        let can_retry = !self.is_processing && !self.is_stopping;
+        let row_height = ui.spacing().interact_size.y;
+        egui::ScrollArea::vertical()
+            .id_salt("file_status")
+            .auto_shrink([false, true])
+            .show_rows(ui, row_height, self.file_rows.len(), |ui, row_range| {
+                for row in &self.file_rows[row_range] {
+                    ui.horizontal(|ui| {
+                        let (label, color) = match row.state {
+                            FileState::Queued => (tr("⏸ 等待"), ui.visuals().weak_text_color()),
+                            FileState::Translating => (tr("⏳ 翻译中"), ui.visuals().text_color()),
+                            FileState::Done => (tr("✅ 完成"), egui::Color32::from_rgb(0, 200, 0)),
+                            FileState::Failed => (tr("❌ 失败"), ui.visuals().error_fg_color),
+                            FileState::Skipped => (tr("⏭ 跳过"), ui.visuals().weak_text_color()),
+                        };
+                        ui.add_sized([90.0, row_height], egui::Label::new(egui::RichText::new(label).color(color)));
+                        if row.state == FileState::Failed
+                            && ui
+                                .add_enabled(can_retry, egui::Button::new(tr("🔁 重试")).small())
+                                .on_hover_text(tr("以更新模式重新处理该文件，语言文件只补翻缺失的条目"))
+                                .clicked()
+                        {
+                            retry = Some((row.path.clone(), row.output.clone()));
+                        }
+                        let path = row.path.display().to_string();
+                        ui.add(egui::Label::new(&path).truncate()).on_hover_text(&path);
+                    });
+                }
+            });
+
+        if let Some((path, output)) = retry {
+            self.retry_file(&path, &output);
+        }
+    }
+
+    /// 运行结束后的统计面板，有失败时提示到日志与复核页查看
+    /// 并发状态：区分受并发设置限制 (许可用满且有排队) 与受服务商限流 (429 重试等待)
+    fn render_concurrency(&self, ui: &mut egui::Ui) {
+        let state = self.concurrency;
+        ui.horizontal(|ui| {
+            ui.label(trf("并发 {}/{}", &[&state.in_flight, &state.capacity]));
+            ui.separator();
+            ui.label(trf("排队 {} 批次", &[&state.queued]));
+            if state.throttled > 0 {
+                ui.separator();
+                ui.colored_label(ui.visuals().warn_fg_color, trf("限流等待 {}", &[&state.throttled]))
+                    .on_hover_text(tr("服务商返回 429，请求正在等待重试，提高并发不会加快速度"));
+            } else if state.saturated() {
+                ui.separator();
+                ui.label(egui::RichText::new(tr("已达并发上限")).weak())
+                    .on_hover_text(tr("所有并发许可都在使用中，可在配置文件中提高 max_network_concurrency"));
+            }
+        });
+    }
+
+    fn render_run_summaries(&self, ui: &mut egui::Ui) {
+        for summary in &self.run_summaries {
+            let visuals = ui.visuals().clone();
+            let has_failures = summary.failed_files > 0 || summary.failed_entries > 0;
+            let accent = if summary.status == RunStatus::Failed || has_failures {
+                visuals.warn_fg_color
+            } else {
+                egui::Color32::from_rgb(0, 200, 0)
+            };
+            egui::Frame::group(ui.style())
+                .fill(visuals.faint_bg_color)
+                .stroke(egui::Stroke::new(1.0, accent))
+                .show(ui, |ui| {
+                    ui.set_min_width(ui.available_width());
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new(JobState::Finished(summary.status).label()).strong().color(accent));
+                        ui.label(egui::RichText::new(&summary.input).weak());
+                    });
+                    let secs = summary.elapsed.as_secs();
+                    ui.label(trf(
+                        "文件: 成功 {}，跳过 {}，失败 {}；条目: 已翻译 {}，失败 {}；耗时 {} 分 {} 秒",
+                        &[
+                            &summary.processed_files,
+                            &summary.skipped_files,
+                            &summary.failed_files,
+                            &summary.translated_entries,
+                            &summary.failed_entries,
+                            &(secs / 60),
+                            &(secs % 60),
+                        ],
+                    ));
+                    if summary.reuse_only {
+                        ui.label(
+                            egui::RichText::new(trf(
+                                "仅复用：已填充 {} 条，仍需翻译 {} 条 (约 {} 字符)",
+                                &[&summary.reused_entries, &summary.untranslated_entries, &summary.untranslated_chars],
+                            ))
+                            .strong(),
+                        );
+                    }
+                    if summary.empty_entries > 0 {
+                        ui.label(egui::RichText::new(trf("{} 个条目的值为空或只含空白，未发给模型", &[&summary.empty_entries])).weak());
+                    }
+                    if !summary.skip_reasons.is_empty() {
+                        let reasons: Vec<String> =
+                            summary.skip_reasons.iter().map(|(reason, n)| format!("{} {}", reason.label(), n)).collect();
+                        ui.label(trf("跳过原因: {}", &[&reasons.join(" · ")]));
+                    }
+                    let rerunnable = summary
+                        .skip_reasons
+                        .iter()
+                        .any(|(reason, _)| matches!(reason, SkipReason::Existing | SkipReason::Filtered));
+                    if rerunnable {
+                        ui.label(egui::RichText::new(tr("跳过的文件输出已存在或超出单文件上限，可关闭“跳过已翻译的文件”或调整上限后重跑")).weak());
+                    }
+                    if !summary.unparsable_files.is_empty() {
+                        egui::CollapsingHeader::new(
+                            egui::RichText::new(trf("{} 个文件存在语法错误，未被处理", &[&summary.unparsable_files.len()]))
+                                .color(visuals.warn_fg_color),
+                        )
+                        .id_salt(("unparsable", &summary.input))
+                        .show(ui, |ui| {
+                            for file in &summary.unparsable_files {
+                                ui.label(egui::RichText::new(file).monospace());
+                            }
+                            ui.label(egui::RichText::new(tr("行列号等详细原因见日志，修复后使用“更新翻译”重试")).weak());
+                        });
+                    }
+                    if !summary.failed_keys.is_empty() {
+                        egui::CollapsingHeader::new(
+                            egui::RichText::new(trf("未翻译条目 ({})", &[&summary.failed_keys.len()])).color(visuals.warn_fg_color),
+                        )
+                        .id_salt(("failed_keys", &summary.input))
+                        .show(ui, |ui| {
+                            egui::ScrollArea::vertical().id_salt(("failed_keys_scroll", &summary.input)).max_height(240.0).show(ui, |ui| {
+                                egui::Grid::new(("failed_keys_grid", &summary.input)).num_columns(3).striped(true).show(ui, |ui| {
+                                    ui.strong(tr("位置"));
+                                    ui.strong(tr("键"));
+                                    ui.strong(tr("原文"));
+                                    ui.end_row();
+                                    for entry in &summary.failed_keys {
+                                        ui.label(&entry.context);
+                                        ui.label(egui::RichText::new(&entry.key).monospace());
+                                        ui.add(egui::Label::new(entry.source.as_str()).wrap());
+                                        ui.end_row();
+                                    }
+                                });
+                            });
+                        });
+                    }
+                    if has_failures {
+                        let hint = match self.config.failed_entries {
+                            FailedEntryMode::KeepSource => tr("失败的文件未写入译文，失败的条目暂以原文写入，请在日志中查看原因后使用“更新翻译”补翻"),
+                            FailedEntryMode::Drop => tr("失败的文件与条目未写入译文，请在日志中查看原因后使用“更新翻译”补翻"),
+                        };
+                        ui.label(egui::RichText::new(hint).color(visuals.warn_fg_color));
+                    }
+                });
+            ui.add_space(5.0);
+        }
+    }
+
+    fn render_stats_tab(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.config.record_run_history, tr("记录运行统计"))
+                .on_hover_text(tr("每次运行结束后在本机记录耗时、token 用量与失败率，不会上传"));
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.add_enabled(!self.run_history.is_empty(), egui::Button::new(tr("清空记录"))).clicked() {
+                    match run_history::clear() {
+                        Ok(()) => self.run_history.clear(),
+                        Err(e) => self.logs.push_back(LogEntry::new(LogLevel::Error, trf("❌ 清空运行统计失败: {}", &[&e]))),
+                    }
+                }
+            });
+        });
+        ui.separator();
+
+        if self.run_history.is_empty() {
+            ui.label(egui::RichText::new(tr("暂无记录。开启后，每次调用 API 的运行结束时会记录一条统计")).weak());
+            return;
+        }
+
+        let stats = run_history::by_model(&self.run_history);
+        ui.label(egui::RichText::new(tr("按模型汇总 (按每条 token 从低到高)")).strong());
+        egui::Grid::new("model_stats_grid").num_columns(6).spacing([16.0, 4.0]).striped(true).show(ui, |ui| {
+            for header in [tr("模型"), tr("运行次数"), tr("已翻译条目"), tr("失败率"), tr("每条 token"), tr("条目/分钟")] {
+                ui.label(egui::RichText::new(header).strong());
+            }
+            ui.end_row();
+            for model in &stats {
+                ui.label(&model.model);
+                ui.label(model.runs.to_string());
+                ui.label(model.translated_entries.to_string());
+                ui.label(format!("{:.1}%", model.failure_rate() * 100.0));
+                let tokens = format!("{:.1}", model.tokens_per_entry());
+                if model.estimated {
+                    ui.label(format!("≈{}", tokens)).on_hover_text(tr("服务商未返回用量，按字符数估算"));
+                } else {
+                    ui.label(tokens);
+                }
+                ui.label(format!("{:.1}", model.entries_per_minute()));
+                ui.end_row();
+            }
+        });
+
+        // 每条 token 的横向条形图，越短越省
+        ui.add_space(8.0);
+        let max = stats.iter().map(|m| m.tokens_per_entry()).fold(0.0_f32, f32::max);
+        if max > 0.0 {
+            let bar_color = ui.visuals().selection.bg_fill;
+            for model in &stats {
+                ui.horizontal(|ui| {
+                    ui.add_sized([160.0, 18.0], egui::Label::new(&model.model).truncate());
+                    let width = (ui.available_width() - 80.0).max(40.0);
+                    let (rect, _) = ui.allocate_exact_size(egui::vec2(width, 14.0), egui::Sense::hover());
+                    let filled = egui::Rect::from_min_size(rect.min, egui::vec2(width * model.tokens_per_entry() / max, rect.height()));
+                    ui.painter().rect_filled(rect, 2.0, ui.visuals().faint_bg_color);
+                    ui.painter().rect_filled(filled, 2.0, bar_color);
+                    ui.label(format!("{:.1}", model.tokens_per_entry()));
+                });
+            }
+        }
+
+        ui.add_space(8.0);
+        egui::CollapsingHeader::new(trf("最近的运行 ({})", &[&self.run_history.len()])).show(ui, |ui| {
+            egui::ScrollArea::vertical().max_height(260.0).show(ui, |ui| {
+                for record in self.run_history.iter().rev() {
+                    ui.label(trf(
+                        "{} · {} · {} · 已翻译 {}，失败 {} · {} token · {} 秒",
+                        &[
+                            &record.finished_at,
+                            &record.model,
+                            &JobState::Finished(record.status).label(),
+                            &record.translated_entries,
+                            &record.failed_entries,
+                            &(record.prompt_tokens + record.completion_tokens),
+                            &record.elapsed_secs,
+                        ],
+                    ))
+                    .on_hover_text(&record.input);
+                }
+            });
+        });
+    }
 
-                        if ui.button("→").on_hover_text("交换语言").clicked() {
-                            std::mem::swap(&mut self.config.source_lang, &mut self.config.target_lang);
-                        }
+    fn render_review_tab(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(trf("共 {} 条可疑译文，请人工复核", &[&self.qa_issues.len()]))
+                .on_hover_text(tr("查看长度异常或丢失换行的译文"));
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.button(tr("清空")).clicked() {
+                    self.qa_issues.clear();
+                }
+                if ui
+                    .button(tr("⚖ 模型对比"))
+                    .on_hover_text(tr("抽样对比两个模型的翻译效果"))
+                    .clicked()
+                {
+                    self.show_compare_window = true;
+                }
+                if ui
+                    .button(tr("🆚 译文对比"))
+                    .on_hover_text(tr("对比两份译文，或译文与源模组，找出缺失和不一致的条目"))
+                    .clicked()
+                {
+                    if self.diff_a.is_empty() {
+                        self.diff_a = self.config.output_path.clone();
+                    }
+                    self.show_diff_window = true;
+                }
+            });
+        });
+        ui.separator();
 
-                        egui::ComboBox::from_id_salt("target_lang")
-                            .selected_text(&self.config.target_lang)
-                            .width(80.0)
-                            .show_ui(ui, |ui| {
-                                for (code, name) in LANGUAGES {
-                                    ui.selectable_value(
-                                        &mut self.config.target_lang,
-                                        code.to_string(),
-                                        format!("{} - {}", code, name),
-                                    );
-                                }
-                            });
-                    });
+        egui::ScrollArea::both().show(ui, |ui| {
+            egui::Grid::new("qa_grid")
+                .num_columns(4)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong(tr("位置"));
+                    ui.strong(tr("原因"));
+                    ui.strong(tr("原文"));
+                    ui.strong(tr("译文"));
                     ui.end_row();
 
-                    ui.label("API KEY:");
-                    ui.add(egui::TextEdit::singleline(&mut self.config.api_key).password(true));
-                    ui.end_row();
+                    for issue in &self.qa_issues {
+                        ui.label(format!("{}\n{}", issue.context_id, issue.key));
+                        ui.label(&issue.reason);
+                        ui.add(egui::Label::new(&issue.source).wrap());
+                        ui.add(egui::Label::new(&issue.translated).wrap());
+                        ui.end_row();
+                    }
+                });
+        });
+    }
+}
 
-                    ui.label("选择模型:");
-                    ui.horizontal(|ui| {
-                        egui::ComboBox::from_id_salt("model_select")
-                            .selected_text(&self.config.model)
-                            .width(180.0)
-                            .show_ui(ui, |ui| {
-                                for model in &self.available_models {
-                                    ui.selectable_value(
-                                        &mut self.config.model,
-                                        model.clone(),
-                                        model,
-                                    );
-                                }
-                            });
+impl MyApp {
+    // 视口矩形以界面点为单位，乘以缩放换算回窗口的逻辑像素
+    fn track_window_geometry(&mut self, ctx: &egui::Context) {
+        let zoom = ctx.zoom_factor();
+        self.config.ui_zoom = zoom;
+        let (inner, outer) = ctx.input(|i| (i.viewport().inner_rect, i.viewport().outer_rect));
+        if let Some(rect) = inner {
+            self.window_size = Some([rect.width() * zoom, rect.height() * zoom]);
+        }
+        if let Some(rect) = outer {
+            self.window_pos = Some([rect.min.x * zoom, rect.min.y * zoom]);
+        }
+    }
+}
 
-                        if ui.button("🔄 检查 & 刷新").clicked() {
-                            if self.config.api_key.is_empty() {
-                                self.logs
-                                    .push(LogEntry::new(LogLevel::Error, "请先填写 API Key"));
-                            } else {
-                                self.check_connection_and_fetch_models();
-                            }
+impl eframe::App for MyApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.track_window_geometry(ctx);
+        self.autosave_config(ctx);
+        self.render_prompt_editor(ctx);
+        self.render_template_editor(ctx);
+        self.render_cancel_confirm(ctx);
+        self.render_preflight_confirm(ctx);
+        self.render_queue_window(ctx);
+        self.render_compare_window(ctx);
+        self.render_merge_window(ctx);
+        self.render_analysis_window(ctx);
+        self.render_mod_updates_window(ctx);
+        self.render_raw_content_window(ctx);
+        self.render_glossary_window(ctx);
+        self.render_diff_window(ctx);
+        // 处理日志
+        while let Ok(msg) = self.msg_receiver.try_recv() {
+            match msg {
+                AppMsg::Log(entry) => {
+                    while self.logs.len() >= self.config.max_log_entries.max(1) {
+                        self.logs.pop_front();
+                    }
+                    self.logs.push_back(entry);
+                }
+                AppMsg::JobStarted(i) => {
+                    if let Some(state) = self.job_states.get_mut(i) {
+                        *state = JobState::Running;
+                    }
+                }
+                AppMsg::JobFinished(i, status) => {
+                    if let Some(state) = self.job_states.get_mut(i) {
+                        *state = JobState::Finished(status);
+                    }
+                }
+                AppMsg::TaskFinished(status) => {
+                    if self.is_stopping && status == RunStatus::Cancelled {
+                        self.logs.push_back(LogEntry::new(LogLevel::Warn, tr("任务已被用户取消")));
+                    }
+                    self.is_processing = false;
+                    self.is_stopping = false;
+                    self.cancellation_token = None;
+                    self.run_started = None;
+                    self.active_batches.clear();
+                    self.concurrency = ConcurrencySnapshot::default();
+                    if status == RunStatus::Completed && self.config.suggest_glossary {
+                        self.start_glossary_mining();
+                    }
+                }
+                AppMsg::QaIssue(issue) => {
+                    self.qa_issues.push(issue);
+                }
+                AppMsg::RunSummary(summary) => {
+                    self.run_summaries.push(summary);
+                    // 后台任务在发送汇总前已写入统计
+                    if self.config.record_run_history {
+                        self.run_history = run_history::load();
+                    }
+                }
+                AppMsg::FileStatus { path, output, state } => match self.file_index.get(&path) {
+                    Some(&i) => self.file_rows[i].state = state,
+                    None => {
+                        self.file_index.insert(path.clone(), self.file_rows.len());
+                        self.file_rows.push(FileRow { path, output, state });
+                    }
+                },
+                AppMsg::BatchProgress { label, received, total } => {
+                    self.active_batches.insert(label, (received, total));
+                }
+                AppMsg::BatchDone(label) => {
+                    self.active_batches.remove(&label);
+                }
+                AppMsg::Concurrency(snapshot) => {
+                    self.concurrency = snapshot;
+                }
+                AppMsg::HealthChecked(latency) => {
+                    self.last_latency_ms = latency;
+                }
+                AppMsg::CompareFinished(result) => {
+                    self.is_comparing = false;
+                    self.compare_result = result;
+                }
+                AppMsg::DiffFinished(report) => {
+                    self.is_diffing = false;
+                    self.diff_report = report;
+                }
+                AppMsg::GlossarySuggested(suggestions) => {
+                    if !suggestions.is_empty() {
+                        self.logs.push_back(LogEntry::new(
+                            LogLevel::Info,
+                            trf("📖 从译文中发现 {} 条候选术语，请在弹出的窗口中确认", &[&suggestions.len()]),
+                        ));
+                    }
+                    self.glossary_suggestions = suggestions;
+                }
+                AppMsg::AnalysisFinished(stats) => {
+                    self.is_analyzing = false;
+                    self.string_stats = Some(stats);
+                }
+                AppMsg::PreflightChecked { jobs, is_update, use_queue, outcome } => {
+                    self.is_preflighting = false;
+                    match outcome {
+                        Err(detail) => self.logs.push_back(LogEntry::new(
+                            LogLevel::Error,
+                            trf("❌ 输出目录不可写，任务未启动: {}", &[&detail]),
+                        )),
+                        Ok(warnings) if !warnings.is_empty() => {
+                            self.pending_run = Some(PendingRun { jobs, is_update, use_queue, warnings });
                         }
-                    });
-                    ui.end_row();
+                        Ok(_) => self.begin_run(jobs, is_update, use_queue),
+                    }
+                }
+                AppMsg::ModUpdatesChecked(updates) => {
+                    self.is_checking_mod_updates = false;
+                    if updates.is_none() {
+                        self.logs.push_back(LogEntry::new(
+                            LogLevel::Warn,
+                            tr("输出目录中没有 pack_manifest.json，请先完成一次翻译"),
+                        ));
+                    }
+                    self.mod_updates = updates;
+                }
+                AppMsg::MergePlanned(plan) => {
+                    self.is_merging = false;
+                    if let Some(mut plan) = plan {
+                        plan.apply_policy(self.merge_policy);
+                        if self.merge_policy == MergePolicy::Ask && !plan.conflicts.is_empty() {
+                            self.merge_plan = Some(plan);
+                        } else {
+                            self.write_merge(&plan);
+                        }
+                    }
+                }
+                AppMsg::UpdateAvailable(release) => {
+                    self.available_update = Some(release);
+                }
+                AppMsg::ModelsFetched(models) => {
+                    if models.is_empty() {
+                        continue;
+                    }
+                    self.available_models = models;
+                    // 允许手动填写列表外的模型，仅在未填写时默认选中第一个
+                    if self.config.model.trim().is_empty() {
+                        self.config.model = self.available_models[0].clone();
+                    }
+                    self.config.cached_models = self.available_models.clone();
+                    self.save_config();
+                }
+            }
+        }
 
-                    ui.label("输入路径:");
-                    ui.horizontal(|ui| {
-                        ui.text_edit_singleline(&mut self.config.input_path);
-                        if ui.button("📂 打开文件夹").clicked() {
-                            if let Some(path) = rfd::FileDialog::new()
-                                .set_directory(&mut self.config.input_path)
-                                .pick_folder()
-                            {
-                                self.config.input_path = path.display().to_string();
-                            }
+        // 底部个人信息
+        egui::TopBottomPanel::bottom("footer_panel").show(ctx, |ui| {
+            ui.add_space(2.0);
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(concat!("v", env!("CARGO_PKG_VERSION"))).weak().size(10.0));
+                ui.separator();
+                let previous = self.config.ui_language;
+                egui::ComboBox::from_id_salt("ui_language")
+                    .selected_text(self.config.ui_language.display_name())
+                    .show_ui(ui, |ui| {
+                        for lang in UiLanguage::ALL {
+                            ui.selectable_value(&mut self.config.ui_language, lang, lang.display_name());
                         }
-                        // 没必要了
-                        if ui.button("📄 打开文件").clicked() {
-                            if let Some(file) = rfd::FileDialog::new()
-                                .add_filter("Minecraft Mod", &["jar", "json", "lang"])
-                                .set_directory(&mut self.config.input_path)
-                                .pick_file()
-                            {
-                                self.config.input_path = file.display().to_string();
-                            }
+                    })
+                    .response
+                    .on_hover_text(tr("界面语言"));
+                if self.config.ui_language != previous {
+                    set_language(self.config.ui_language);
+                }
+
+                let previous = self.config.theme;
+                egui::ComboBox::from_id_salt("ui_theme")
+                    .selected_text(theme_name(self.config.theme))
+                    .show_ui(ui, |ui| {
+                        for theme in [UiTheme::System, UiTheme::Dark, UiTheme::Light] {
+                            ui.selectable_value(&mut self.config.theme, theme, theme_name(theme));
                         }
-                    });
-                    ui.end_row();
+                    })
+                    .response
+                    .on_hover_text(tr("界面主题"));
+                if self.config.theme != previous {
+                    ctx.set_theme(theme_preference(self.config.theme));
+                }
 
-                    ui.label("输出目录:");
-                    ui.horizontal(|ui| {
-                        ui.text_edit_singleline(&mut self.config.output_path);
-                        if ui.button("📂 选择文件夹").clicked() {
-                            if let Some(path) = rfd::FileDialog::new()
-                                .set_directory(&mut self.config.output_path)
-                                .pick_folder()
-                            {
-                                self.config.output_path = path.display().to_string();
-                            }
+                // Ctrl +/- 也会改变缩放，这里以上下文中的实际值为准
+                let mut zoom = ctx.zoom_factor();
+                let response = ui
+                    .add(egui::DragValue::new(&mut zoom).range(0.5..=2.5).speed(0.01).fixed_decimals(2).prefix("🔍 "))
+                    .on_hover_text(tr("界面缩放"));
+                if response.changed() {
+                    ctx.set_zoom_factor(zoom);
+                }
+                if self.is_config_dirty() {
+                    ui.label(egui::RichText::new(tr("● 未保存")).weak().size(11.0))
+                        .on_hover_text(tr("设置修改后会自动保存"));
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.hyperlink_to(
+                        egui::RichText::new(tr("GitHub 主页")).size(11.0),
+                        "https://github.com/chrysoljq/mc_translator",
+                    );
+
+                    ui.label(egui::RichText::new("|").weak().size(11.0));
+
+                    ui.hyperlink_to(
+                        egui::RichText::new(tr("关于作者")).size(11.0),
+                        "https://github.com/chrysoljq",
+                    );
+                });
+            });
+            ui.add_space(2.0); // 底部留白
+        });
+
+        if let Some(release) = &self.available_update {
+            let mut dismissed = false;
+            egui::TopBottomPanel::top("update_banner").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(trf("🎉 发现新版本 {}", &[&release.version]));
+                    ui.hyperlink_to(tr("前往下载"), &release.url);
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.small_button("✖").on_hover_text(tr("忽略")).clicked() {
+                            dismissed = true;
                         }
                     });
-                    ui.end_row();
                 });
-            ui.add_space(10.0);
-            ui.horizontal(|ui| {
-                if ui
-                    .button("📝 编辑提示词")
-                    .on_hover_text("自定义发送给 AI 的系统提示词")
-                    .clicked()
-                {
-                    self.show_prompt_editor = true;
-                }
-                ui.separator();
-                ui.label("批次大小:");
-                ui.add(egui::DragValue::new(&mut self.config.batch_size).range(1..=1000))
-                    .on_hover_text("影响上下文的处理");
-                ui.add_space(10.0);
-                ui.checkbox(&mut self.config.skip_existing, "跳过已翻译的文件");
-                ui.separator();
-                ui.checkbox(&mut self.config.skip_quest, "跳过 snbt")
-                    .on_hover_text("勾选后将不再检查config/ftbquests，只检查kubejs下的本地化文件");
             });
-            ui.end_row();
-            ui.add_space(15.0);
+            if dismissed {
+                self.available_update = None;
+            }
+        }
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.heading(tr("Minecraft Mod 汉化助手（支持任务、模组、资源包）"));
+            ui.separator();
 
             ui.horizontal(|ui| {
-                if self.is_processing {
-                    ui.add_enabled(false, egui::Button::new("⏳ 处理中..."));
-                    ui.spinner();
-                    if ui.button("❌ 取消任务").clicked() {
-                        self.cancel_processing();
+                if self.is_stopping {
+                    ui.add_enabled(false, egui::Button::new(tr("⏳ 正在停止…")));
+                } else if self.is_preflighting {
+                    ui.add_enabled(false, egui::Button::new(tr("⏳ 正在检查输出目录…")));
+                } else if self.is_processing {
+                    ui.add_enabled(false, egui::Button::new(tr("⏳ 处理中...")));
+                    if ui.button(tr("❌ 取消任务")).clicked() {
+                        self.request_cancel();
                     }
                 } else {
-                    if ui.button("🚀 开始翻译").clicked() {
-                        if self.config.api_key.is_empty() {
+                    if ui.button(tr("🚀 开始翻译")).clicked() {
+                        if self.run_missing_api_key() {
                             self.logs
-                                .push(LogEntry::new(LogLevel::Error, "请先填写 API Key"));
+                                .push_back(LogEntry::new(LogLevel::Error, tr("请先填写 API Key")));
                         } else {
-                            self.logs.push(LogEntry::new(LogLevel::Info, "任务启动..."));
-                            self.start_processing(false);
+                            self.logs.push_back(LogEntry::new(LogLevel::Info, tr("任务启动...")));
+                            self.start_processing(false, false);
                         }
                     }
-                    if ui.button("🔄 更新翻译").clicked() {
-                        if self.config.api_key.is_empty() {
+                    if ui.button(tr("🔄 更新翻译")).clicked() {
+                        if self.run_missing_api_key() {
                             self.logs
-                                .push(LogEntry::new(LogLevel::Error, "请先填写 API Key"));
+                                .push_back(LogEntry::new(LogLevel::Error, tr("请先填写 API Key")));
                         } else {
                             self.logs
-                                .push(LogEntry::new(LogLevel::Info, "更新任务启动..."));
-                            self.start_processing(true);
+                                .push_back(LogEntry::new(LogLevel::Info, tr("更新任务启动...")));
+                            self.start_processing(true, false);
                         }
                     }
                 }
             });
 
+            ui.add_space(5.0);
+
+            ui.horizontal(|ui| {
+                for tab in MainTab::ALL {
+                    let text = match tab {
+                        MainTab::Review if !self.qa_issues.is_empty() => {
+                            format!("{} ({})", tab.label(), self.qa_issues.len())
+                        }
+                        MainTab::Progress if !self.active_batches.is_empty() => {
+                            format!("{} ({})", tab.label(), self.active_batches.len())
+                        }
+                        _ => tab.label().to_string(),
+                    };
+                    ui.selectable_value(&mut self.tab, tab, text);
+                }
+            });
             ui.separator();
 
-            ui.push_id("log_area", |ui| {
-                ui.style_mut().spacing.item_spacing.y = 0.0;
-                egui::ScrollArea::vertical()
-                    .stick_to_bottom(true)
-                    .auto_shrink([false, true])
-                    .show(ui, |ui| {
-                        for (i, entry) in self.logs.iter().enumerate() {
-                            let visuals = ui.visuals();
-                            let (text_color, prefix) = match entry.level {
-                                LogLevel::Info => (visuals.text_color(), "INFO"),
-                                LogLevel::Success => (egui::Color32::from_rgb(0, 200, 0), "DONE"),
-                                LogLevel::Warn => (visuals.warn_fg_color, "WARN"),
-                                LogLevel::Error => (visuals.error_fg_color, "ERR "),
-                            };
+            match self.tab {
+                MainTab::Settings => {
+                    egui::Grid::new("settings_grid")
+                        .num_columns(3)
+                        .spacing([10.0, 8.0])
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.label("BASE URL:");
+                    
 
-                            let bg_color = if i % 2 == 1 {
-                                visuals.faint_bg_color
-                            } else {
-                                egui::Color32::TRANSPARENT
-                            };
+                            ui.horizontal(|ui| {
+                                ui.text_edit_singleline(&mut self.config.base_url);
+                                const LANGUAGES: &[(&str, &str)] = &[
+                                    ("en_us", "English"),
+                                    ("zh_cn", "Simplified Chinese"),
+                                    ("zh_tw", "Traditional Chinese"),
+                                    ("ja_jp", "Japanese"),
+                                    ("ko_kr", "Korean"),
+                                    ("ru_ru", "Russian"),
+                                    ("fr_fr", "French"),
+                                    ("es_es", "Spanish"),
+                                    ("de_de", "German"),
+                                    ("it_it", "Italian"),
+                                    ("pt_br", "Brazil"),
+                                ];
+
+                                egui::ComboBox::from_id_salt("source_lang")
+                                    .selected_text(&self.config.source_lang)
+                                    .width(80.0)
+                                    .show_ui(ui, |ui| {
+                                        for (code, name) in LANGUAGES {
+                                            ui.selectable_value(
+                                                &mut self.config.source_lang,
+                                                code.to_string(),
+                                                format!("{} - {}", code, name),
+                                            );
+                                        }
+                                    });
+
+                                if ui.button("→").on_hover_text(tr("交换语言")).clicked() {
+                                    std::mem::swap(&mut self.config.source_lang, &mut self.config.target_lang);
+                                }
 
-                            let full_text =
-                                format!("{} [{}] {}", entry.time, prefix, entry.message);
+                                egui::ComboBox::from_id_salt("target_lang")
+                                    .selected_text(&self.config.target_lang)
+                                    .width(80.0)
+                                    .show_ui(ui, |ui| {
+                                        for (code, name) in LANGUAGES {
+                                            ui.selectable_value(
+                                                &mut self.config.target_lang,
+                                                code.to_string(),
+                                                format!("{} - {}", code, name),
+                                            );
+                                        }
+                                    });
+                            });
+                            ui.end_row();
 
-                            let mut job = egui::text::LayoutJob::single_section(
-                                full_text,
-                                egui::TextFormat {
-                                    font_id: egui::FontId::monospace(13.0),
-                                    color: text_color,
-                                    ..Default::default()
-                                },
+                            ui.label(tr("备选源语言:"));
+                            let response = ui.add(
+                                egui::TextEdit::singleline(&mut self.fallback_langs_input)
+                                    .hint_text("en_gb, en_au")
+                                    .desired_width(160.0),
                             );
-                            job.wrap.break_anywhere = true;
-
-                            egui::Frame::new()
-                                .fill(bg_color)
-                                .inner_margin(2.0)
-                                .show(ui, |ui| {
-                                    ui.set_min_width(ui.available_width());
-                                    ui.label(job);
-                                });
+                            if response.changed() {
+                                self.config.source_lang_fallbacks = self
+                                    .fallback_langs_input
+                                    .split(',')
+                                    .map(|s| s.trim().to_lowercase())
+                                    .filter(|s| !s.is_empty())
+                                    .collect();
+                            }
+                            response.on_hover_text(tr("目录中没有源语言文件时，按顺序改用这些语言的文件作为原文，逗号分隔"));
+                            ui.end_row();
+
+                            ui.label("API KEY:");
+                            ui.add(egui::TextEdit::singleline(&mut self.config.api_key).password(true));
+                            ui.end_row();
+
+                            ui.label(tr("选择模型:"));
+                            ui.horizontal(|ui| {
+                                model_picker(
+                                    ui,
+                                    "model_select",
+                                    &mut self.config.model,
+                                    &self.available_models,
+                                    &mut self.model_filter,
+                                );
+
+                                if ui.button(tr("🔄 检查 & 刷新")).clicked() {
+                                    if self.missing_api_key() {
+                                        self.logs
+                                            .push_back(LogEntry::new(LogLevel::Error, tr("请先填写 API Key")));
+                                    } else {
+                                        self.check_connection_and_fetch_models();
+                                    }
+                                }
+                                if let Some(ms) = self.last_latency_ms {
+                                    ui.label(egui::RichText::new(format!("{} ms", ms)).weak())
+                                        .on_hover_text(tr("最近一次测试请求的往返延迟"));
+                                }
+                                match context_window::lookup(&self.config.context_windows, &self.config.model) {
+                                    Some(tokens) => ui.label(egui::RichText::new(trf("上下文 {}K", &[&(tokens / 1000)])).weak()),
+                                    None => ui.label(egui::RichText::new(tr("上下文未知")).weak()),
+                                }
+                                .on_hover_text(tr("按模型上下文窗口自动缩小过长的批次并精简参考译文；窗口大小可在配置文件的 context_windows 中按模型名片段修改"));
+                            });
+                            ui.end_row();
+                        });
+                    ui.add_space(10.0);
+                    ui.horizontal_wrapped(|ui| {
+                        if ui
+                            .button(tr("📝 编辑提示词"))
+                            .on_hover_text(tr("自定义发送给 AI 的系统提示词"))
+                            .clicked()
+                        {
+                            self.show_prompt_editor = true;
+                        }
+                        if ui
+                            .button(tr("🧩 请求模板"))
+                            .on_hover_text(tr("自定义接口路径与请求体，适配请求格式略有不同的兼容网关"))
+                            .clicked()
+                        {
+                            self.show_template_editor = true;
+                        }
+                        ui.separator();
+                        ui.label(tr("批次大小:"));
+                        ui.add(egui::DragValue::new(&mut self.config.batch_size).range(1..=1000))
+                            .on_hover_text(tr("影响上下文的处理"));
+                        ui.separator();
+                        ui.checkbox(&mut self.config.mock_provider, tr("离线伪翻译"))
+                            .on_hover_text(tr("不请求 API，生成带重音和填充的伪译文，用于测试输出路径和格式代码"));
+                        ui.checkbox(&mut self.config.batch_api_mode, tr("批量模式"))
+                            .on_hover_text(tr("通过 OpenAI Batch API 提交，最长 24 小时完成，费用约减半；中断后重新运行会继续等待已提交的任务"));
+                        ui.checkbox(&mut self.config.notify_on_finish, tr("完成通知"))
+                            .on_hover_text(tr("任务结束时发送系统通知"));
+                        ui.add_enabled(
+                            self.config.notify_on_finish,
+                            egui::Checkbox::new(&mut self.config.notify_sound, tr("提示音")),
+                        );
+                        ui.checkbox(&mut self.config.debug_dump, tr("调试日志"))
+                            .on_hover_text(tr("将每个批次的原始请求和响应保存到输出目录的 debug 文件夹"));
+                        ui.checkbox(&mut self.config.check_update, tr("检查更新"))
+                            .on_hover_text(tr("启动时从 GitHub 检查是否有新版本"));
+                        ui.checkbox(&mut self.config.suggest_glossary, tr("术语建议"))
+                            .on_hover_text(tr("任务完成后从译文中挖掘反复出现的术语，确认后加入提示词"));
+                        ui.checkbox(&mut self.config.mask_placeholders, tr("占位符保护"))
+                            .on_hover_text(tr("发送前把 §a、%s、{0} 等格式代码替换为 ⟦P1⟧ 标记，收到译文后还原"));
+                    });
+                    ui.horizontal_wrapped(|ui| {
+                        ui.checkbox(&mut self.config.fuzzy_memory, tr("模糊翻译记忆"))
+                            .on_hover_text(tr("通过 /embeddings 接口为每批原文检索语义相近的历史译文，附在提示词中作为参考；批量模式下不生效"));
+                        ui.add_enabled_ui(self.config.fuzzy_memory, |ui| {
+                            ui.label(tr("向量模型:"));
+                            ui.add(egui::TextEdit::singleline(&mut self.config.embedding_model).desired_width(160.0));
+                            ui.label(tr("相似度阈值:"));
+                            ui.add(egui::DragValue::new(&mut self.config.fuzzy_memory_threshold).range(0.5..=1.0).speed(0.01));
+                        });
+                    });
+                    ui.horizontal_wrapped(|ui| {
+                        ui.checkbox(&mut self.config.reuse_only, tr("仅复用"))
+                            .on_hover_text(tr("不调用 API，只用内置汉化、社区汉化包和翻译记忆中原文相同的译文填充语言文件，并统计仍需翻译的条目"));
+                        ui.add_enabled_ui(self.config.reuse_only, |ui| {
+                            ui.label(tr("社区汉化包:"));
+                            let response = ui.add(
+                                egui::TextEdit::singleline(&mut self.community_packs_input)
+                                    .hint_text("packs/ChineseTranslation.zip")
+                                    .desired_width(260.0),
+                            );
+                            if response.changed() {
+                                self.config.community_packs = self
+                                    .community_packs_input
+                                    .split(',')
+                                    .map(|s| s.trim().to_string())
+                                    .filter(|s| !s.is_empty())
+                                    .collect();
+                            }
+                            response.on_hover_text(tr("目录或资源包 zip，逗号分隔，靠前的优先"));
+                        });
+                    });
+                    ui.horizontal_wrapped(|ui| {
+                        ui.label(tr("脚本钩子:"));
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.config.script_hooks)
+                                .hint_text("hooks.rhai")
+                                .desired_width(260.0),
+                        )
+                        .on_hover_text(tr("rhai 脚本，可定义 on_key_filter(key, value)、post_translate(key, src, dst)、map_output_path(path)，留空不启用"));
+                        if ui.button(tr("📄 选择")).clicked()
+                            && let Some(path) = rfd::FileDialog::new().add_filter("Rhai", &["rhai"]).pick_file()
+                        {
+                            self.config.script_hooks = path.display().to_string();
                         }
                     });
-            });
+                }
+                MainTab::Files => {
+                    egui::Grid::new("files_grid")
+                        .num_columns(2)
+                        .spacing([10.0, 8.0])
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.label(tr("输入路径:"));
+                            ui.horizontal(|ui| {
+                                ui.text_edit_singleline(&mut self.config.input_path);
+                                if ui.button(tr("📂 打开文件夹")).clicked()
+                                    && let Some(path) = rfd::FileDialog::new()
+                                        .set_directory(&mut self.config.input_path)
+                                        .pick_folder()
+                                    {
+                                        self.config.input_path = path.display().to_string();
+                                    }
+                                // 没必要了
+                                if ui.button(tr("📄 打开文件")).clicked()
+                                    && let Some(file) = rfd::FileDialog::new()
+                                        .add_filter("Minecraft Mod", &["jar", "json", "lang"])
+                                        .set_directory(&mut self.config.input_path)
+                                        .pick_file()
+                                    {
+                                        self.config.input_path = file.display().to_string();
+                                    }
+                                let mut picked = None;
+                                egui::ComboBox::from_id_salt("launcher_instances")
+                                    .selected_text(tr("🎮 实例"))
+                                    .show_ui(ui, |ui| {
+                                        let found = self.instances.get_or_insert_with(instances::discover);
+                                        if found.is_empty() {
+                                            ui.label(tr("未找到启动器实例"));
+                                        }
+                                        for instance in found.iter() {
+                                            let label = format!("[{}] {}", instance.launcher, instance.name);
+                                            if ui
+                                                .selectable_label(false, label)
+                                                .on_hover_text(instance.game_dir.display().to_string())
+                                                .clicked()
+                                            {
+                                                picked = Some(instance.clone());
+                                            }
+                                        }
+                                        ui.separator();
+                                        if ui.button(tr("🔄 重新查找")).clicked() {
+                                            self.instances = Some(instances::discover());
+                                        }
+                                    })
+                                    .response
+                                    .on_hover_text(tr("从官方启动器、Prism/MultiMC 与 CurseForge 的实例中选择输入路径"));
+                                if let Some(instance) = picked {
+                                    self.select_instance(&instance);
+                                }
+                            });
+                            ui.end_row();
+
+                            ui.label(tr("输出目录:"));
+                            ui.horizontal(|ui| {
+                                ui.text_edit_singleline(&mut self.config.output_path);
+                                if ui.button(tr("📂 选择文件夹")).clicked()
+                                    && let Some(path) = rfd::FileDialog::new()
+                                        .set_directory(&mut self.config.output_path)
+                                        .pick_folder()
+                                    {
+                                        self.config.output_path = path.display().to_string();
+                                    }
+                            });
+                            ui.end_row();
+
+                            ui.label(tr("输出结构:"));
+                            ui.horizontal(|ui| {
+                                let layout_name = |layout: OutputLayout| match layout {
+                                    OutputLayout::ResourcePack => tr("资源包 (assets/)"),
+                                    OutputLayout::Kubejs => tr("KubeJS (kubejs/assets/)"),
+                                };
+                                egui::ComboBox::from_id_salt("output_layout")
+                                    .selected_text(layout_name(self.config.output_layout))
+                                    .show_ui(ui, |ui| {
+                                        for layout in [OutputLayout::ResourcePack, OutputLayout::Kubejs] {
+                                            ui.selectable_value(&mut self.config.output_layout, layout, layout_name(layout));
+                                        }
+                                    })
+                                    .response
+                                    .on_hover_text(tr("KubeJS 结构可直接放入整合包根目录，玩家无需手动启用资源包"));
+                            });
+                            ui.end_row();
+
+                            ui.label(tr("输出路径模板:"));
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.config.output_path_template)
+                                    .hint_text("{output}/assets/{modid}/lang/{filename}")
+                                    .desired_width(360.0),
+                            )
+                            .on_hover_text(tr("留空按输出结构；可用 {output} {modid} {target} {ext} {filename}，须包含 {modid}"));
+                            ui.end_row();
+
+                            ui.label(tr("额外输出:"));
+                            ui.vertical(|ui| {
+                                let layout_name = |layout: OutputLayout| match layout {
+                                    OutputLayout::ResourcePack => tr("资源包 (assets/)"),
+                                    OutputLayout::Kubejs => tr("KubeJS (kubejs/assets/)"),
+                                };
+                                let mut remove = None;
+                                for (i, target) in self.config.output_targets.iter_mut().enumerate() {
+                                    ui.horizontal(|ui| {
+                                        ui.add(
+                                            egui::TextEdit::singleline(&mut target.path)
+                                                .hint_text("resourcepacks/translation.zip")
+                                                .desired_width(260.0),
+                                        );
+                                        egui::ComboBox::from_id_salt(("output_target_layout", i))
+                                            .selected_text(layout_name(target.layout))
+                                            .show_ui(ui, |ui| {
+                                                for layout in [OutputLayout::ResourcePack, OutputLayout::Kubejs] {
+                                                    ui.selectable_value(&mut target.layout, layout, layout_name(layout));
+                                                }
+                                            });
+                                        if ui.small_button("🗑").clicked() {
+                                            remove = Some(i);
+                                        }
+                                    });
+                                }
+                                if let Some(i) = remove {
+                                    self.config.output_targets.remove(i);
+                                }
+                                if ui
+                                    .button(tr("➕ 添加输出目标"))
+                                    .on_hover_text(tr("任务完成后把输出目录中的 assets/ 同步到这些目录，路径以 .zip 结尾时打包为资源包"))
+                                    .clicked()
+                                {
+                                    self.config.output_targets.push(OutputTarget::default());
+                                }
+                            });
+                            ui.end_row();
+
+                            ui.label(tr("服务端分发包:"));
+                            ui.horizontal(|ui| {
+                                ui.add(
+                                    egui::TextEdit::singleline(&mut self.config.server_pack_dir)
+                                        .hint_text(tr("留空不生成"))
+                                        .desired_width(200.0),
+                                )
+                                .on_hover_text(tr("任务完成后生成服务端语言数据包、资源包 zip 与强制加载资源包的 server.properties 片段"));
+                                if ui.button(tr("📂 选择")).clicked()
+                                    && let Some(path) = rfd::FileDialog::new().pick_folder()
+                                {
+                                    self.config.server_pack_dir = path.display().to_string();
+                                }
+                                ui.add(
+                                    egui::TextEdit::singleline(&mut self.config.server_pack_url)
+                                        .hint_text("https://.../mc_translator_zh_cn.zip")
+                                        .desired_width(200.0),
+                                )
+                                .on_hover_text(tr("资源包上传后的直链下载地址，写入 server.properties 的 resource-pack"));
+                            });
+                            ui.end_row();
+
+                            ui.label(tr("结束后执行:"));
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.config.post_run_command)
+                                    .hint_text("xcopy /E /Y \"%MCT_OUTPUT%\" ...")
+                                    .desired_width(360.0),
+                            )
+                            .on_hover_text(tr("任务结束后执行的命令，可读取 MCT_STATUS、MCT_OUTPUT、MCT_TRANSLATED_ENTRIES 等环境变量，输出写入日志"));
+                            ui.end_row();
+
+                            ui.label(tr("游戏目录:"));
+                            ui.horizontal(|ui| {
+                                ui.add(
+                                    egui::TextEdit::singleline(&mut self.config.game_dir)
+                                        .hint_text(".minecraft")
+                                        .desired_width(260.0),
+                                )
+                                .on_hover_text(tr("可选择 .minecraft、resourcepacks 或 Prism / CurseForge 的实例目录"));
+                                if ui.button(tr("📂 选择")).clicked()
+                                    && let Some(path) = rfd::FileDialog::new().pick_folder()
+                                {
+                                    self.config.game_dir = path.display().to_string();
+                                }
+                                ui.checkbox(&mut self.config.enable_pack_in_options, tr("写入 options.txt 启用"))
+                                    .on_hover_text(tr("游戏运行时会覆盖 options.txt，请先关闭游戏"));
+                                let can_install = !self.is_processing && !self.config.game_dir.trim().is_empty();
+                                if ui
+                                    .add_enabled(can_install, egui::Button::new(tr("🎮 安装到游戏")))
+                                    .on_hover_text(tr("把输出目录打包为资源包放入游戏的 resourcepacks 文件夹"))
+                                    .clicked()
+                                {
+                                    self.install_to_game();
+                                }
+                            });
+                            ui.end_row();
+
+                            ui.label(tr("条目顺序:"));
+                            ui.horizontal(|ui| {
+                                let order_name = |order: OutputOrder| match order {
+                                    OutputOrder::Source => tr("与源文件一致"),
+                                    OutputOrder::Alphabetical => tr("按字母排序"),
+                                    OutputOrder::Grouped => tr("按键前缀分组"),
+                                };
+                                egui::ComboBox::from_id_salt("output_order")
+                                    .selected_text(order_name(self.config.output_order))
+                                    .show_ui(ui, |ui| {
+                                        for order in [OutputOrder::Source, OutputOrder::Alphabetical, OutputOrder::Grouped] {
+                                            ui.selectable_value(&mut self.config.output_order, order, order_name(order));
+                                        }
+                                    })
+                                    .response
+                                    .on_hover_text(tr("按键前缀分组时，.lang 文件的各组之间以空行分隔"));
+                            });
+                            ui.end_row();
+
+                            ui.label(tr("颜色代码:"));
+                            ui.horizontal(|ui| {
+                                let mode_name = |mode: ColorCodeMode| match mode {
+                                    ColorCodeMode::Keep => tr("保持原样"),
+                                    ColorCodeMode::Section => tr("&a 转为 §a"),
+                                    ColorCodeMode::Ampersand => tr("§a 转为 &a"),
+                                    ColorCodeMode::Strip => tr("全部移除"),
+                                };
+                                egui::ComboBox::from_id_salt("color_codes")
+                                    .selected_text(mode_name(self.config.color_codes))
+                                    .show_ui(ui, |ui| {
+                                        for mode in [ColorCodeMode::Keep, ColorCodeMode::Section, ColorCodeMode::Ampersand, ColorCodeMode::Strip] {
+                                            ui.selectable_value(&mut self.config.color_codes, mode, mode_name(mode));
+                                        }
+                                    })
+                                    .response
+                                    .on_hover_text(tr("写出语言文件前统一颜色代码，便于把插件文本移入资源包或清理杂乱的源文件"));
+                            });
+                            ui.end_row();
+
+                            ui.label(tr("译文标点:"));
+                            ui.horizontal(|ui| {
+                                let mode_name = |mode: PunctuationMode| match mode {
+                                    PunctuationMode::Keep => tr("保持原样"),
+                                    PunctuationMode::FullWidth => tr("统一为全角"),
+                                    PunctuationMode::HalfWidth => tr("统一为半角"),
+                                };
+                                let current = self.config.punctuation_for(&self.config.target_lang);
+                                let mut selected = current;
+                                egui::ComboBox::from_id_salt("punctuation")
+                                    .selected_text(mode_name(current))
+                                    .show_ui(ui, |ui| {
+                                        for mode in [PunctuationMode::Keep, PunctuationMode::FullWidth, PunctuationMode::HalfWidth] {
+                                            ui.selectable_value(&mut selected, mode, mode_name(mode));
+                                        }
+                                    })
+                                    .response
+                                    .on_hover_text(trf("按目标语言分别设置，当前为 {}；写出前先做 Unicode 规范化，再转换紧跟中日文字的标点，... 转为 ……", &[&self.config.target_lang]));
+                                if selected != current {
+                                    let lang = self.config.target_lang.to_lowercase();
+                                    self.config.punctuation.retain(|code, _| !code.eq_ignore_ascii_case(&lang));
+                                    if selected != PunctuationMode::Keep {
+                                        self.config.punctuation.insert(lang, selected);
+                                    }
+                                }
+                            });
+                            ui.end_row();
+
+                            ui.label(tr("空值条目:"));
+                            ui.horizontal(|ui| {
+                                let mode_name = |mode: EmptyValueMode| match mode {
+                                    EmptyValueMode::Keep => tr("保持原样"),
+                                    EmptyValueMode::Drop => tr("不写入"),
+                                    EmptyValueMode::KeyName => tr("以键名作为值"),
+                                };
+                                egui::ComboBox::from_id_salt("empty_values")
+                                    .selected_text(mode_name(self.config.empty_values))
+                                    .show_ui(ui, |ui| {
+                                        for mode in [EmptyValueMode::Keep, EmptyValueMode::Drop, EmptyValueMode::KeyName] {
+                                            ui.selectable_value(&mut self.config.empty_values, mode, mode_name(mode));
+                                        }
+                                    })
+                                    .response
+                                    .on_hover_text(tr("值为空或只含空白的条目不会发给模型；部分模组有意使用空字符串，不写入时游戏会显示原文"));
+                            });
+                            ui.end_row();
+
+                            ui.label(tr("失败条目:"));
+                            ui.horizontal(|ui| {
+                                let mode_name = |mode: FailedEntryMode| match mode {
+                                    FailedEntryMode::KeepSource => tr("以原文写入"),
+                                    FailedEntryMode::Drop => tr("不写入"),
+                                };
+                                egui::ComboBox::from_id_salt("failed_entries")
+                                    .selected_text(mode_name(self.config.failed_entries))
+                                    .show_ui(ui, |ui| {
+                                        for mode in [FailedEntryMode::KeepSource, FailedEntryMode::Drop] {
+                                            ui.selectable_value(&mut self.config.failed_entries, mode, mode_name(mode));
+                                        }
+                                    })
+                                    .response
+                                    .on_hover_text(tr("批次翻译失败的条目会在运行汇总的“未翻译条目”中列出，“更新翻译”时重新翻译"));
+                            });
+                            ui.end_row();
+
+                            ui.label(tr("文件名大小写:"));
+                            ui.horizontal(|ui| {
+                                let casing_name = |casing: FilenameCasing| match casing {
+                                    FilenameCasing::Auto => tr("自动"),
+                                    FilenameCasing::Lower => "zh_cn",
+                                    FilenameCasing::UpperRegion => "zh_CN",
+                                };
+                                egui::ComboBox::from_id_salt("filename_casing")
+                                    .selected_text(casing_name(self.config.filename_casing))
+                                    .show_ui(ui, |ui| {
+                                        for casing in [FilenameCasing::Auto, FilenameCasing::Lower, FilenameCasing::UpperRegion] {
+                                            ui.selectable_value(&mut self.config.filename_casing, casing, casing_name(casing));
+                                        }
+                                    })
+                                    .response
+                                    .on_hover_text(tr("自动：按模组元数据推断的整合包版本决定，无法推断时 .json (1.13+) 一律小写，.lang 沿用源文件的大小写"));
+                            });
+                            ui.end_row();
+
+                            ui.label(tr("Origins:"));
+                            ui.horizontal(|ui| {
+                                let mode_name = |mode: OriginsMode| match mode {
+                                    OriginsMode::Off => tr("不处理"),
+                                    OriginsMode::Copy => tr("翻译定义副本 (datapack/)"),
+                                    OriginsMode::Lang => tr("生成语言键覆盖"),
+                                };
+                                egui::ComboBox::from_id_salt("origins_mode")
+                                    .selected_text(mode_name(self.config.origins_mode))
+                                    .show_ui(ui, |ui| {
+                                        for mode in [OriginsMode::Off, OriginsMode::Copy, OriginsMode::Lang] {
+                                            ui.selectable_value(&mut self.config.origins_mode, mode, mode_name(mode));
+                                        }
+                                    })
+                                    .response
+                                    .on_hover_text(tr("翻译 data/*/origins 与 data/*/powers 中的名称和描述"));
+                            });
+                            ui.end_row();
+
+                            ui.label(tr("任务文件:"));
+                            ui.horizontal(|ui| {
+                                let mode_name = |mode: QuestMode| match mode {
+                                    QuestMode::InPlace => tr("直接替换文本"),
+                                    QuestMode::LangKeys => tr("改为语言键"),
+                                };
+                                egui::ComboBox::from_id_salt("quest_mode")
+                                    .selected_text(mode_name(self.config.quest_mode))
+                                    .show_ui(ui, |ui| {
+                                        for mode in [QuestMode::InPlace, QuestMode::LangKeys] {
+                                            ui.selectable_value(&mut self.config.quest_mode, mode, mode_name(mode));
+                                        }
+                                    })
+                                    .response
+                                    .on_hover_text(tr("改为语言键时，任务文件中的文本替换为 {键}，原文与译文写入 ftbquests 语言文件，任务文件对各语言通用；1.21+ 的 lang/*.snbt 不受影响"));
+                            });
+                            ui.end_row();
+
+                            ui.label(tr("扫描:"));
+                            ui.horizontal(|ui| {
+                                ui.label(tr("最大深度"));
+                                ui.add(egui::DragValue::new(&mut self.config.walk_max_depth).range(0..=64))
+                                    .on_hover_text(tr("0 为不限制"));
+                                ui.checkbox(&mut self.config.follow_symlinks, tr("跟随符号链接"))
+                                    .on_hover_text(tr("进入符号链接指向的目录，如链接到其他位置的 mods/"));
+                                ui.checkbox(&mut self.config.skip_hidden_dirs, tr("跳过隐藏目录"))
+                                    .on_hover_text(tr("跳过以 . 开头的目录，启动器实例中的 .minecraft 除外"));
+                            });
+                            ui.end_row();
+
+                            ui.label(tr("单文件上限:"));
+                            ui.horizontal(|ui| {
+                                ui.add(egui::DragValue::new(&mut self.config.max_file_size_kb).suffix(" KB").speed(64))
+                                    .on_hover_text(tr("超出则跳过并警告，0 为不限制；JAR 按其中的单个文件计算"));
+                                ui.add(egui::DragValue::new(&mut self.config.max_file_entries).suffix(tr(" 条")).speed(100))
+                                    .on_hover_text(tr("待翻译条目超出则跳过并警告，0 为不限制"));
+                                ui.label(tr("白名单:"));
+                                let response = ui.add(
+                                    egui::TextEdit::singleline(&mut self.limit_whitelist_input)
+                                        .hint_text("chapters/big.snbt, create")
+                                        .desired_width(160.0),
+                                );
+                                if response.changed() {
+                                    self.config.limit_whitelist = self
+                                        .limit_whitelist_input
+                                        .split(',')
+                                        .map(|s| s.trim().to_string())
+                                        .filter(|s| !s.is_empty())
+                                        .collect();
+                                }
+                                response.on_hover_text(tr("路径包含其中任一项的文件不受上限限制，逗号分隔"));
+                            });
+                            ui.end_row();
+                        });
+                    ui.add_space(10.0);
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut self.config.skip_existing, tr("跳过已翻译的文件"));
+                        ui.separator();
+                        ui.checkbox(&mut self.config.reuse_builtin, tr("沿用内置汉化"))
+                            .on_hover_text(tr("全量翻译时也沿用模组 JAR 自带的目标语言译文，只翻译缺失的条目"));
+                        ui.separator();
+                        ui.checkbox(&mut self.config.retranslate_identical, tr("重译未翻译占位"))
+                            .on_hover_text(tr("沿用内置或社区汉化时，与英文原文相同的条目视为未翻译，仍交给模型翻译"));
+                        ui.separator();
+                        ui.checkbox(&mut self.config.skip_quest, tr("跳过 snbt"))
+                            .on_hover_text(tr("勾选后将不再检查config/ftbquests，只检查kubejs下的本地化文件"));
+                        ui.separator();
+                        ui.checkbox(&mut self.config.patch_jars, tr("修补 JAR"))
+                            .on_hover_text(tr("⚠ 将译文直接写入模组 JAR 的副本，输出到 mods_patched/。签名会失效，更新模组后需重新生成，推荐优先使用资源包"));
+                        ui.separator();
+                        ui.checkbox(&mut self.config.merge_mod_versions, tr("合并模组多版本"))
+                            .on_hover_text(tr("同一模组出现在多个 JAR 中时，合并各版本的条目后只翻译一次，输出对各版本都适用"));
+                        ui.separator();
+                        ui.checkbox(&mut self.config.translate_structures, tr("翻译结构"))
+                            .on_hover_text(tr("翻译结构文件 (.nbt) 中的书页与告示牌文字，输出到 datapack/ 数据包"));
+                        ui.separator();
+                        ui.checkbox(&mut self.config.translate_description_data, tr("翻译描述数据"))
+                            .on_hover_text(tr("翻译附魔描述、物品描述等模组放在 data/ 下的描述文件 (含 JAR 内)，输出到 datapack/ 数据包"));
+                        ui.separator();
+                        ui.checkbox(&mut self.config.translate_config_comments, tr("翻译配置注释"))
+                            .on_hover_text(tr("将 config 目录下 .toml/.hjson/.json5 的注释翻译后输出到 config_docs/，用于整合包文档，不修改原配置"));
+                        ui.separator();
+                        if ui
+                            .button(trf("📋 任务队列 ({})", &[&self.config.job_queue.len()]))
+                            .on_hover_text(tr("添加多个整合包或目录，依次或并行处理"))
+                            .clicked()
+                        {
+                            self.show_queue_window = true;
+                        }
+                        if ui
+                            .add_enabled(!self.is_processing, egui::Button::new(tr("✔ 校验输出")))
+                            .on_hover_text(tr("检查输出目录中的语言文件能否被游戏正常加载，并自动修复尾逗号、控制字符等问题"))
+                            .clicked()
+                        {
+                            let output = self.config.output_path.clone();
+                            thread::spawn(move || {
+                                validate::validate_output(&output);
+                            });
+                        }
+                        if ui
+                            .button(tr("🔀 合并译文"))
+                            .on_hover_text(tr("将多个译文目录或资源包合并为一个"))
+                            .clicked()
+                        {
+                            if self.merge_output.is_empty() {
+                                self.merge_output = format!("{}_merged", self.config.output_path.trim_end_matches(['/', '\\']));
+                            }
+                            self.show_merge_window = true;
+                        }
+                        if ui
+                            .button(tr("🗄 原文快照"))
+                            .on_hover_text(tr("浏览和清理增量更新用于检测原文改动的快照"))
+                            .clicked()
+                        {
+                            self.raw_snapshots = raw_content::list(Path::new(&self.config.output_path));
+                            self.show_raw_content_window = true;
+                        }
+                        if ui
+                            .button(tr("📊 字符串分析"))
+                            .on_hover_text(tr("统计整合包中出现最多和最长的原文，可在正式翻译前把高频术语加入提示词"))
+                            .clicked()
+                        {
+                            self.show_analysis_window = true;
+                        }
+                        if ui
+                            .button(tr("🔄 检查模组更新"))
+                            .on_hover_text(tr("列出自上次翻译以来版本或 JAR 内容有变化的模组，可一键只更新这些模组的翻译"))
+                            .clicked()
+                        {
+                            self.show_mod_updates_window = true;
+                            if !self.is_checking_mod_updates {
+                                self.start_mod_update_check();
+                            }
+                        }
+                    });
+                }
+                MainTab::Progress => {
+                    self.render_run_summaries(ui);
+                    if self.is_processing {
+                        self.render_concurrency(ui);
+                    }
+                    if self.active_batches.is_empty() {
+                        ui.label(egui::RichText::new(tr("当前没有进行中的批次")).weak());
+                    } else {
+                        ui.label(trf("批次进度 ({})", &[&self.active_batches.len()]));
+                        egui::ScrollArea::vertical().id_salt("batch_progress").show(ui, |ui| {
+                            for (label, (received, total)) in &self.active_batches {
+                                let fraction = if *total == 0 { 0.0 } else { *received as f32 / *total as f32 };
+                                ui.add(
+                                    egui::ProgressBar::new(fraction)
+                                        .desired_height(12.0)
+                                        .text(format!("{} {}/{}", label, received, total)),
+                                );
+                            }
+                        });
+                    }
+                    ui.separator();
+                    self.render_file_table(ui);
+                }
+                MainTab::Log => {
+                    ui.horizontal(|ui| {
+                        ui.label(trf("日志 ({})", &[&self.logs.len()]));
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui.small_button(tr("复制全部")).clicked() {
+                                ctx.copy_text(join_log_lines(&self.logs, false));
+                            }
+                        });
+                    });
+
+                    ui.push_id("log_area", |ui| {
+                        ui.style_mut().spacing.item_spacing.y = 0.0;
+                        // 每行固定高度，只为可见行构建 LayoutJob，长消息截断并在悬停时显示全文
+                        let font_id = egui::FontId::monospace(13.0);
+                        let row_height = ui.fonts_mut(|f| f.row_height(&font_id)) + 4.0;
+                        egui::ScrollArea::vertical()
+                            .stick_to_bottom(true)
+                            .auto_shrink([false, true])
+                            .show_rows(ui, row_height, self.logs.len(), |ui, row_range| {
+                                for i in row_range {
+                                    let entry = &self.logs[i];
+                                    let visuals = ui.visuals();
+                                    let text_color = match entry.level {
+                                        LogLevel::Info => visuals.text_color(),
+                                        LogLevel::Success => egui::Color32::from_rgb(0, 200, 0),
+                                        LogLevel::Warn => visuals.warn_fg_color,
+                                        LogLevel::Error => visuals.error_fg_color,
+                                    };
+
+                                    let bg_color = if i % 2 == 1 {
+                                        visuals.faint_bg_color
+                                    } else {
+                                        egui::Color32::TRANSPARENT
+                                    };
+
+                                    let full_text = format_log_line(entry);
+
+                                    let job = egui::text::LayoutJob::single_section(
+                                        full_text.clone(),
+                                        egui::TextFormat {
+                                            font_id: font_id.clone(),
+                                            color: text_color,
+                                            ..Default::default()
+                                        },
+                                    );
+
+                                    egui::Frame::new()
+                                        .fill(bg_color)
+                                        .inner_margin(2.0)
+                                        .show(ui, |ui| {
+                                            ui.set_min_width(ui.available_width());
+                                            ui.add(egui::Label::new(job).truncate().sense(egui::Sense::click()))
+                                                .context_menu(|ui| {
+                                                    if ui.button(tr("复制此行")).clicked() {
+                                                        ui.ctx().copy_text(full_text);
+                                                        ui.close();
+                                                    }
+                                                    if ui.button(tr("复制所有错误")).clicked() {
+                                                        ui.ctx().copy_text(join_log_lines(&self.logs, true));
+                                                        ui.close();
+                                                    }
+                                                });
+                                        });
+                                }
+                            });
+                    });
+                }
+                MainTab::Review => self.render_review_tab(ui),
+                MainTab::Stats => self.render_stats_tab(ui),
+            }
         });
 
-        if self.is_processing {
-            ctx.request_repaint();
+        // 后台消息会主动唤醒界面，这里只需低频刷新运行时长等显示；spinner 会逐帧重绘，因此不再使用
+        if self.is_processing || self.is_comparing {
+            ctx.request_repaint_after(Duration::from_secs(1));
+        }
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        // 通知正在运行的任务尽快退出，运行时随 MyApp 一同销毁
+        if let Some(token) = &self.cancellation_token {
+            token.cancel();
+        }
+        // 窗口大小和位置只在退出时写入，拖动窗口不算作未保存的修改
+        if self.window_size.is_some() {
+            self.config.window_size = self.window_size;
+        }
+        if self.window_pos.is_some() {
+            self.config.window_pos = self.window_pos;
         }
+        self.save_config();
     }
 }
@@ -0,0 +1,28 @@
+use crate::logic::processor::RunStatus;
+use super::i18n::tr;
+use notify_rust::Notification;
+
+#[cfg(target_os = "macos")]
+const SOUND_NAME: &str = "Glass";
+#[cfg(target_os = "windows")]
+const SOUND_NAME: &str = "Default";
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const SOUND_NAME: &str = "complete";
+
+/// 任务结束时发送系统通知，失败时只写入 env_logger，不打扰界面
+pub fn notify_run_finished(status: RunStatus, with_sound: bool) {
+    let body = match status {
+        RunStatus::Completed => tr("翻译任务已完成"),
+        RunStatus::Cancelled => tr("翻译任务已取消"),
+        RunStatus::Failed => tr("翻译任务失败，请查看日志"),
+    };
+
+    let mut notification = Notification::new();
+    notification.summary("MC Translator").body(body).appname("MC Translator");
+    if with_sound {
+        notification.sound_name(SOUND_NAME);
+    }
+    if let Err(e) = notification.show() {
+        log::warn!("发送系统通知失败: {}", e);
+    }
+}
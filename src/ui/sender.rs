@@ -0,0 +1,33 @@
+use crate::message::{AppMsg, MessageSink};
+use crossbeam_channel::Sender;
+
+/// 发送消息后唤醒界面，界面无需在任务运行期间持续重绘
+#[derive(Clone)]
+pub struct UiSender {
+    sender: Sender<AppMsg>,
+    ctx: egui::Context,
+}
+
+impl UiSender {
+    pub fn new(sender: Sender<AppMsg>, ctx: egui::Context) -> Self {
+        Self { sender, ctx }
+    }
+
+    /// 返回 false 表示界面已关闭
+    pub fn send(&self, msg: AppMsg) -> bool {
+        if let AppMsg::Log(entry) = &msg {
+            crate::crash::record_log(format!("{} {:?} {}", entry.time, entry.level, entry.message));
+        }
+        if self.sender.send(msg).is_err() {
+            return false;
+        }
+        self.ctx.request_repaint();
+        true
+    }
+}
+
+impl MessageSink for UiSender {
+    fn send(&self, msg: AppMsg) -> bool {
+        UiSender::send(self, msg)
+    }
+}